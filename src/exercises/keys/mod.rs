@@ -1,9 +1,11 @@
 pub mod derivation;
 pub mod commitment;
 pub mod channel_key_manager;
+pub mod scheme;
 
 // Re-export commonly used items
 pub use derivation::{new_keys_manager};
+pub use scheme::DerivationScheme;
 pub use commitment::{
     derive_public_key, 
     derive_private_key,
@@ -3,23 +3,25 @@ use bitcoin::secp256k1::PublicKey;
 use bitcoin::script::{Builder, ScriptBuf};
 use bitcoin::blockdata::opcodes::all as opcodes;
 use bitcoin::hashes::{Hash, ripemd160, hash160};
-use bitcoin::hashes::ripemd160::Hash as Ripemd160;
 use bitcoin::hashes::hash160::Hash as Hash160;
 use bitcoin::{PubkeyHash, WPubkeyHash};
 use hex;
 
+use crate::internal::script_utils::check_witness_script_standard;
+use crate::types::{payment_hash160, ChannelType, PaymentHash};
+
 
 /// Exercise 22: Create offered HTLC script
 pub fn create_offered_htlc_script(
     revocation_pubkey: &PublicKey,
     local_htlcpubkey: &PublicKey,
     remote_htlcpubkey: &PublicKey,
-    payment_hash: &[u8; 32],
+    payment_hash: &PaymentHash,
 ) -> ScriptBuf {
 
     unimplemented!();
 
-    // Hash the payment hash with RIPEMD160
+    // Hash the payment hash with RIPEMD160 (see `payment_hash160`)
 
     // Hash the revocation public key with PubkeyHash
 
@@ -52,18 +54,41 @@ pub fn create_offered_htlc_script(
     // OP_ENDIF
 }
 
+/// nLockTime values from here on are UNIX timestamps, not block heights
+pub const LOCKTIME_THRESHOLD: u32 = 500_000_000;
+
+/// helper (Not an exercise)
+/// Panics if `cltv_expiry` is not a block height. HTLC expiries are always
+/// heights; a value at or above 500,000,000 would be read as a timestamp.
+pub fn check_cltv_expiry(cltv_expiry: u32) {
+    assert!(
+        cltv_expiry < LOCKTIME_THRESHOLD,
+        "cltv_expiry {} is a timestamp, not a block height",
+        cltv_expiry
+    );
+}
+
+/// helper (Not an exercise)
+/// How `<cltv_expiry>` appears in the received HTLC script: a minimal
+/// CScriptNum push, so 1-16 become OP_1..OP_16 and 500 becomes `02 f401`.
+pub fn cltv_expiry_push(cltv_expiry: u32) -> ScriptBuf {
+    Builder::new().push_int(cltv_expiry as i64).into_script()
+}
+
 /// Exercise 25: Create received HTLC script
 pub fn create_received_htlc_script(
     revocation_pubkey: &PublicKey,
     local_htlcpubkey: &PublicKey,
     remote_htlcpubkey: &PublicKey,
-    payment_hash: &[u8; 32],
+    payment_hash: &PaymentHash,
     cltv_expiry: u32,
 ) -> ScriptBuf {
+    // Expiries must be block heights (provided)
+    check_cltv_expiry(cltv_expiry);
 
     unimplemented!();
 
-    // Hash the payment hash with RIPEMD160
+    // Hash the payment hash with RIPEMD160 (see `payment_hash160`)
 
     // Hash the revocation public key with PubkeyHash
 
@@ -98,4 +123,72 @@ pub fn create_received_htlc_script(
     //     OP_ENDIF
     // OP_ENDIF
 
-}
\ No newline at end of file
+}
+
+/// helper (Not an exercise)
+/// Add the anchors `1 OP_CHECKSEQUENCEVERIFY OP_DROP` before the final
+/// OP_ENDIF of an offered or received HTLC script, so every non-revocation
+/// spend has to wait one block (and cannot be pinned in the mempool).
+pub fn add_anchors_csv(htlc_script: &ScriptBuf) -> ScriptBuf {
+    let bytes = htlc_script.as_bytes();
+    assert_eq!(
+        bytes.last(),
+        Some(&opcodes::OP_ENDIF.to_u8()),
+        "HTLC script must end with OP_ENDIF"
+    );
+
+    let mut anchors_script = ScriptBuf::from_bytes(bytes[..bytes.len() - 1].to_vec());
+    anchors_script.push_opcode(opcodes::OP_PUSHNUM_1);
+    anchors_script.push_opcode(opcodes::OP_CSV);
+    anchors_script.push_opcode(opcodes::OP_DROP);
+    anchors_script.push_opcode(opcodes::OP_ENDIF);
+    anchors_script
+}
+
+/// helper (Not an exercise)
+/// Offered HTLC script for the given channel type
+pub fn create_offered_htlc_script_for_channel_type(
+    revocation_pubkey: &PublicKey,
+    local_htlcpubkey: &PublicKey,
+    remote_htlcpubkey: &PublicKey,
+    payment_hash: &PaymentHash,
+    channel_type: ChannelType,
+) -> ScriptBuf {
+    let script = create_offered_htlc_script(
+        revocation_pubkey,
+        local_htlcpubkey,
+        remote_htlcpubkey,
+        payment_hash,
+    );
+    let script = match channel_type {
+        ChannelType::StaticRemoteKey => script,
+        ChannelType::AnchorsZeroFeeHtlcTx => add_anchors_csv(&script),
+    };
+    check_witness_script_standard(&script).expect("HTLC script exceeds P2WSH standardness limit");
+    script
+}
+
+/// helper (Not an exercise)
+/// Received HTLC script for the given channel type
+pub fn create_received_htlc_script_for_channel_type(
+    revocation_pubkey: &PublicKey,
+    local_htlcpubkey: &PublicKey,
+    remote_htlcpubkey: &PublicKey,
+    payment_hash: &PaymentHash,
+    cltv_expiry: u32,
+    channel_type: ChannelType,
+) -> ScriptBuf {
+    let script = create_received_htlc_script(
+        revocation_pubkey,
+        local_htlcpubkey,
+        remote_htlcpubkey,
+        payment_hash,
+        cltv_expiry,
+    );
+    let script = match channel_type {
+        ChannelType::StaticRemoteKey => script,
+        ChannelType::AnchorsZeroFeeHtlcTx => add_anchors_csv(&script),
+    };
+    check_witness_script_standard(&script).expect("HTLC script exceeds P2WSH standardness limit");
+    script
+}
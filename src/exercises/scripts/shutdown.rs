@@ -0,0 +1,37 @@
+use bitcoin::script::Script;
+use bitcoin::WitnessVersion;
+
+/// Why a `shutdown` message's `scriptpubkey` was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShutdownScriptError {
+    /// A segwit v1+ output (e.g. P2TR), but `option_shutdown_anysegwit` was not negotiated
+    AnySegwitNotNegotiated,
+    /// Not one of the templates BOLT 2 allows
+    NotAllowed,
+}
+
+/// helper (Not an exercise)
+/// Check a closing output script against the BOLT 2 `shutdown` rules.
+///
+/// P2PKH, P2SH, P2WPKH and P2WSH are always allowed. Any other witness
+/// program (version 1 to 16, 2 to 40 bytes) is only allowed when both
+/// peers negotiated `option_shutdown_anysegwit`.
+pub fn validate_shutdown_script(
+    script: &Script,
+    anysegwit: bool,
+) -> Result<(), ShutdownScriptError> {
+    if script.is_p2pkh() || script.is_p2sh() || script.is_p2wpkh() || script.is_p2wsh() {
+        return Ok(());
+    }
+
+    match script.witness_version() {
+        Some(version) if version != WitnessVersion::V0 && script.is_witness_program() => {
+            if anysegwit {
+                Ok(())
+            } else {
+                Err(ShutdownScriptError::AnySegwitNotNegotiated)
+            }
+        }
+        _ => Err(ShutdownScriptError::NotAllowed),
+    }
+}
@@ -0,0 +1,25 @@
+use bitcoin::script::ScriptBuf;
+
+/// Why a sweep or justice transaction could not be built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimError {
+    /// There were no outputs to sweep
+    NoOutputs,
+    /// The claimed amount cannot cover the fee plus a non-dust output
+    InsufficientFunds { input_sat: u64, required_sat: u64 },
+}
+
+/// helper (Not an exercise)
+/// What is left for the destination once the fee is paid, as long as that
+/// is still above the destination script's dust limit
+pub fn value_after_fee(
+    input_sat: u64,
+    fee: u64,
+    destination_script: &ScriptBuf,
+) -> Result<u64, ClaimError> {
+    let required_sat = fee + destination_script.minimal_non_dust().to_sat();
+    if input_sat < required_sat {
+        return Err(ClaimError::InsufficientFunds { input_sat, required_sat });
+    }
+    Ok(input_sat - fee)
+}
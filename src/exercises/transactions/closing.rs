@@ -0,0 +1,57 @@
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
+
+/// helper (Not an exercise)
+/// Build the BOLT 3 closing transaction for a mutual close.
+///
+/// Each side is paid to the script it sent in `shutdown` (see
+/// `validate_shutdown_script`). The funder pays `closing_fee_sat`, and an
+/// output left below `dust_limit_satoshis` is dropped, its value going to
+/// fees. Outputs are in BIP69 order and, unlike a commitment, nothing is
+/// encoded in the locktime or sequence.
+#[allow(clippy::too_many_arguments)]
+pub fn create_closing_transaction(
+    funding_outpoint: OutPoint,
+    local_amount_sat: u64,
+    remote_amount_sat: u64,
+    local_shutdown_script: &ScriptBuf,
+    remote_shutdown_script: &ScriptBuf,
+    closing_fee_sat: u64,
+    local_is_funder: bool,
+    dust_limit_satoshis: u64,
+) -> Transaction {
+    let (local_amount_sat, remote_amount_sat) = if local_is_funder {
+        (local_amount_sat.saturating_sub(closing_fee_sat), remote_amount_sat)
+    } else {
+        (local_amount_sat, remote_amount_sat.saturating_sub(closing_fee_sat))
+    };
+
+    let mut outputs: Vec<TxOut> = [
+        (local_amount_sat, local_shutdown_script),
+        (remote_amount_sat, remote_shutdown_script),
+    ]
+    .into_iter()
+    .filter(|(amount, _)| *amount >= dust_limit_satoshis)
+    .map(|(amount, script)| TxOut {
+        value: Amount::from_sat(amount),
+        script_pubkey: script.clone(),
+    })
+    .collect();
+
+    // BIP69: by amount, then by scriptPubKey
+    outputs.sort_by(|a, b| a.value.cmp(&b.value).then(a.script_pubkey.cmp(&b.script_pubkey)));
+
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: funding_outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: outputs,
+    }
+}
@@ -9,8 +9,125 @@ use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
 
 use crate::scripts::{create_offered_htlc_script, create_received_htlc_script};
 use crate::scripts::{create_to_local_script, create_to_remote_script};
-use crate::transactions::fees::calculate_commitment_tx_fee;
-use crate::types::{ChannelKeyManager, CommitmentKeys, OutputWithMetadata, HTLCOutput};
+use crate::transactions::fees::{calculate_commitment_tx_fee, htlc_trimming};
+use crate::transactions::sanity::check_commitment_tx_encoding;
+use crate::types::{
+    ChannelKeyManager, ChannelPublicKeys, ChannelType, CommitmentKeys, OutputWithMetadata, HTLCOutput,
+};
+
+/// BOLT 2: `max_accepted_htlcs` must not exceed 483, so that a penalty
+/// transaction sweeping every HTLC output of a revoked commitment stays
+/// within the standard transaction size.
+pub const MAX_ACCEPTED_HTLCS: usize = 483;
+
+/// Why a set of HTLCs cannot go on a commitment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HtlcCountError {
+    TooManyOffered { count: usize, max: usize },
+    TooManyReceived { count: usize, max: usize },
+}
+
+/// helper (Not an exercise)
+/// Check neither direction carries more HTLCs than BOLT 2 allows. The
+/// counterparty chooses how many HTLCs it adds, so reject rather than panic.
+pub fn check_htlc_count(
+    offered_htlcs: &[HTLCOutput],
+    received_htlcs: &[HTLCOutput],
+) -> Result<(), HtlcCountError> {
+    if offered_htlcs.len() > MAX_ACCEPTED_HTLCS {
+        return Err(HtlcCountError::TooManyOffered {
+            count: offered_htlcs.len(),
+            max: MAX_ACCEPTED_HTLCS,
+        });
+    }
+    if received_htlcs.len() > MAX_ACCEPTED_HTLCS {
+        return Err(HtlcCountError::TooManyReceived {
+            count: received_htlcs.len(),
+            max: MAX_ACCEPTED_HTLCS,
+        });
+    }
+    Ok(())
+}
+
+/// The `to_self_delay` each side's to_local output is locked with.
+///
+/// In `open_channel`/`accept_channel` each node sets the delay the *other*
+/// node must wait, so our to_local uses the delay they chose and theirs uses ours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToSelfDelays {
+    /// Delay on our to_local (and our HTLC-timeout/success outputs)
+    pub local: u16,
+    /// Delay on the counterparty's to_local
+    pub remote: u16,
+}
+
+/// Why a proposed `to_self_delay` was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToSelfDelayError {
+    /// A zero delay would leave us no time to punish a revoked commitment
+    Zero,
+    /// The counterparty wants our funds locked longer than we accept
+    TooLarge { to_self_delay: u16, max_to_self_delay: u16 },
+}
+
+/// helper (Not an exercise)
+/// Check the delays exchanged while opening a channel: `ours` is the delay we
+/// ask the counterparty to wait, `theirs` is the delay they ask of us.
+pub fn validate_to_self_delay(
+    ours: u16,
+    theirs: u16,
+    max_to_self_delay: u16,
+) -> Result<ToSelfDelays, ToSelfDelayError> {
+    if ours == 0 {
+        return Err(ToSelfDelayError::Zero);
+    }
+    if theirs > max_to_self_delay {
+        return Err(ToSelfDelayError::TooLarge { to_self_delay: theirs, max_to_self_delay });
+    }
+
+    Ok(ToSelfDelays { local: theirs, remote: ours })
+}
+
+/// Why a relative locktime cannot be expressed in nSequence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelativeLocktimeError {
+    /// BIP68 only has 16 bits for the lock value
+    TooManyBlocks(u32),
+    /// More than 65535 * 512 seconds
+    TooManySeconds(u32),
+}
+
+/// BIP68 type flag: the lock value counts 512-second units instead of blocks
+const SEQUENCE_LOCKTIME_TYPE_FLAG: u32 = 1 << 22;
+
+/// BIP68 keeps the lock value in the low 16 bits
+const SEQUENCE_LOCKTIME_MASK: u32 = 0x0000ffff;
+
+/// helper (Not an exercise)
+/// nSequence for an input that must wait `blocks` blocks after its parent
+/// confirms (e.g. to satisfy `<to_self_delay> OP_CHECKSEQUENCEVERIFY`).
+///
+/// The disable flag (bit 31) and type flag (bit 22) are left clear, so the
+/// value is enforced by BIP68 and counts blocks.
+pub fn relative_locktime_sequence(blocks: u32) -> Result<Sequence, RelativeLocktimeError> {
+    if blocks > SEQUENCE_LOCKTIME_MASK {
+        return Err(RelativeLocktimeError::TooManyBlocks(blocks));
+    }
+    Ok(Sequence::from_consensus(blocks))
+}
+
+/// helper (Not an exercise)
+/// nSequence for a time-based relative locktime, rounded up to the next
+/// 512-second unit so the input never becomes valid early.
+pub fn relative_locktime_sequence_from_seconds(
+    seconds: u32,
+) -> Result<Sequence, RelativeLocktimeError> {
+    let units = seconds.div_ceil(512);
+    if units > SEQUENCE_LOCKTIME_MASK {
+        return Err(RelativeLocktimeError::TooManySeconds(seconds));
+    }
+    Ok(Sequence::from_consensus(SEQUENCE_LOCKTIME_TYPE_FLAG | units))
+}
 
 /// Exercise 16: Calculate obscure factor for commitment number
 pub fn get_commitment_transaction_number_obscure_factor(
@@ -48,6 +165,33 @@ pub fn set_obscured_commitment_number(
     // Encode upper 24 bits in sequence
 }
 
+/// helper (Not an exercise)
+/// Reverse `set_obscured_commitment_number`: read the commitment number back
+/// from a commitment transaction's nLockTime and (first input's) nSequence.
+///
+/// Each carries 24 bits of the obscured number under a fixed prefix (0x20
+/// and 0x80); XORing with the obscure factor undoes the obscuring. Returns
+/// `None` if either prefix is missing, i.e. this is not a commitment.
+pub fn recover_commitment_number(
+    lock_time: LockTime,
+    sequence: Sequence,
+    initiator_payment_basepoint: &PublicKey,
+    receiver_payment_basepoint: &PublicKey,
+) -> Option<u64> {
+    let lock_time = lock_time.to_consensus_u32();
+    let sequence = sequence.to_consensus_u32();
+    if lock_time >> 24 != 0x20 || sequence >> 24 != 0x80 {
+        return None;
+    }
+
+    let obscured = ((sequence as u64 & 0xffffff) << 24) | (lock_time as u64 & 0xffffff);
+    let obscure_factor = get_commitment_transaction_number_obscure_factor(
+        initiator_payment_basepoint,
+        receiver_payment_basepoint,
+    );
+    Some(obscured ^ obscure_factor)
+}
+
 /// Exercise 18: Create commitment transaction outputs
 pub fn create_commitment_transaction_outputs(
     to_local_value: u64,
@@ -114,6 +258,10 @@ pub fn create_commitment_transaction(
     offered_htlcs: &[HTLCOutput],
     received_htlcs: &[HTLCOutput],
 ) -> Transaction {
+    // Enforce the BOLT 2 limit of 483 HTLCs per direction (provided)
+    if let Err(e) = check_htlc_count(offered_htlcs, received_htlcs) {
+        panic!("Refusing to build commitment transaction: {:?}", e);
+    }
 
     unimplemented!();
 
@@ -140,6 +288,177 @@ pub fn create_commitment_transaction(
     // Return transaction
 }
 
+/// helper (Not an exercise)
+/// Like `create_commitment_transaction`, but for a channel either side may
+/// have opened. BOLT 3 takes the commitment fee from the funder's output on
+/// *both* parties' commitments, and obscures the commitment number with the
+/// funder's payment basepoint first.
+///
+/// With `local_is_funder` set this is exactly `create_commitment_transaction`.
+/// Otherwise the fee comes out of `to_remote_value`, and a to_remote left
+/// below `dust_limit_satoshis` after the fee is trimmed. Our to_local is
+/// compared with the dust limit as it is, since it pays no fee.
+#[allow(clippy::too_many_arguments)]
+pub fn create_commitment_transaction_with_funder(
+    funding_outpoint: OutPoint,
+    to_local_value: u64,
+    to_remote_value: u64,
+    commitment_keys: &CommitmentKeys,
+    local_payment_basepoint: &PublicKey,
+    remote_payment_basepoint: &PublicKey,
+    commitment_number: u64,
+    to_self_delay: u16,
+    dust_limit_satoshis: u64,
+    feerate_per_kw: u64,
+    offered_htlcs: &[HTLCOutput],
+    received_htlcs: &[HTLCOutput],
+    local_is_funder: bool,
+) -> Transaction {
+    if local_is_funder {
+        return create_commitment_transaction(
+            funding_outpoint,
+            to_local_value,
+            to_remote_value,
+            commitment_keys,
+            local_payment_basepoint,
+            remote_payment_basepoint,
+            commitment_number,
+            to_self_delay,
+            dust_limit_satoshis,
+            feerate_per_kw,
+            offered_htlcs,
+            received_htlcs,
+        );
+    }
+    if let Err(e) = check_htlc_count(offered_htlcs, received_htlcs) {
+        panic!("Refusing to build commitment transaction: {:?}", e);
+    }
+
+    // The remote funder pays the fee: take it off to_remote before its dust
+    // check, and leave our to_local (and its dust check) alone
+    let num_htlcs = offered_htlcs.len() + received_htlcs.len();
+    let fee = calculate_commitment_tx_fee(feerate_per_kw, num_htlcs);
+    let mut output_metadata = create_commitment_transaction_outputs(
+        to_local_value,
+        to_remote_value.saturating_sub(fee),
+        commitment_keys,
+        remote_payment_basepoint,
+        to_self_delay,
+        dust_limit_satoshis,
+        0,
+    );
+    output_metadata.extend(create_htlc_outputs(commitment_keys, offered_htlcs, received_htlcs));
+    sort_outputs(&mut output_metadata);
+
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: funding_outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: output_metadata
+            .iter()
+            .map(|meta| TxOut {
+                value: Amount::from_sat(meta.value),
+                script_pubkey: meta.script.clone(),
+            })
+            .collect(),
+    };
+
+    // The obscuring factor always hashes the funder's basepoint first
+    set_obscured_commitment_number(
+        &mut tx,
+        commitment_number,
+        remote_payment_basepoint,
+        local_payment_basepoint,
+    );
+
+    tx
+}
+
+/// helper (Not an exercise)
+/// Build the counterparty's commitment transaction: the one they hold and
+/// can broadcast, which we sign in `commitment_signed`.
+///
+/// Everything is mirrored from our holder commitment. Their balance is the
+/// delayed `to_local`, revocable with a key derived from *our* revocation
+/// basepoint and their `remote_per_commitment_point`. Our balance is the
+/// `to_remote`, straight to our payment basepoint. HTLCs we offered are
+/// received HTLCs on their commitment, and vice versa. `to_self_delay` is
+/// the delay we asked of them, and their dust limit governs which outputs
+/// are trimmed: an HTLC that is dust on our commitment may still be present
+/// on theirs, and the other way round. The fee still comes from the
+/// funder's output, and the commitment number is obscured with the funder's
+/// payment basepoint first.
+#[allow(clippy::too_many_arguments)]
+pub fn build_remote_commitment_transaction(
+    funding_outpoint: OutPoint,
+    local_value: u64,
+    remote_value: u64,
+    keys: &ChannelKeyManager,
+    remote_keys: &ChannelPublicKeys,
+    remote_per_commitment_point: &PublicKey,
+    commitment_number: u64,
+    to_self_delay: u16,
+    local_dust_limit_satoshis: u64,
+    remote_dust_limit_satoshis: u64,
+    feerate_per_kw: u64,
+    offered_htlcs: &[HTLCOutput],
+    received_htlcs: &[HTLCOutput],
+    local_is_funder: bool,
+) -> Transaction {
+    let local_keys = keys.to_public_keys();
+    let commitment_keys = CommitmentKeys::from_basepoints(
+        remote_per_commitment_point,
+        &remote_keys.delayed_payment_basepoint,
+        &remote_keys.htlc_basepoint,
+        &local_keys.revocation_basepoint,
+        &local_keys.htlc_basepoint,
+        &keys.secp_ctx,
+    );
+
+    // Keep the HTLCs that are not dust on their commitment
+    let untrimmed_on_remote = |htlcs: &[HTLCOutput], offered_by_local: bool| -> Vec<HTLCOutput> {
+        htlcs
+            .iter()
+            .filter(|htlc| {
+                !htlc_trimming(
+                    htlc.amount_sat,
+                    local_dust_limit_satoshis,
+                    remote_dust_limit_satoshis,
+                    feerate_per_kw,
+                    offered_by_local,
+                    ChannelType::StaticRemoteKey,
+                )
+                .trimmed_on_remote
+            })
+            .cloned()
+            .collect()
+    };
+    let offered_htlcs = untrimmed_on_remote(offered_htlcs, true);
+    let received_htlcs = untrimmed_on_remote(received_htlcs, false);
+
+    // From here on "local" is the counterparty, who holds this commitment
+    create_commitment_transaction_with_funder(
+        funding_outpoint,
+        remote_value,
+        local_value,
+        &commitment_keys,
+        &remote_keys.payment_basepoint,
+        &local_keys.payment_basepoint,
+        commitment_number,
+        to_self_delay,
+        remote_dust_limit_satoshis,
+        feerate_per_kw,
+        &received_htlcs,
+        &offered_htlcs,
+        !local_is_funder,
+    )
+}
+
 /// Exercise 21: Finalize holder commitment transaction
 pub fn finalize_holder_commitment(
     keys_manager: ChannelKeyManager,
@@ -150,6 +469,10 @@ pub fn finalize_holder_commitment(
     remote_funding_signature: Vec<u8>,
     local_sig_first: bool,
 ) -> Transaction {
+    // Refuse to sign a transaction that breaks the BOLT 3 encoding (provided)
+    if let Err(e) = check_commitment_tx_encoding(&tx) {
+        panic!("Refusing to sign malformed commitment transaction: {:?}", e);
+    }
 
     unimplemented!();
 
@@ -163,4 +486,74 @@ pub fn finalize_holder_commitment(
 
     // Return Transaction
 
-}
\ No newline at end of file
+}
+
+/// A holder commitment with both funding signatures in place, and the
+/// pieces that went into it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedCommitment {
+    pub tx: Transaction,
+    /// Our funding signature, with its sighash flag
+    pub local_sig: Vec<u8>,
+    /// The counterparty's funding signature from `commitment_signed`
+    pub remote_sig: Vec<u8>,
+    /// The 2-of-2 witness script both signatures are for
+    pub funding_script: ScriptBuf,
+    /// Weight of the signed transaction, in weight units
+    pub weight: u64,
+    /// Fee in satoshis: the funding amount less the outputs
+    pub fee: u64,
+}
+
+/// Why a holder commitment could not be finalized
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignedCommitmentError {
+    /// The outputs spend more than the funding output holds
+    OutputsExceedFunding { funding_amount: u64, outputs_sat: u64 },
+}
+
+/// helper (Not an exercise)
+/// `finalize_holder_commitment`, returning a `SignedCommitment` so callers
+/// can use the signatures, weight and fee without deriving them again.
+/// A transaction whose outputs exceed `funding_amount` is refused unsigned.
+pub fn finalize_holder_commitment_detailed(
+    keys_manager: ChannelKeyManager,
+    tx: Transaction,
+    input_index: usize,
+    funding_script: &ScriptBuf,
+    funding_amount: u64,
+    remote_funding_signature: Vec<u8>,
+    local_sig_first: bool,
+) -> Result<SignedCommitment, SignedCommitmentError> {
+    let outputs_sat: u64 = tx.output.iter().map(|output| output.value.to_sat()).sum();
+    let fee = funding_amount
+        .checked_sub(outputs_sat)
+        .ok_or(SignedCommitmentError::OutputsExceedFunding { funding_amount, outputs_sat })?;
+
+    let signed_tx = finalize_holder_commitment(
+        keys_manager,
+        tx,
+        input_index,
+        funding_script,
+        funding_amount,
+        remote_funding_signature.clone(),
+        local_sig_first,
+    );
+
+    // The witness is <> <sig> <sig> <funding_script>
+    let local_sig_position = if local_sig_first { 1 } else { 2 };
+    let local_sig = signed_tx.input[input_index]
+        .witness
+        .nth(local_sig_position)
+        .expect("Finalized witness holds both signatures")
+        .to_vec();
+
+    Ok(SignedCommitment {
+        weight: signed_tx.weight().to_wu(),
+        fee,
+        tx: signed_tx,
+        local_sig,
+        remote_sig: remote_funding_signature,
+        funding_script: funding_script.clone(),
+    })
+}
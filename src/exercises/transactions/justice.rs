@@ -0,0 +1,193 @@
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::{PublicKey, SecretKey};
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
+
+use crate::keys::commitment::derive_revocation_private_key;
+use crate::transactions::claim::{value_after_fee, ClaimError};
+use crate::transactions::weights::{
+    JUSTICE_TO_LOCAL_WEIGHT, OFFERED_HTLC_PENALTY_INPUT_WEIGHT, RECEIVED_HTLC_PENALTY_INPUT_WEIGHT,
+    SWEEP_TX_BASE_WEIGHT,
+};
+use crate::scripts::create_to_local_script;
+use crate::types::{ChannelKeyManager, CommitmentKeys};
+
+/// helper (Not an exercise)
+/// Sweep the `to_local` output of a counterparty commitment they have since
+/// revoked.
+///
+/// Once they hand over `per_commitment_secret` in `revoke_and_ack`, we can
+/// derive the revocation private key for that commitment and take their
+/// whole balance through the revocation branch of the to_local script,
+/// without waiting for `to_self_delay`. The fee, at `feerate_per_kw`, comes
+/// out of the swept amount, which must leave a non-dust output.
+pub fn create_justice_transaction(
+    revoked_outpoint: OutPoint,
+    to_local_value: u64,
+    to_local_script: &ScriptBuf,
+    per_commitment_secret: &SecretKey,
+    keys: &ChannelKeyManager,
+    destination_script: &ScriptBuf,
+    feerate_per_kw: u64,
+) -> Result<Transaction, ClaimError> {
+    let fee = feerate_per_kw * JUSTICE_TO_LOCAL_WEIGHT / 1000;
+    let output_sat = value_after_fee(to_local_value, fee, destination_script)?;
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: revoked_outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(output_sat),
+            script_pubkey: destination_script.clone(),
+        }],
+    };
+
+    let revocation_privkey = derive_revocation_private_key(
+        &keys.revocation_basepoint_secret,
+        per_commitment_secret,
+        &keys.secp_ctx,
+    );
+    let signature = keys.sign_transaction_input_sighash_all(
+        &tx,
+        0,
+        to_local_script,
+        to_local_value,
+        &revocation_privkey,
+    );
+
+    // <revocation_sig> 1 <to_local_script>
+    tx.input[0].witness = Witness::from_slice(&[&signature[..], &[0x01], to_local_script.as_bytes()]);
+    Ok(tx)
+}
+
+/// An HTLC output on a revoked counterparty commitment
+#[derive(Debug, Clone)]
+pub struct RevokedHtlcOutput {
+    pub outpoint: OutPoint,
+    pub amount_sat: u64,
+    /// The offered or received HTLC script the output pays to
+    pub htlc_script: ScriptBuf,
+    /// Offered by the counterparty, i.e. an offered HTLC on their commitment
+    pub offered: bool,
+}
+
+/// helper (Not an exercise)
+/// Sweep every HTLC output of a revoked counterparty commitment in one
+/// transaction, through the revocation branch both HTLC scripts start with.
+///
+/// Each input is signed with the revocation private key for the commitment
+/// and spent with `<revocation_sig> <revocationpubkey> <htlc_script>`. The
+/// fee, at `feerate_per_kw`, comes out of the single output, which must not
+/// end up dust.
+pub fn create_htlc_justice_transaction(
+    revoked_htlcs: &[RevokedHtlcOutput],
+    per_commitment_secret: &SecretKey,
+    keys: &ChannelKeyManager,
+    destination_script: &ScriptBuf,
+    feerate_per_kw: u64,
+) -> Result<Transaction, ClaimError> {
+    if revoked_htlcs.is_empty() {
+        return Err(ClaimError::NoOutputs);
+    }
+    let weight = SWEEP_TX_BASE_WEIGHT
+        + revoked_htlcs
+            .iter()
+            .map(|htlc| {
+                if htlc.offered {
+                    OFFERED_HTLC_PENALTY_INPUT_WEIGHT
+                } else {
+                    RECEIVED_HTLC_PENALTY_INPUT_WEIGHT
+                }
+            })
+            .sum::<u64>();
+    let total_sat: u64 = revoked_htlcs.iter().map(|htlc| htlc.amount_sat).sum();
+    let fee = feerate_per_kw * weight / 1000;
+    let output_sat = value_after_fee(total_sat, fee, destination_script)?;
+
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: revoked_htlcs
+            .iter()
+            .map(|htlc| TxIn {
+                previous_output: htlc.outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            })
+            .collect(),
+        output: vec![TxOut {
+            value: Amount::from_sat(output_sat),
+            script_pubkey: destination_script.clone(),
+        }],
+    };
+
+    let revocation_privkey = derive_revocation_private_key(
+        &keys.revocation_basepoint_secret,
+        per_commitment_secret,
+        &keys.secp_ctx,
+    );
+    let revocation_pubkey = PublicKey::from_secret_key(&keys.secp_ctx, &revocation_privkey);
+
+    // Sign every input first: each signature commits to all the inputs
+    let signatures: Vec<Vec<u8>> = revoked_htlcs
+        .iter()
+        .enumerate()
+        .map(|(i, htlc)| {
+            keys.sign_transaction_input_sighash_all(
+                &tx,
+                i,
+                &htlc.htlc_script,
+                htlc.amount_sat,
+                &revocation_privkey,
+            )
+        })
+        .collect();
+    for ((input, htlc), signature) in tx.input.iter_mut().zip(revoked_htlcs).zip(signatures) {
+        input.witness = Witness::from_slice(&[
+            &signature[..],
+            &revocation_pubkey.serialize()[..],
+            htlc.htlc_script.as_bytes(),
+        ]);
+    }
+
+    Ok(tx)
+}
+
+/// helper (Not an exercise)
+/// Sweep the output of an HTLC-timeout or HTLC-success transaction the
+/// counterparty broadcast from a commitment they had revoked.
+///
+/// Its output uses the `to_local` script of their commitment
+/// (`commitment_keys`, with their `to_self_delay`), so we can take it
+/// through the revocation branch before their delay runs out.
+pub fn create_second_stage_justice_transaction(
+    htlc_tx: &Transaction,
+    commitment_keys: &CommitmentKeys,
+    to_self_delay: u16,
+    per_commitment_secret: &SecretKey,
+    keys: &ChannelKeyManager,
+    destination_script: &ScriptBuf,
+    feerate_per_kw: u64,
+) -> Result<Transaction, ClaimError> {
+    let script = create_to_local_script(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_delayed_payment_key,
+        to_self_delay,
+    );
+    create_justice_transaction(
+        OutPoint::new(htlc_tx.compute_txid(), 0),
+        htlc_tx.output[0].value.to_sat(),
+        &script,
+        per_commitment_secret,
+        keys,
+        destination_script,
+        feerate_per_kw,
+    )
+}
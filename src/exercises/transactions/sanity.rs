@@ -0,0 +1,138 @@
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::transaction::Version;
+use bitcoin::hashes::Hash;
+use bitcoin::{Sequence, Transaction};
+
+use crate::types::ChannelType;
+
+// BOLT 3 hides the obscured commitment number in the commitment transaction:
+//   nLockTime = 0x20 << 24 | lower 24 bits
+//   nSequence = 0x80 << 24 | upper 24 bits
+// The 0x20 prefix keeps the locktime in the past (a timestamp in 1987), and
+// the 0x80 prefix sets the BIP68 disable flag, so neither field actually
+// delays the commitment transaction.
+const COMMITMENT_LOCKTIME_PREFIX: u32 = 0x20;
+const COMMITMENT_SEQUENCE_PREFIX: u32 = 0x80;
+
+/// How a transaction breaks the BOLT 3 version/locktime/sequence rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxEncodingError {
+    /// Channel transactions are version 2 (needed for OP_CSV)
+    WrongVersion(Version),
+    /// Commitment and HTLC transactions spend exactly one output
+    WrongInputCount(usize),
+    /// Commitment nLockTime must start with 0x20
+    CommitmentLocktime(u32),
+    /// Commitment nSequence must start with 0x80
+    CommitmentSequence(u32),
+    /// HTLC-timeout nLockTime must be the HTLC's cltv_expiry (a block height)
+    HtlcTimeoutLocktime(LockTime),
+    /// HTLC-success nLockTime must be 0
+    HtlcSuccessLocktime(LockTime),
+    /// HTLC transaction input has the wrong nSequence for the channel type
+    HtlcSequence { expected: Sequence, actual: Sequence },
+}
+
+fn check_single_input_v2(tx: &Transaction) -> Result<(), TxEncodingError> {
+    if tx.version != Version::TWO {
+        return Err(TxEncodingError::WrongVersion(tx.version));
+    }
+    if tx.input.len() != 1 {
+        return Err(TxEncodingError::WrongInputCount(tx.input.len()));
+    }
+    Ok(())
+}
+
+/// helper (Not an exercise)
+/// Check a commitment transaction carries the obscured commitment number encoding
+pub fn check_commitment_tx_encoding(tx: &Transaction) -> Result<(), TxEncodingError> {
+    check_single_input_v2(tx)?;
+
+    let lock_time = tx.lock_time.to_consensus_u32();
+    if lock_time >> 24 != COMMITMENT_LOCKTIME_PREFIX {
+        return Err(TxEncodingError::CommitmentLocktime(lock_time));
+    }
+
+    let sequence = tx.input[0].sequence.to_consensus_u32();
+    if sequence >> 24 != COMMITMENT_SEQUENCE_PREFIX {
+        return Err(TxEncodingError::CommitmentSequence(sequence));
+    }
+
+    Ok(())
+}
+
+fn check_htlc_sequence(tx: &Transaction, channel_type: ChannelType) -> Result<(), TxEncodingError> {
+    let expected = channel_type.htlc_input_sequence();
+    let actual = tx.input[0].sequence;
+    if actual != expected {
+        return Err(TxEncodingError::HtlcSequence { expected, actual });
+    }
+    Ok(())
+}
+
+/// helper (Not an exercise)
+/// Check an HTLC-timeout transaction: nLockTime is the (non-zero) cltv_expiry
+/// block height and the input sequence matches the channel type
+pub fn check_htlc_timeout_tx_encoding(
+    tx: &Transaction,
+    channel_type: ChannelType,
+) -> Result<(), TxEncodingError> {
+    check_single_input_v2(tx)?;
+
+    if !tx.lock_time.is_block_height() || tx.lock_time == LockTime::ZERO {
+        return Err(TxEncodingError::HtlcTimeoutLocktime(tx.lock_time));
+    }
+
+    check_htlc_sequence(tx, channel_type)
+}
+
+/// helper (Not an exercise)
+/// Check an HTLC-success transaction: nLockTime is 0 and the input sequence
+/// matches the channel type
+pub fn check_htlc_success_tx_encoding(
+    tx: &Transaction,
+    channel_type: ChannelType,
+) -> Result<(), TxEncodingError> {
+    check_single_input_v2(tx)?;
+
+    if tx.lock_time != LockTime::ZERO {
+        return Err(TxEncodingError::HtlcSuccessLocktime(tx.lock_time));
+    }
+
+    check_htlc_sequence(tx, channel_type)
+}
+
+/// Why a pre-signed child transaction does not safely reference its parent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutpointError {
+    /// The input names the parent's wtxid, which changes whenever its witness does
+    ReferencesWtxid { input_index: usize },
+    /// No input spends the parent
+    DoesNotSpendParent,
+}
+
+/// helper (Not an exercise)
+/// Check that `child` spends `parent` by its txid, returning the input index.
+///
+/// Segwit txids exclude the witness, so signatures can be added to (or
+/// malleated on) the parent without changing any outpoint a pre-signed
+/// child refers to. This is what lets us sign the commitment transaction
+/// before the funding transaction is broadcast.
+pub fn check_spends_parent_txid(
+    child: &Transaction,
+    parent: &Transaction,
+) -> Result<usize, OutpointError> {
+    let txid = parent.compute_txid();
+    let wtxid = parent.compute_wtxid();
+
+    for (input_index, input) in child.input.iter().enumerate() {
+        if input.previous_output.txid == txid {
+            return Ok(input_index);
+        }
+        if input.previous_output.txid.to_byte_array() == wtxid.to_byte_array() {
+            return Err(OutpointError::ReferencesWtxid { input_index });
+        }
+    }
+
+    Err(OutpointError::DoesNotSpendParent)
+}
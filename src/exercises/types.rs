@@ -1,7 +1,11 @@
 use bitcoin::bip32::Xpriv;
+use bitcoin::hashes::ripemd160::Hash as Ripemd160;
+use bitcoin::hashes::sha256::Hash as Sha256;
+use bitcoin::hashes::Hash;
 use bitcoin::script::ScriptBuf;
 use bitcoin::secp256k1::{All, PublicKey, Secp256k1, SecretKey};
-use bitcoin::Network;
+use bitcoin::sighash::EcdsaSighashType;
+use bitcoin::{Network, Sequence};
 
 // KEY FAMILY ENUM
 // ============================================================================
@@ -98,11 +102,87 @@ pub struct HTLCOutput {
     /// Amount in satoshis
     pub amount_sat: u64,
     /// Payment hash for this HTLC
-    pub payment_hash: [u8; 32],
+    pub payment_hash: PaymentHash,
     /// CLTV expiry height
     pub cltv_expiry: u32,
 }
 
+// PAYMENT HASH
+// ============================================================================
+
+/// SHA256 of a payment preimage.
+///
+/// HTLC scripts commit to RIPEMD160(payment_hash), never to the preimage.
+/// Wrapping the hash in its own type stops a preimage (also 32 bytes) from
+/// being passed where the hash is expected. The field is private, so a
+/// `PaymentHash` is only ever built by hashing a preimage or by explicitly
+/// wrapping hash bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PaymentHash([u8; 32]);
+
+impl PaymentHash {
+    /// Hash a preimage into the payment hash that locks the HTLC
+    pub fn from_preimage(preimage: &[u8; 32]) -> Self {
+        PaymentHash(Sha256::hash(preimage).to_byte_array())
+    }
+
+    /// Wrap a payment hash computed elsewhere, e.g. one received in
+    /// `update_add_htlc` or read from an invoice
+    pub fn from_hash_bytes(payment_hash: [u8; 32]) -> Self {
+        PaymentHash(payment_hash)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// RIPEMD160(payment_hash): the 20 bytes HTLC scripts compare against
+pub fn payment_hash160(payment_hash: &PaymentHash) -> [u8; 20] {
+    Ripemd160::hash(payment_hash.as_bytes()).to_byte_array()
+}
+
+// CHANNEL TYPE
+// ============================================================================
+
+/// The commitment format negotiated for a channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelType {
+    /// `option_static_remotekey`: the format the exercises build
+    StaticRemoteKey,
+    /// `option_anchors_zero_fee_htlc_tx`: HTLC outputs carry `1 OP_CSV` and
+    /// second-stage HTLC transactions pay no fee of their own
+    AnchorsZeroFeeHtlcTx,
+}
+
+impl ChannelType {
+    /// nSequence for inputs spending an HTLC output (1 to satisfy `1 OP_CSV` with anchors)
+    pub fn htlc_input_sequence(&self) -> Sequence {
+        match self {
+            ChannelType::StaticRemoteKey => Sequence::ZERO,
+            ChannelType::AnchorsZeroFeeHtlcTx => Sequence::from_consensus(1),
+        }
+    }
+
+    /// Feerate to build HTLC-timeout/success transactions at. With anchors they
+    /// are zero-fee and the broadcaster attaches fees later.
+    pub fn htlc_tx_feerate_per_kw(&self, feerate_per_kw: u64) -> u64 {
+        match self {
+            ChannelType::StaticRemoteKey => feerate_per_kw,
+            ChannelType::AnchorsZeroFeeHtlcTx => 0,
+        }
+    }
+
+    /// Sighash flag the counterparty uses for its HTLC transaction signatures.
+    /// SINGLE|ANYONECANPAY lets the broadcaster add inputs and outputs for fees.
+    pub fn remote_htlc_sighash_type(&self) -> EcdsaSighashType {
+        match self {
+            ChannelType::StaticRemoteKey => EcdsaSighashType::All,
+            ChannelType::AnchorsZeroFeeHtlcTx => EcdsaSighashType::SinglePlusAnyoneCanPay,
+        }
+    }
+}
+
 // TEST VECTOR STRUCTURES
 // ============================================================================
 
@@ -140,7 +220,7 @@ pub struct Bolt3TestVector {
 pub struct Bolt3Htlc {
     pub direction: HtlcDirection,
     pub amount_msat: u64,
-    pub payment_hash: [u8; 32],
+    pub payment_hash: PaymentHash,
     pub cltv_expiry: u32,
 }
 
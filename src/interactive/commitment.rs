@@ -1,8 +1,10 @@
 use crate::internal::bitcoind_client::{get_bitcoind_client, BitcoindClient};
-use crate::internal::helper::get_outpoint;
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::internal::spv::verify_funding_inclusion;
+use crate::internal::helper::{check_mempool_accept, print_outputs, print_tx_link, get_outpoint};
 use crate::keys::derivation::new_keys_manager;
 use crate::scripts::funding::create_funding_script;
-use crate::transactions::commitment::{create_commitment_transaction, finalize_holder_commitment};
+use crate::transactions::commitment::{create_commitment_transaction, finalize_holder_commitment_detailed};
 use crate::types::{CommitmentKeys, KeyFamily, HTLCOutput};
 use bitcoin::consensus::encode::serialize_hex;
 use bitcoin::locktime::absolute::LockTime;
@@ -10,18 +12,31 @@ use bitcoin::script::ScriptBuf;
 use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
 use bitcoin::transaction::Version;
 use bitcoin::Network;
-use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
+use bitcoin::{Amount, BlockHash, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
 use bitcoin::PublicKey as BitcoinPublicKey;
 
-pub fn run(funding_txid: String) {
+pub fn run(funding_txid: Txid, funding_block: Option<BlockHash>) {
     // Parse the argument as txid
     let txid = funding_txid;
 
     // get bitcoin client
     let bitcoind = get_bitcoind_client();
 
+    // A node only sends channel_ready once it has proof the funding
+    // transaction is in a block, not just bitcoind's word for it
+    if let Some(block_hash) = funding_block {
+        match verify_funding_inclusion(&bitcoind, &txid, &block_hash) {
+            Ok(position) => {
+                println!("\n🔗 Funding tx {} is transaction #{} in block {}", txid, position, block_hash);
+            }
+            Err(e) => {
+                eprintln!("❌ Could not prove the funding tx is in block {}: {}", block_hash, e);
+                return;
+            }
+        }
+    }
+
     let our_seed = [0x01; 32];
-    let remote_seed = [0x02; 32];
     let bitcoin_network = Network::Bitcoin;
     let channel_index = 0;
     let secp_ctx = Secp256k1::new();
@@ -37,12 +52,10 @@ pub fn run(funding_txid: String) {
     let local_funding_pubkey = BitcoinPublicKey::new(our_channel_public_keys.funding_pubkey);
     let first_commitment_point = our_channel_keys_manager.derive_per_commitment_point(commitment_number);
 
-    // Get our Counterparty keys
-    let remote_node_keys_manager = new_keys_manager(remote_seed, bitcoin_network);
-    let remote_channel_keys_manager = remote_node_keys_manager.derive_channel_keys(channel_index);
-    let remote_channel_public_keys = remote_channel_keys_manager.to_public_keys();
+    // Get our Counterparty's public keys (all a real peer would tell us)
+    let remote_peer = SimulatedPeer::new();
+    let remote_channel_public_keys = remote_peer.public_keys();
     let remote_payment_basepoint = remote_channel_public_keys.payment_basepoint;
-    let remote_funding_privkey = remote_channel_keys_manager.funding_key;
     let remote_funding_pubkey = BitcoinPublicKey::new(remote_channel_public_keys.funding_pubkey);
 
     // Get our commitment keys
@@ -58,7 +71,7 @@ pub fn run(funding_txid: String) {
     );
 
     let txid_index = 0;
-    let funding_outpoint = get_outpoint(txid.to_string(), txid_index);
+    let funding_outpoint = get_outpoint(txid, txid_index);
 
     let funding_amount = 5_000_000;
     let to_local_value = 4_998_500;
@@ -89,19 +102,18 @@ pub fn run(funding_txid: String) {
     let funding_script = create_funding_script(&local_funding_pubkey, &remote_funding_pubkey);
 
     // Step 2: In real Lightning, we would send this transaction to our counterparty
-    // and they would send us back their signature. Here we simulate that by
-    // creating their signature ourselves (but in reality we wouldn't have their key!)
-    let remote_funding_signature = remote_channel_keys_manager.sign_transaction_input_sighash_all(
+    // and they would send us back their signature (commitment_signed). Here the
+    // simulated peer signs with its own key, which we never see.
+    let remote_funding_signature = remote_peer.sign_holder_commitment(
         &tx,
         txid_index,
         &funding_script,
         funding_amount,
-        &remote_funding_privkey,
     );
 
     let local_sig_first = true;
 
-    let signed_tx = finalize_holder_commitment(
+    let signed = finalize_holder_commitment_detailed(
         our_channel_keys_manager,
         tx,
         txid_index,
@@ -109,9 +121,21 @@ pub fn run(funding_txid: String) {
         funding_amount,
         remote_funding_signature,
         local_sig_first);
+    let signed = match signed {
+        Ok(signed) => signed,
+        Err(e) => {
+            eprintln!("❌ Could not finalize the commitment transaction: {:?}", e);
+            return;
+        }
+    };
+    let signed_tx = signed.tx;
 
     println!("\n✅ Commitment Transaction Created\n");
     println!("Tx ID: {}", signed_tx.compute_txid());
+    println!("Fee: {} sats for {} weight units", signed.fee, signed.weight);
+    print_tx_link(&signed_tx.compute_txid());
     println!("\nTx Hex: {}", serialize_hex(&signed_tx));
+    print_outputs(&signed_tx);
+    check_mempool_accept(&bitcoind, &signed_tx);
     println!();
 }
@@ -4,22 +4,23 @@ use bitcoin::secp256k1::{Secp256k1, PublicKey, SecretKey};
 use bitcoin::transaction::Version;
 use bitcoin::locktime::absolute::LockTime;
 use bitcoin::consensus::encode::serialize_hex;
-use crate::internal::helper::{get_unspent_output, sign_raw_transaction};
+use crate::internal::helper::{get_unspent_output, sign_raw_transaction, check_mempool_accept, print_outputs, print_tx_link};
+use crate::internal::simulated_peer::SimulatedPeer;
 use crate::internal::bitcoind_client::{BitcoindClient, get_bitcoind_client};
 use crate::scripts::funding::create_funding_script;
 use crate::keys::derivation::new_keys_manager;
-use crate::transactions::funding::create_funding_transaction;
+use crate::transactions::funding::{
+    bump_funding_transaction_fee, check_funding_amount, create_funding_transaction,
+    create_funding_transaction_with_change, FundingBumpError, FundingChangeError,
+    P2WPKH_INPUT_SATISFACTION_WEIGHT,
+};
 use bitcoin::Network;
 use crate::types::{KeyFamily};
 use bitcoin::PublicKey as BitcoinPublicKey;
+use bitcoin::psbt::{self, Psbt};
 
-pub fn build_funding_tx(
-    bitcoind: BitcoindClient,
-    tx_input: TxIn,
-    funding_amount_sat: u64,
-) { 
+fn funding_pubkeys() -> (BitcoinPublicKey, BitcoinPublicKey) {
     let our_seed = [0x01; 32];
-    let remote_seed = [0x02; 32];
     let bitcoin_network = Network::Bitcoin;
     let channel_index = 0;
     let secp_ctx = Secp256k1::new();
@@ -29,10 +30,18 @@ pub fn build_funding_tx(
     let local_funding_pubkey = BitcoinPublicKey::new(
             PublicKey::from_secret_key(&secp_ctx, &local_funding_privkey));
     
-    let remote_keys_manager = new_keys_manager(remote_seed, bitcoin_network);
-    let remote_funding_privkey = remote_keys_manager.derive_key(KeyFamily::MultiSig, channel_index);
-    let remote_funding_pubkey = BitcoinPublicKey::new(
-        PublicKey::from_secret_key(&secp_ctx, &remote_funding_privkey));
+    // The counterparty only tells us its funding pubkey
+    let remote_funding_pubkey = BitcoinPublicKey::new(SimulatedPeer::new().public_keys().funding_pubkey);
+
+    (local_funding_pubkey, remote_funding_pubkey)
+}
+
+pub fn build_funding_tx(
+    bitcoind: BitcoindClient,
+    tx_input: TxIn,
+    funding_amount_sat: u64,
+) { 
+    let (local_funding_pubkey, remote_funding_pubkey) = funding_pubkeys();
     
     let input_txid = tx_input.previous_output.txid;
     let input_vout = tx_input.previous_output.vout;
@@ -49,20 +58,400 @@ pub fn build_funding_tx(
     
     println!("\n✅ Funding Transaction Created\n");
     println!("Tx ID: {}", signed_tx.compute_txid());
+    print_tx_link(&signed_tx.compute_txid());
+    println!("\nTx Hex: {}", serialize_hex(&signed_tx));
+    print_outputs(&signed_tx);
+    check_mempool_accept(&bitcoind, &signed_tx);
+
+    // Reserve the input, so running `Funding` again before this transaction
+    // is broadcast does not pick the same UTXO and double-spend it
+    match bitcoind.lock_unspent(&[tx_input.previous_output]) {
+        Ok(()) => {
+            println!("\n🔒 Locked input {} for this funding transaction", tx_input.previous_output);
+            println!("   (see `utxos --locked`, release with `utxos --unlock-all`)");
+        }
+        Err(e) => println!("\n⚠️  Could not lock input {}: {}", tx_input.previous_output, e),
+    }
+    println!();
+}
+
+/// Feerate the original funding transaction pays before it is bumped
+const RBF_ORIGINAL_FEERATE_PER_KW: u64 = 253;
+
+/// Sats of the input kept out of the channel in the RBF demo, so both the
+/// original and the replacement can pay their fee and keep a change output
+pub const RBF_FEE_AND_CHANGE_RESERVE_SAT: u64 = 100_000;
+
+/// Why the RBF demo's funding transactions could not be built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FundingRbfError {
+    /// The input does not even cover `RBF_FEE_AND_CHANGE_RESERVE_SAT`
+    InputTooSmall { input_amount_sat: u64 },
+    /// The original funding transaction could not be built
+    Original(FundingChangeError),
+    /// The replacement could not be built
+    Bump(FundingBumpError),
+}
+
+/// The unsigned funding transaction for the RBF demo, signalling
+/// replaceability and paying `RBF_ORIGINAL_FEERATE_PER_KW`, and its
+/// replacement paying `bump_feerate_per_kw`. The channel is funded with the
+/// input less `RBF_FEE_AND_CHANGE_RESERVE_SAT`.
+pub fn build_rbf_funding_transactions(
+    prevout: OutPoint,
+    input_amount_sat: u64,
+    change_script: &ScriptBuf,
+    bump_feerate_per_kw: u64,
+) -> Result<(Transaction, Transaction), FundingRbfError> {
+    let (local_funding_pubkey, remote_funding_pubkey) = funding_pubkeys();
+    let funding_amount_sat = input_amount_sat
+        .checked_sub(RBF_FEE_AND_CHANGE_RESERVE_SAT)
+        .ok_or(FundingRbfError::InputTooSmall { input_amount_sat })?;
+
+    let mut original = create_funding_transaction_with_change(
+        prevout,
+        input_amount_sat,
+        P2WPKH_INPUT_SATISFACTION_WEIGHT,
+        funding_amount_sat,
+        &local_funding_pubkey,
+        &remote_funding_pubkey,
+        change_script,
+        RBF_ORIGINAL_FEERATE_PER_KW,
+    )
+    .map_err(FundingRbfError::Original)?;
+    // BIP125: only a transaction that signals can be replaced
+    for input in &mut original.input {
+        input.sequence = Sequence::ENABLE_RBF_NO_LOCKTIME;
+    }
+
+    let bumped = bump_funding_transaction_fee(
+        &original,
+        input_amount_sat,
+        P2WPKH_INPUT_SATISFACTION_WEIGHT,
+        bump_feerate_per_kw,
+    )
+    .map_err(FundingRbfError::Bump)?;
+
+    Ok((original, bumped))
+}
+
+/// Broadcast a funding transaction that signals replace-by-fee, then replace
+/// it with one paying `bump_feerate_per_kw`. The replacement has a new txid,
+/// so any commitment transaction signed against the original is void.
+pub fn demonstrate_funding_rbf(bitcoind: BitcoindClient, tx_input: TxIn, bump_feerate_per_kw: u64) {
+    let prevout = tx_input.previous_output;
+    let input_amount_sat = match bitcoind.get_raw_transaction(&prevout.txid) {
+        Ok(prev_tx) => prev_tx.output[prevout.vout as usize].value.to_sat(),
+        Err(e) => {
+            eprintln!("❌ Could not fetch the input's previous transaction: {}", e);
+            return;
+        }
+    };
+
+    let change_address = match bitcoind.get_new_address() {
+        Ok(address) => address,
+        Err(e) => {
+            eprintln!("❌ Could not get a change address: {}", e);
+            return;
+        }
+    };
+
+    let (tx, bumped) = match build_rbf_funding_transactions(
+        prevout,
+        input_amount_sat,
+        &change_address.script_pubkey(),
+        bump_feerate_per_kw,
+    ) {
+        Ok(txs) => txs,
+        Err(e) => {
+            eprintln!("❌ Could not build the funding transactions: {:?}", e);
+            return;
+        }
+    };
+    let original = sign_raw_transaction(bitcoind.clone(), tx);
+    if let Err(e) = bitcoind.send_raw_transaction(&original) {
+        eprintln!("❌ Could not broadcast the original funding transaction: {}", e);
+        return;
+    }
+    println!("\n📤 Broadcast funding transaction at {} sat/kw", RBF_ORIGINAL_FEERATE_PER_KW);
+    println!("Tx ID: {}", original.compute_txid());
+    print_tx_link(&original.compute_txid());
+
+    let bumped = sign_raw_transaction(bitcoind.clone(), bumped);
+    if let Err(e) = bitcoind.send_raw_transaction(&bumped) {
+        eprintln!("❌ The replacement was rejected: {}", e);
+        return;
+    }
+
+    let fee = |tx: &Transaction| {
+        input_amount_sat - tx.output.iter().map(|output| output.value.to_sat()).sum::<u64>()
+    };
+    println!("\n✅ Funding Transaction Replaced at {} sat/kw\n", bump_feerate_per_kw);
+    println!("Tx ID: {}", bumped.compute_txid());
+    print_tx_link(&bumped.compute_txid());
+    println!("Fee: {} sats (was {} sats)", fee(&bumped), fee(&original));
+    print_outputs(&bumped);
+    println!("\nThe funding outpoint is now {}:0", bumped.compute_txid());
+    println!();
+}
+
+/// Append the channel's P2WSH funding output to a PSBT from an external
+/// wallet, returning its output index. The funding script is recorded as
+/// the output's witness script so the wallet can show what it pays to.
+pub fn add_funding_output(
+    psbt: &mut Psbt,
+    funding_amount_sat: u64,
+    local_funding_pubkey: &BitcoinPublicKey,
+    remote_funding_pubkey: &BitcoinPublicKey,
+) -> usize {
+    let funding_script = create_funding_script(local_funding_pubkey, remote_funding_pubkey);
+
+    psbt.unsigned_tx.output.push(TxOut {
+        value: Amount::from_sat(funding_amount_sat),
+        script_pubkey: funding_script.to_p2wsh(),
+    });
+    psbt.outputs.push(psbt::Output {
+        witness_script: Some(funding_script),
+        ..Default::default()
+    });
+
+    psbt.unsigned_tx.output.len() - 1
+}
+
+/// Errors from importing a signed funding PSBT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FundingPsbtError {
+    /// The wallet returned an input without a final scriptSig or witness
+    InputNotFinalized { input_index: usize },
+    /// The signed transaction no longer pays the funding script
+    MissingFundingOutput,
+    /// The PSBT could not be turned into a transaction (e.g. an absurd fee)
+    Extract(String),
+}
+
+/// Wrap the unsigned funding transaction in a PSBT for an external wallet.
+///
+/// Each input gets its witness UTXO (`input_utxos`, in input order) so the
+/// wallet can sign it and check the fee without the previous transaction.
+/// The funding output gets the 2-of-2 as its witness script.
+pub fn export_funding_psbt(
+    tx: Transaction,
+    input_utxos: &[TxOut],
+    funding_script: &ScriptBuf,
+) -> Result<Psbt, psbt::Error> {
+    let mut psbt = Psbt::from_unsigned_tx(tx)?;
+
+    for (input, utxo) in psbt.inputs.iter_mut().zip(input_utxos) {
+        input.witness_utxo = Some(utxo.clone());
+    }
+
+    let funding_script_pubkey = funding_script.to_p2wsh();
+    for (output, txout) in psbt.outputs.iter_mut().zip(&psbt.unsigned_tx.output) {
+        if txout.script_pubkey == funding_script_pubkey {
+            output.witness_script = Some(funding_script.clone());
+        }
+    }
+
+    Ok(psbt)
+}
+
+/// Turn the PSBT signed by an external wallet back into the funding
+/// transaction, checking every input is finalized and the funding output
+/// is still there.
+pub fn import_signed_funding_psbt(
+    psbt: Psbt,
+    funding_script: &ScriptBuf,
+) -> Result<Transaction, FundingPsbtError> {
+    if let Some(input_index) = psbt
+        .inputs
+        .iter()
+        .position(|input| input.final_script_witness.is_none() && input.final_script_sig.is_none())
+    {
+        return Err(FundingPsbtError::InputNotFinalized { input_index });
+    }
+
+    let funding_script_pubkey = funding_script.to_p2wsh();
+    if !psbt.unsigned_tx.output.iter().any(|output| output.script_pubkey == funding_script_pubkey) {
+        return Err(FundingPsbtError::MissingFundingOutput);
+    }
+
+    psbt.extract_tx().map_err(|e| FundingPsbtError::Extract(e.to_string()))
+}
+
+/// Read a PSBT file, either base64 (as most wallets export it) or binary
+fn read_psbt(path: &str) -> Result<Psbt, String> {
+    let contents = std::fs::read(path).map_err(|e| format!("could not read {}: {}", path, e))?;
+    let bytes = match std::str::from_utf8(&contents) {
+        Ok(text) => base64::decode(text.trim()).map_err(|e| format!("invalid base64: {}", e))?,
+        Err(_) => contents,
+    };
+    Psbt::deserialize(&bytes).map_err(|e| format!("invalid PSBT: {}", e))
+}
+
+/// Indices of the PSBT's inputs that are not known to spend native segwit
+/// outputs. Signing those fills in a scriptSig, which changes the txid, so
+/// the funding outpoint cannot be taken from the unsigned transaction.
+pub fn non_segwit_inputs(psbt: &Psbt) -> Vec<usize> {
+    psbt.inputs
+        .iter()
+        .enumerate()
+        .filter(|(_, input)| {
+            !input
+                .witness_utxo
+                .as_ref()
+                .is_some_and(|utxo| utxo.script_pubkey.is_witness_program())
+        })
+        .map(|(input_index, _)| input_index)
+        .collect()
+}
+
+pub fn build_funding_psbt(psbt_path: &str, funding_amount_sat: u64) {
+    let mut psbt = match read_psbt(psbt_path) {
+        Ok(psbt) => psbt,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return;
+        }
+    };
+
+    // Signatures do not change the txid as long as every input is segwit
+    let non_segwit = non_segwit_inputs(&psbt);
+    if !non_segwit.is_empty() {
+        eprintln!(
+            "❌ Inputs {:?} have no native segwit witness_utxo: signing them would change the txid, \
+             so the funding outpoint is not known yet. Fund the channel from segwit outputs.",
+            non_segwit
+        );
+        return;
+    }
+
+    let (local_funding_pubkey, remote_funding_pubkey) = funding_pubkeys();
+    let funding_output_index =
+        add_funding_output(&mut psbt, funding_amount_sat, &local_funding_pubkey, &remote_funding_pubkey);
+
+    println!("\n✅ Funding Output Added to PSBT\n");
+    println!(
+        "Funding outpoint: {}:{}",
+        psbt.unsigned_tx.compute_txid(),
+        funding_output_index
+    );
+    match psbt.fee() {
+        Ok(fee) => println!("Fee: {} sats", fee.to_sat()),
+        Err(e) => println!("⚠️  Could not compute the fee ({}). Check the inputs cover {} sats.", e, funding_amount_sat),
+    }
+    println!("\nSign and broadcast this PSBT with your wallet:\n{}", base64::encode(psbt.serialize()));
+    println!();
+}
+
+/// Build the funding transaction from a wallet UTXO and write it, unsigned,
+/// as a base64 PSBT to `export_path` for signing with an external wallet
+pub fn export_unsigned_funding_psbt(
+    bitcoind: BitcoindClient,
+    tx_input: TxIn,
+    funding_amount_sat: u64,
+    export_path: &str,
+) {
+    let (local_funding_pubkey, remote_funding_pubkey) = funding_pubkeys();
+    let funding_script = create_funding_script(&local_funding_pubkey, &remote_funding_pubkey);
+
+    let prevout = tx_input.previous_output;
+    let input_utxo = match bitcoind.get_raw_transaction(&prevout.txid) {
+        Ok(prev_tx) => prev_tx.output[prevout.vout as usize].clone(),
+        Err(e) => {
+            eprintln!("❌ Could not fetch the input's previous transaction: {}", e);
+            return;
+        }
+    };
+
+    let tx = create_funding_transaction(
+        prevout.txid,
+        prevout.vout,
+        funding_amount_sat,
+        &local_funding_pubkey,
+        &remote_funding_pubkey,
+    );
+    let psbt = match export_funding_psbt(tx, &[input_utxo], &funding_script) {
+        Ok(psbt) => psbt,
+        Err(e) => {
+            eprintln!("❌ Could not build the PSBT: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = std::fs::write(export_path, base64::encode(psbt.serialize()) + "\n") {
+        eprintln!("❌ Could not write {}: {}", export_path, e);
+        return;
+    }
+    println!("\n✅ Unsigned Funding PSBT written to {}\n", export_path);
+    println!("Funding outpoint: {}:0", psbt.unsigned_tx.compute_txid());
+    println!("\nSign it with your wallet, then run `funding --signed-psbt <file>`");
+    println!();
+}
+
+/// Import a funding PSBT signed by an external wallet and print the
+/// finished funding transaction
+pub fn import_signed_psbt(bitcoind: BitcoindClient, psbt_path: &str) {
+    let (local_funding_pubkey, remote_funding_pubkey) = funding_pubkeys();
+    let funding_script = create_funding_script(&local_funding_pubkey, &remote_funding_pubkey);
+
+    let signed_tx = match read_psbt(psbt_path)
+        .and_then(|psbt| import_signed_funding_psbt(psbt, &funding_script).map_err(|e| format!("{:?}", e)))
+    {
+        Ok(tx) => tx,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return;
+        }
+    };
+
+    println!("\n✅ Funding Transaction Imported\n");
+    println!("Tx ID: {}", signed_tx.compute_txid());
+    print_tx_link(&signed_tx.compute_txid());
     println!("\nTx Hex: {}", serialize_hex(&signed_tx));
+    print_outputs(&signed_tx);
+    check_mempool_accept(&bitcoind, &signed_tx);
     println!();
 }
 
 /// Interactive CLI function to create a Funding Transaction
-/// This fetches a UTXO automatically and creates the Funding Transaction
-pub fn run() {
+/// This fetches a UTXO automatically and creates the Funding Transaction,
+/// or, given a PSBT from an external wallet, adds the funding output to it.
+/// With `export_psbt` the transaction is written out unsigned instead, and
+/// `signed_psbt` imports it once the external wallet has signed it.
+/// `rbf_feerate` broadcasts it and replaces it with one paying that feerate.
+pub fn run(
+    large_channel: bool,
+    psbt_path: Option<String>,
+    export_psbt: Option<String>,
+    signed_psbt: Option<String>,
+    rbf_feerate: Option<u64>,
+) {
+    let tx_in_amount = 5_000_000;
+
+    if let Err(e) = check_funding_amount(tx_in_amount, large_channel) {
+        eprintln!("❌ Invalid channel size: {:?}", e);
+        return;
+    }
+
+    if let Some(psbt_path) = psbt_path {
+        build_funding_psbt(&psbt_path, tx_in_amount);
+        return;
+    }
+
     // Connect to bitcoind
     let bitcoind = get_bitcoind_client();
+
+    if let Some(signed_psbt) = signed_psbt {
+        import_signed_psbt(bitcoind, &signed_psbt);
+        return;
+    }
     
     // get an unspent output for Funding Transaction
     let tx_input = get_unspent_output(bitcoind.clone());
 
-    let tx_in_amount = 5_000_000;
-    
-    build_funding_tx(bitcoind, tx_input, tx_in_amount);
+    match (export_psbt, rbf_feerate) {
+        (Some(export_path), _) => export_unsigned_funding_psbt(bitcoind, tx_input, tx_in_amount, &export_path),
+        (None, Some(feerate_per_kw)) => demonstrate_funding_rbf(bitcoind, tx_input, feerate_per_kw),
+        (None, None) => build_funding_tx(bitcoind, tx_input, tx_in_amount),
+    }
 }
@@ -0,0 +1,307 @@
+use bitcoin::consensus::encode::serialize_hex;
+use bitcoin::hashes::Hash;
+use bitcoin::script::ScriptBuf;
+use bitcoin::{Network, OutPoint, Transaction, Txid};
+use bitcoin::PublicKey as BitcoinPublicKey;
+use serde_json::json;
+
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::keys::commitment::derive_private_key;
+use crate::keys::derivation::new_keys_manager;
+use crate::scripts::funding::create_funding_script;
+use crate::scripts::htlc::{create_offered_htlc_script, create_received_htlc_script};
+use crate::transactions::commitment::{
+    check_htlc_count, create_commitment_transaction, finalize_holder_commitment_detailed,
+};
+use crate::transactions::fees::is_htlc_dust;
+use crate::transactions::htlc::{
+    create_htlc_success_transaction, create_htlc_success_witness, create_htlc_timeout_transaction,
+    create_htlc_timeout_witness,
+};
+use crate::types::{ChannelKeyManager, CommitmentKeys, HTLCOutput, PaymentHash};
+
+/// One HTLC to put on the generated commitment transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HtlcSpec {
+    pub amount_sat: u64,
+    pub cltv_expiry: u32,
+}
+
+/// Parse an HTLC given on the command line as `<amount_sat>:<cltv_expiry>`
+pub fn parse_htlc_spec(s: &str) -> Result<HtlcSpec, String> {
+    let (amount, cltv_expiry) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected <amount_sat>:<cltv_expiry>, got '{}'", s))?;
+    Ok(HtlcSpec {
+        amount_sat: amount.parse().map_err(|e| format!("invalid amount '{}': {}", amount, e))?,
+        cltv_expiry: cltv_expiry
+            .parse()
+            .map_err(|e| format!("invalid cltv_expiry '{}': {}", cltv_expiry, e))?,
+    })
+}
+
+/// Everything a generated vector set is built from
+#[derive(Debug, Clone)]
+pub struct VectorParams {
+    pub local_seed: [u8; 32],
+    pub remote_seed: [u8; 32],
+    pub funding_txid: Txid,
+    pub funding_output_index: u32,
+    pub funding_amount_sat: u64,
+    pub commitment_number: u64,
+    pub to_self_delay: u16,
+    pub dust_limit_satoshis: u64,
+    pub feerate_per_kw: u64,
+    pub to_local_msat: u64,
+    pub to_remote_msat: u64,
+    pub offered_htlcs: Vec<HtlcSpec>,
+    pub received_htlcs: Vec<HtlcSpec>,
+}
+
+// An untrimmed HTLC and where it ended up on the commitment transaction
+struct PlacedHtlc {
+    htlc_index: usize,
+    offered: bool,
+    htlc: HTLCOutput,
+    payment_preimage: [u8; 32],
+    script: ScriptBuf,
+    commitment_output_index: u32,
+}
+
+fn channel_keys(seed: [u8; 32]) -> ChannelKeyManager {
+    new_keys_manager(seed, Network::Bitcoin).derive_channel_keys(0)
+}
+
+// Signatures are listed without the trailing sighash flag, as in BOLT 3
+fn signature_hex(signature: &[u8]) -> String {
+    hex::encode(&signature[..signature.len() - 1])
+}
+
+/// helper (Not an exercise)
+/// Build and sign the holder commitment transaction described by `params`,
+/// plus every HTLC-timeout/success transaction spending it, and return them
+/// as JSON in the layout of the BOLT 3 appendix vectors.
+///
+/// HTLC `i` (offered first, then received) uses the preimage `[i; 32]`,
+/// like the BOLT 3 appendix. Dust HTLCs are trimmed from the commitment and
+/// get no second-stage transaction.
+pub fn generate_vectors(params: &VectorParams) -> Result<serde_json::Value, String> {
+    let local_keys = channel_keys(params.local_seed);
+    let remote_peer = SimulatedPeer::from_seed(params.remote_seed, 0);
+    let local_public_keys = local_keys.to_public_keys();
+    let remote_public_keys = remote_peer.public_keys();
+    let secp_ctx = &local_keys.secp_ctx;
+
+    let per_commitment_point = local_keys.derive_per_commitment_point(params.commitment_number);
+    let commitment_keys = CommitmentKeys::from_basepoints(
+        &per_commitment_point,
+        &local_public_keys.delayed_payment_basepoint,
+        &local_public_keys.htlc_basepoint,
+        &remote_public_keys.revocation_basepoint,
+        &remote_public_keys.htlc_basepoint,
+        secp_ctx,
+    );
+
+    // Number the HTLCs, derive their preimages and drop the dust ones
+    let mut untrimmed = Vec::new();
+    let htlcs = params
+        .offered_htlcs
+        .iter()
+        .map(|spec| (true, spec))
+        .chain(params.received_htlcs.iter().map(|spec| (false, spec)));
+    for (htlc_index, (offered, spec)) in htlcs.enumerate() {
+        if is_htlc_dust(spec.amount_sat, params.dust_limit_satoshis, params.feerate_per_kw, offered) {
+            continue;
+        }
+        let payment_preimage = [htlc_index as u8; 32];
+        let htlc = HTLCOutput {
+            amount_sat: spec.amount_sat,
+            payment_hash: PaymentHash::from_preimage(&payment_preimage),
+            cltv_expiry: spec.cltv_expiry,
+        };
+        untrimmed.push((htlc_index, offered, htlc, payment_preimage));
+    }
+    let offered: Vec<HTLCOutput> =
+        untrimmed.iter().filter(|(_, offered, ..)| *offered).map(|(_, _, htlc, _)| htlc.clone()).collect();
+    let received: Vec<HTLCOutput> =
+        untrimmed.iter().filter(|(_, offered, ..)| !*offered).map(|(_, _, htlc, _)| htlc.clone()).collect();
+
+    check_htlc_count(&offered, &received).map_err(|e| format!("Too many HTLCs: {:?}", e))?;
+
+    let funding_outpoint = OutPoint::new(params.funding_txid, params.funding_output_index);
+    let tx = create_commitment_transaction(
+        funding_outpoint,
+        params.to_local_msat / 1000,
+        params.to_remote_msat / 1000,
+        &commitment_keys,
+        &local_public_keys.payment_basepoint,
+        &remote_public_keys.payment_basepoint,
+        params.commitment_number,
+        params.to_self_delay,
+        params.dust_limit_satoshis,
+        params.feerate_per_kw,
+        &offered,
+        &received,
+    );
+
+    // Both sides sign the commitment with their funding keys
+    let local_funding_pubkey = BitcoinPublicKey::new(local_public_keys.funding_pubkey);
+    let remote_funding_pubkey = BitcoinPublicKey::new(remote_public_keys.funding_pubkey);
+    let funding_script = create_funding_script(&local_funding_pubkey, &remote_funding_pubkey);
+    let remote_signature =
+        remote_peer.sign_holder_commitment(&tx, 0, &funding_script, params.funding_amount_sat);
+    let local_sig_first =
+        local_funding_pubkey.inner.serialize() < remote_funding_pubkey.inner.serialize();
+    let signed = finalize_holder_commitment_detailed(
+        channel_keys(params.local_seed),
+        tx,
+        0,
+        &funding_script,
+        params.funding_amount_sat,
+        remote_signature,
+        local_sig_first,
+    )
+    .map_err(|e| format!("Could not finalize the commitment transaction: {:?}", e))?;
+    let commitment_tx = signed.tx;
+
+    // Locate each HTLC output, in commitment output order
+    let mut placed: Vec<PlacedHtlc> = untrimmed
+        .into_iter()
+        .map(|(htlc_index, offered, htlc, payment_preimage)| {
+            let script = if offered {
+                create_offered_htlc_script(
+                    &commitment_keys.revocation_key,
+                    &commitment_keys.local_htlc_key,
+                    &commitment_keys.remote_htlc_key,
+                    &htlc.payment_hash,
+                )
+            } else {
+                create_received_htlc_script(
+                    &commitment_keys.revocation_key,
+                    &commitment_keys.local_htlc_key,
+                    &commitment_keys.remote_htlc_key,
+                    &htlc.payment_hash,
+                    htlc.cltv_expiry,
+                )
+            };
+            let script_pubkey = script.to_p2wsh();
+            let commitment_output_index = commitment_tx
+                .output
+                .iter()
+                .position(|output| output.script_pubkey == script_pubkey)
+                .expect("untrimmed HTLC has an output") as u32;
+            PlacedHtlc { htlc_index, offered, htlc, payment_preimage, script, commitment_output_index }
+        })
+        .collect();
+    placed.sort_by_key(|htlc| htlc.commitment_output_index);
+
+    let local_htlc_privkey =
+        derive_private_key(&local_keys.htlc_basepoint_secret, &per_commitment_point, secp_ctx);
+    let commitment_txid = commitment_tx.compute_txid();
+
+    let htlc_txs: Vec<serde_json::Value> = placed
+        .iter()
+        .map(|placed| {
+            let htlc_outpoint = OutPoint::new(commitment_txid, placed.commitment_output_index);
+            let mut tx: Transaction = if placed.offered {
+                create_htlc_timeout_transaction(
+                    htlc_outpoint,
+                    placed.htlc.amount_sat,
+                    placed.htlc.cltv_expiry,
+                    &commitment_keys,
+                    params.to_self_delay,
+                    params.feerate_per_kw,
+                )
+            } else {
+                create_htlc_success_transaction(
+                    htlc_outpoint,
+                    placed.htlc.amount_sat,
+                    &commitment_keys,
+                    params.to_self_delay,
+                    params.feerate_per_kw,
+                )
+            };
+
+            let remote_htlc_signature = remote_peer.sign_htlc_transaction(
+                &tx,
+                0,
+                &placed.script,
+                placed.htlc.amount_sat,
+                &per_commitment_point,
+            );
+            let local_htlc_signature = local_keys.sign_transaction_input_sighash_all(
+                &tx,
+                0,
+                &placed.script,
+                placed.htlc.amount_sat,
+                &local_htlc_privkey,
+            );
+            tx.input[0].witness = if placed.offered {
+                create_htlc_timeout_witness(&remote_htlc_signature, &local_htlc_signature, &placed.script)
+            } else {
+                create_htlc_success_witness(
+                    &remote_htlc_signature,
+                    &local_htlc_signature,
+                    &placed.payment_preimage,
+                    &placed.script,
+                )
+            };
+
+            let kind = if placed.offered { "timeout" } else { "success" };
+            json!({
+                "name": format!("htlc-{} #{}", kind, placed.htlc_index),
+                "htlc_index": placed.htlc_index,
+                "kind": kind,
+                "commitment_output_index": placed.commitment_output_index,
+                "amount_sat": placed.htlc.amount_sat,
+                "cltv_expiry": placed.htlc.cltv_expiry,
+                "payment_preimage": hex::encode(placed.payment_preimage),
+                "remote_htlc_signature": signature_hex(&remote_htlc_signature),
+                "local_htlc_signature": signature_hex(&local_htlc_signature),
+                "expected_tx_hex": serialize_hex(&tx),
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "local_funding_pubkey": local_public_keys.funding_pubkey.to_string(),
+        "remote_funding_pubkey": remote_public_keys.funding_pubkey.to_string(),
+        "local_payment_basepoint": local_public_keys.payment_basepoint.to_string(),
+        "remote_payment_basepoint": remote_public_keys.payment_basepoint.to_string(),
+        "funding_txid": params.funding_txid.to_string(),
+        "funding_output_index": params.funding_output_index,
+        "funding_amount_satoshi": params.funding_amount_sat,
+        "commitment_number": params.commitment_number,
+        "local_delay": params.to_self_delay,
+        "local_dust_limit_satoshi": params.dust_limit_satoshis,
+        "feerate_per_kw": params.feerate_per_kw,
+        "to_local_msat": params.to_local_msat,
+        "to_remote_msat": params.to_remote_msat,
+        "per_commitment_point": per_commitment_point.to_string(),
+        "remote_signature": signature_hex(&signed.remote_sig),
+        "local_signature": signature_hex(&signed.local_sig),
+        "output_commit_tx": serialize_hex(&commitment_tx),
+        "htlc_txs": htlc_txs,
+    }))
+}
+
+/// Interactive CLI function to generate a custom BOLT 3 style vector set.
+/// Prints the JSON, or writes it to `output` if given.
+pub fn run(params: VectorParams, output: Option<String>) {
+    let vectors = match generate_vectors(&params) {
+        Ok(vectors) => vectors,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return;
+        }
+    };
+    let json = serde_json::to_string_pretty(&vectors).expect("vectors serialize");
+
+    match output {
+        Some(path) => match std::fs::write(&path, json + "\n") {
+            Ok(()) => println!("\n✅ Wrote vectors to {}\n", path),
+            Err(e) => eprintln!("❌ Could not write {}: {}", path, e),
+        },
+        None => println!("{}", json),
+    }
+}
@@ -0,0 +1,90 @@
+use bitcoin::hashes::hash160::Hash as Hash160;
+use bitcoin::hashes::sha256::Hash as Sha256;
+use bitcoin::hashes::Hash;
+use clap::ValueEnum;
+
+use crate::internal::hex_utils::{parse_hex, parse_preimage, ParseError};
+use crate::types::{payment_hash160, PaymentHash};
+
+/// How the `--input` argument should be turned into bytes before hashing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputEncoding {
+    /// Input is a hex string (e.g. a witness script or 32-byte preimage)
+    Hex,
+    /// Input is a plain string, hashed as its UTF-8 bytes
+    Utf8,
+}
+
+/// Hash functions exposed by the `hash` subcommand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HashAlgo {
+    /// SHA256(input)
+    Sha256,
+    /// RIPEMD160(SHA256(input)), also accepted as `ripemd-sha`
+    #[value(alias = "ripemd-sha")]
+    Hash160,
+}
+
+/// Convert the CLI input into raw bytes according to the requested encoding
+pub fn decode_input(input: &str, encoding: InputEncoding) -> Result<Vec<u8>, ParseError> {
+    match encoding {
+        InputEncoding::Hex => parse_hex(input),
+        InputEncoding::Utf8 => Ok(input.as_bytes().to_vec()),
+    }
+}
+
+/// Hash `data` with the selected algorithm
+pub fn hash_bytes(data: &[u8], algo: HashAlgo) -> Vec<u8> {
+    match algo {
+        HashAlgo::Sha256 => Sha256::hash(data).to_byte_array().to_vec(),
+        HashAlgo::Hash160 => Hash160::hash(data).to_byte_array().to_vec(),
+    }
+}
+
+/// Returns (payment_hash, RIPEMD160(payment_hash)) for a preimage.
+/// The second value is what the offered/received HTLC scripts commit to.
+pub fn payment_hashes(preimage: &[u8; 32]) -> (PaymentHash, [u8; 20]) {
+    let payment_hash = PaymentHash::from_preimage(preimage);
+    let hash160 = payment_hash160(&payment_hash);
+    (payment_hash, hash160)
+}
+
+/// Interactive CLI function for the unified `hash` subcommand
+pub fn run(input: String, encoding: InputEncoding, algo: HashAlgo, payment_hash: bool) {
+    if payment_hash {
+        // A preimage must be exactly 32 bytes, as in update_fulfill_htlc
+        let preimage = match encoding {
+            InputEncoding::Hex => parse_preimage(&input),
+            InputEncoding::Utf8 => input
+                .as_bytes()
+                .try_into()
+                .map_err(|_| ParseError::WrongLength { expected: 32, actual: input.len() }),
+        };
+        let preimage = match preimage {
+            Ok(preimage) => preimage,
+            Err(e) => {
+                eprintln!("❌ Could not decode input as a payment preimage: {}", e);
+                return;
+            }
+        };
+        let (hash, hash160) = payment_hashes(&preimage);
+        println!("Preimage (hex): {}", hex::encode(preimage));
+        println!("Payment Hash SHA256: {}", hex::encode(hash.as_bytes()));
+        println!("RIPEMD160(Payment Hash): {}", hex::encode(hash160));
+        return;
+    }
+
+    let data = match decode_input(&input, encoding) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("❌ Could not decode input as hex: {}", e);
+            return;
+        }
+    };
+
+    let label = match algo {
+        HashAlgo::Sha256 => "SHA256",
+        HashAlgo::Hash160 => "HASH160 (RIPEMD160(SHA256()))",
+    };
+    println!("{} Hash: {}", label, hex::encode(hash_bytes(&data, algo)));
+}
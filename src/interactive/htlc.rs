@@ -1,22 +1,22 @@
 use crate::internal::bitcoind_client::{get_bitcoind_client, BitcoindClient};
-use crate::internal::helper::get_outpoint;
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::internal::helper::{check_mempool_accept, print_outputs, print_tx_link, get_outpoint};
 use crate::keys::derivation::new_keys_manager;
 use crate::scripts::funding::create_funding_script;
-use crate::transactions::commitment::{finalize_holder_commitment};
+use crate::transactions::commitment::{finalize_holder_commitment_detailed};
 use crate::transactions::commitment::create_commitment_transaction;
-use crate::types::{CommitmentKeys, ChannelKeyManager, KeyFamily, HTLCOutput};
+use crate::types::{CommitmentKeys, ChannelKeyManager, KeyFamily, HTLCOutput, PaymentHash};
 use bitcoin::consensus::encode::serialize_hex;
-use bitcoin::hashes::sha256::Hash as Sha256;
 use bitcoin::hashes::{sha256, Hash};
 use bitcoin::locktime::absolute::LockTime;
 use bitcoin::script::ScriptBuf;
 use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
 use bitcoin::transaction::Version;
 use bitcoin::Network;
-use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
 use bitcoin::PublicKey as BitcoinPublicKey;
 
-pub fn run(funding_txid: String) {
+pub fn run(funding_txid: Txid) {
     // Parse the argument as txid
     let txid = funding_txid;
 
@@ -24,7 +24,6 @@ pub fn run(funding_txid: String) {
     let bitcoind = get_bitcoind_client();
 
     let our_seed = [0x01; 32];
-    let remote_seed = [0x02; 32];
     let bitcoin_network = Network::Bitcoin;
     let channel_index = 0;
     let secp_ctx = Secp256k1::new();
@@ -40,12 +39,10 @@ pub fn run(funding_txid: String) {
     let local_payment_basepoint = our_channel_public_keys.payment_basepoint;
     let second_commitment_point = our_channel_keys_manager.derive_per_commitment_point(commitment_number);
 
-    // Get our Counterparty keys
-    let remote_node_keys_manager = new_keys_manager(remote_seed, bitcoin_network);
-    let remote_channel_keys_manager = remote_node_keys_manager.derive_channel_keys(channel_index);
-    let remote_channel_public_keys = remote_channel_keys_manager.to_public_keys();
+    // Get our Counterparty's public keys (all a real peer would tell us)
+    let remote_peer = SimulatedPeer::new();
+    let remote_channel_public_keys = remote_peer.public_keys();
     let remote_payment_basepoint = remote_channel_public_keys.payment_basepoint;
-    let remote_funding_privkey = remote_channel_keys_manager.funding_key;
     let remote_funding_pubkey = BitcoinPublicKey::new(remote_channel_public_keys.funding_pubkey);
 
     // Get our commitment keys
@@ -61,14 +58,14 @@ pub fn run(funding_txid: String) {
     );
 
     let txid_index = 0;
-    let funding_outpoint = get_outpoint(txid.to_string(), txid_index);
+    let funding_outpoint = get_outpoint(txid, txid_index);
 
     let funding_amount = 5_000_000;
     let to_local_value = 4_594_500;
     let to_remote_value = 500;
     let to_self_delay = 144;
     let feerate_per_kw = 1117;
-    let payment_hash = Sha256::hash(&[0u8; 32]).to_byte_array();
+    let payment_hash = PaymentHash::from_preimage(&[0u8; 32]);
     let mut offered_htlcs: Vec<HTLCOutput> = Vec::new();
     offered_htlcs.push(HTLCOutput {
         amount_sat: 405_000,
@@ -97,19 +94,18 @@ pub fn run(funding_txid: String) {
     let funding_script = create_funding_script(&local_funding_pubkey, &remote_funding_pubkey);
 
     // Step 2: In real Lightning, we would send this transaction to our counterparty
-    // and they would send us back their signature. Here we simulate that by
-    // creating their signature ourselves (but in reality we wouldn't have their key!)
-    let remote_funding_signature = remote_channel_keys_manager.sign_transaction_input_sighash_all(
+    // and they would send us back their signature (commitment_signed). Here the
+    // simulated peer signs with its own key, which we never see.
+    let remote_funding_signature = remote_peer.sign_holder_commitment(
         &tx,
         0,
         &funding_script,
         funding_amount,
-        &remote_funding_privkey,
     );
 
     let local_sig_first = true;
 
-    let signed_tx = finalize_holder_commitment(
+    let signed = finalize_holder_commitment_detailed(
         our_channel_keys_manager,
         tx,
         0,
@@ -117,9 +113,21 @@ pub fn run(funding_txid: String) {
         funding_amount,
         remote_funding_signature,
         local_sig_first);
+    let signed = match signed {
+        Ok(signed) => signed,
+        Err(e) => {
+            eprintln!("❌ Could not finalize the commitment transaction: {:?}", e);
+            return;
+        }
+    };
+    let signed_tx = signed.tx;
 
     println!("\n✅ Commitment Transaction Created\n");
     println!("Tx ID: {}", signed_tx.compute_txid());
+    println!("Fee: {} sats for {} weight units", signed.fee, signed.weight);
+    print_tx_link(&signed_tx.compute_txid());
     println!("\nTx Hex: {}", serialize_hex(&signed_tx));
+    print_outputs(&signed_tx);
+    check_mempool_accept(&bitcoind, &signed_tx);
     println!();
 }
@@ -1,21 +1,22 @@
 use crate::internal::bitcoind_client::{get_bitcoind_client, BitcoindClient};
-use crate::internal::helper::get_outpoint;
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::internal::height::blocks_until_cltv_expiry;
+use crate::internal::helper::{check_mempool_accept, print_outputs, print_tx_link, get_outpoint};
 use crate::keys::derivation::new_keys_manager;
 use crate::scripts::funding::create_funding_script;
 use crate::scripts::htlc::create_offered_htlc_script;
 use crate::keys::commitment::{derive_private_key};
 use crate::transactions::htlc::{create_htlc_timeout_transaction, finalize_htlc_timeout};
-use crate::types::{CommitmentKeys,ChannelKeyManager, KeyFamily};
+use crate::types::{ChannelType, CommitmentKeys, ChannelKeyManager, KeyFamily, PaymentHash};
 use bitcoin::consensus::encode::serialize_hex;
-use bitcoin::hashes::sha256::Hash as Sha256;
 use bitcoin::hashes::{sha256, Hash};
 use bitcoin::locktime::absolute::LockTime;
 use bitcoin::script::ScriptBuf;
 use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
 use bitcoin::transaction::Version;
 use bitcoin::Network;
-use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
-pub fn run(commitment_txid: String) {
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+pub fn run(commitment_txid: Txid) {
     // Parse the argument as txid
     let txid = commitment_txid;
 
@@ -23,7 +24,6 @@ pub fn run(commitment_txid: String) {
     let bitcoind = get_bitcoind_client();
 
     let our_seed = [0x01; 32];
-    let remote_seed = [0x02; 32];
     let bitcoin_network = Network::Bitcoin;
     let channel_index = 0;
     let secp_ctx = Secp256k1::new();
@@ -45,20 +45,11 @@ pub fn run(commitment_txid: String) {
                                 &secp_ctx,
                                 );
 
-    // Get our Counterparty keys
-    let remote_node_keys_manager = new_keys_manager(remote_seed, bitcoin_network);
-    let remote_channel_keys_manager = remote_node_keys_manager.derive_channel_keys(channel_index);
-    let remote_channel_public_keys = remote_channel_keys_manager.to_public_keys();
+    // Get our Counterparty's public keys (all a real peer would tell us)
+    let remote_peer = SimulatedPeer::new();
+    let remote_channel_public_keys = remote_peer.public_keys();
     let remote_payment_pubkey = remote_channel_public_keys.payment_basepoint;
-    let remote_funding_privkey = remote_channel_keys_manager.funding_key;
     let remote_funding_pubkey = remote_channel_public_keys.funding_pubkey;
-    
-    let remote_htlc_basepoint_secret = remote_channel_keys_manager.htlc_basepoint_secret;
-    let remote_htlc_secret = derive_private_key(
-                                &remote_htlc_basepoint_secret,
-                                &second_commitment_point,
-                                &secp_ctx,
-                                );
 
     // Get our commitment keys
     // we need the remote basepoints for revocation and htlc,
@@ -73,14 +64,14 @@ pub fn run(commitment_txid: String) {
     );
 
     let txid_index = 1;
-    let htlc_outpoint = get_outpoint(txid.to_string(), txid_index);
+    let htlc_outpoint = get_outpoint(txid, txid_index);
 
     let htlc_input_amount = 405_000;
     let htlc_timeout_amount = 404_000;
     let cltv_expiry = 200;
     let to_self_delay = 144;
     let feerate_per_kw = 1117;
-    let payment_hash = Sha256::hash(&[0u8; 32]).to_byte_array();
+    let payment_hash = PaymentHash::from_preimage(&[0u8; 32]);
 
     // Create the HTLC script that we're spending from
     let htlc_script = create_offered_htlc_script(
@@ -105,14 +96,14 @@ pub fn run(commitment_txid: String) {
     let input_index = 0;
 
     // Step 2: In real Lightning, we would send this transaction to our counterparty
-    // and they would send us back their signature. Here we simulate that by
-    // creating their signature ourselves (but in reality we wouldn't have their key!)
-    let remote_htlc_signature = remote_channel_keys_manager.sign_transaction_input_sighash_all(
+    // and they would send us back their signature (commitment_signed). Here the
+    // simulated peer signs with its own HTLC key, which we never see.
+    let remote_htlc_signature = remote_peer.sign_htlc_transaction(
         &tx,
         input_index,
         &htlc_script,
         htlc_input_amount,
-        &remote_htlc_secret,
+        &second_commitment_point,
     );
 
     let signed_tx = finalize_htlc_timeout(
@@ -122,11 +113,20 @@ pub fn run(commitment_txid: String) {
         &htlc_script,
         htlc_input_amount,
         remote_htlc_signature,
-        local_htlc_secret);
+        local_htlc_secret,
+        ChannelType::StaticRemoteKey);
 
 
     println!("\n✅ HTLC Timeout Transaction Created\n");
     println!("Tx ID: {}", signed_tx.compute_txid());
+    print_tx_link(&signed_tx.compute_txid());
     println!("\nTx Hex: {}", serialize_hex(&signed_tx));
+    print_outputs(&signed_tx);
+
+    let blocks_to_go = blocks_until_cltv_expiry(&bitcoind, cltv_expiry);
+    if blocks_to_go > 0 {
+        println!("\n⏳ cltv_expiry {} is {} block(s) away. Mine them before broadcasting.", cltv_expiry, blocks_to_go);
+    }
+    check_mempool_accept(&bitcoind, &signed_tx);
     println!();
 }
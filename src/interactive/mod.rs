@@ -4,10 +4,15 @@
 
 pub mod commitment;
 pub mod funding;
+pub mod gen_vectors;
+pub mod hash;
 pub mod htlc;
 pub mod htlc_timeout;
+pub mod reorg;
 pub mod simple_htlc;
 pub mod simple_htlc_claim;
+pub mod tx_info;
+pub mod utxos;
 
 // Re-export commonly used functions for convenience
 pub use funding::run as funding_run;
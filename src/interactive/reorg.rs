@@ -0,0 +1,93 @@
+use bitcoin::BlockHash;
+
+use crate::internal::bitcoind_client::{get_bitcoind_client, BitcoindClient};
+
+// Mine `num_blocks` replacement blocks on top of the current tip
+fn mine_replacement_chain(
+    bitcoind: &BitcoindClient,
+    num_blocks: u64,
+) -> Result<Vec<BlockHash>, Box<dyn std::error::Error>> {
+    let address = bitcoind.get_new_address()?;
+    bitcoind.generate_to_address(num_blocks, &address)
+}
+
+/// Interactive CLI function to simulate a chain reorganization on regtest.
+/// Disconnects the top `depth` blocks and, unless `no_mine` is set, mines a
+/// longer replacement chain so the original blocks are reorged out.
+///
+/// If mining the replacement chain fails, the disconnected blocks are
+/// reconsidered so the node is not left stuck on a shorter chain.
+pub fn run(depth: u64, no_mine: bool) {
+    let bitcoind = get_bitcoind_client();
+
+    let tip_height = match bitcoind.get_block_count() {
+        Ok(height) => height,
+        Err(e) => {
+            eprintln!("❌ getblockcount failed: {}", e);
+            return;
+        }
+    };
+    if depth == 0 || depth > tip_height {
+        eprintln!("❌ Depth must be between 1 and the current height ({})", tip_height);
+        return;
+    }
+
+    let fork_height = tip_height - depth + 1;
+    let mut disconnected = Vec::new();
+    for height in fork_height..=tip_height {
+        match bitcoind.get_block_hash(height) {
+            Ok(block_hash) => disconnected.push(block_hash),
+            Err(e) => {
+                eprintln!("❌ getblockhash {} failed: {}", height, e);
+                return;
+            }
+        }
+    }
+    let fork_block = disconnected[0];
+
+    println!("\n🔀 Simulating a {}-block reorg\n", depth);
+    println!("Old tip height: {}", tip_height);
+    for (height, block_hash) in (fork_height..=tip_height).zip(&disconnected) {
+        println!("  Disconnecting block {}: {}", height, block_hash);
+    }
+
+    // Invalidating the lowest block disconnects everything built on it
+    if let Err(e) = bitcoind.invalidate_block(&fork_block) {
+        eprintln!("❌ invalidateblock {} failed: {}", fork_block, e);
+        return;
+    }
+    match bitcoind.get_block_count() {
+        Ok(height) => println!("\nHeight after invalidateblock: {}", height),
+        Err(e) => println!("\n⚠️  Could not read the height after invalidateblock: {}", e),
+    }
+
+    if !no_mine {
+        let new_blocks = match mine_replacement_chain(&bitcoind, depth + 1) {
+            Ok(new_blocks) => new_blocks,
+            Err(e) => {
+                eprintln!("❌ Could not mine the replacement chain: {}", e);
+                match bitcoind.reconsider_block(&fork_block) {
+                    Ok(()) => eprintln!("   Reconsidered {}, the old chain is restored", fork_block),
+                    Err(e) => eprintln!(
+                        "   reconsiderblock {} also failed ({}), run it by hand",
+                        fork_block, e
+                    ),
+                }
+                return;
+            }
+        };
+        println!("\nMined {} replacement blocks:", new_blocks.len());
+        for block_hash in &new_blocks {
+            println!("  {}", block_hash);
+        }
+        match bitcoind.get_block_count() {
+            Ok(height) => println!("\nNew tip height: {}", height),
+            Err(e) => println!("\n⚠️  Could not read the new tip height: {}", e),
+        }
+    }
+
+    println!("\nTransactions from the disconnected blocks are back in the mempool");
+    println!("(unless they were re-mined). To restore the old chain instead, run:");
+    println!("  bitcoin-cli reconsiderblock {}", fork_block);
+    println!();
+}
@@ -9,7 +9,7 @@ use bitcoin::script::{Builder};
 use bitcoin::transaction::Version;
 use bitcoin::locktime::absolute::LockTime;
 use bitcoin::consensus::encode::serialize_hex;
-use crate::internal::helper::{get_unspent_output, sign_raw_transaction};
+use crate::internal::helper::{get_unspent_output, sign_raw_transaction, check_mempool_accept, print_outputs, print_tx_link};
 use crate::internal::bitcoind_client::{BitcoindClient, get_bitcoind_client};
 use crate::scripts::funding::create_funding_script;
 use crate::keys::derivation::new_keys_manager;
@@ -91,7 +91,10 @@ pub fn build_simple_htlc_tx(
 
     println!("\n✅ Simple HTLC Transaction Created\n");
     println!("Tx ID: {}", signed_tx.compute_txid());
+    print_tx_link(&signed_tx.compute_txid());
     println!("\nTx Hex: {}", serialize_hex(&signed_tx));
+    print_outputs(&signed_tx);
+    check_mempool_accept(&bitcoind, &signed_tx);
     println!();
 }
 
@@ -1,4 +1,4 @@
-use bitcoin::{Transaction, TxIn, TxOut, OutPoint, Sequence, Witness, Amount};
+use bitcoin::{Transaction, TxIn, TxOut, OutPoint, Sequence, Witness, Amount, Txid};
 use bitcoin::script::ScriptBuf;
 use bitcoin::hashes::sha256::Hash as Sha256;
 use bitcoin::blockdata::opcodes::all as opcodes;
@@ -9,7 +9,7 @@ use bitcoin::script::{Builder};
 use bitcoin::transaction::Version;
 use bitcoin::locktime::absolute::LockTime;
 use bitcoin::consensus::encode::serialize_hex;
-use crate::internal::helper::{get_unspent_output, get_outpoint, sign_raw_transaction};
+use crate::internal::helper::{get_unspent_output, get_outpoint, sign_raw_transaction, check_mempool_accept, print_outputs, print_tx_link};
 use crate::internal::bitcoind_client::{BitcoindClient, get_bitcoind_client};
 use crate::scripts::funding::create_funding_script;
 use crate::keys::derivation::new_keys_manager;
@@ -25,7 +25,7 @@ use bitcoin::secp256k1::Message;
 
 pub fn build_simple_htlc_spend_tx(
     bitcoind: BitcoindClient,
-    txid: String,
+    txid: Txid,
     htlc_amount_sat: u64,
 ) { 
     let alice_seed = [0x01; 32];
@@ -46,7 +46,7 @@ pub fn build_simple_htlc_spend_tx(
         PublicKey::from_secret_key(&secp_ctx, &bob_privkey));
 
 
-    let simple_htlc_outpoint = get_outpoint(txid.to_string(), txid_index);
+    let simple_htlc_outpoint = get_outpoint(txid, txid_index);
 
     let output_script = ScriptBuf::new_p2wpkh(&bob_pubkey.wpubkey_hash().unwrap());
 
@@ -128,19 +128,22 @@ pub fn build_simple_htlc_spend_tx(
 
     println!("\n✅ Simple HTLC Transaction Created\n");
     println!("Tx ID: {}", signed_tx.compute_txid());
+    print_tx_link(&signed_tx.compute_txid());
     println!("\nTx Hex: {}", serialize_hex(&signed_tx));
+    print_outputs(&signed_tx);
+    check_mempool_accept(&bitcoind, &signed_tx);
     println!();
 }
 
 /// Interactive CLI function to create a Funding Transaction
 /// This fetches a UTXO automatically and creates the Funding Transaction
-pub fn run(simple_htlc_txid: String) {
+pub fn run(simple_htlc_txid: Txid) {
     // Connect to bitcoind
     let bitcoind = get_bitcoind_client();
 
     let htlc_amount_sat = 405_000;
 
-    build_simple_htlc_spend_tx(bitcoind, simple_htlc_txid.clone(), htlc_amount_sat);
+    build_simple_htlc_spend_tx(bitcoind, simple_htlc_txid, htlc_amount_sat);
 }
 
 
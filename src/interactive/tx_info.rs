@@ -0,0 +1,210 @@
+use bitcoin::blockdata::opcodes::all as opcodes;
+use bitcoin::consensus::encode;
+use bitcoin::script::{Instruction, Script};
+use bitcoin::secp256k1::PublicKey;
+use bitcoin::{Network, Transaction, Witness};
+
+use crate::internal::bitcoind_client::get_bitcoind_client;
+use crate::internal::explorer::{address_url, explorer_base_url};
+use crate::internal::helper::print_tx_link;
+use crate::internal::hex_utils::parse_hex;
+use crate::internal::script_utils::{address_for_script, display_script};
+use crate::transactions::commitment::recover_commitment_number;
+
+/// Sum of the previous output values spent by `tx`, if every one is known
+fn total_input_value(tx: &Transaction, prevout_values: &[u64], lookup: bool) -> Option<u64> {
+    if !prevout_values.is_empty() {
+        if prevout_values.len() != tx.input.len() {
+            println!(
+                "⚠️  Got {} prevout values for {} inputs, skipping fee calculation",
+                prevout_values.len(),
+                tx.input.len()
+            );
+            return None;
+        }
+        return Some(prevout_values.iter().sum());
+    }
+
+    if !lookup {
+        return None;
+    }
+
+    // Ask bitcoind (txindex=1) for each parent transaction
+    let bitcoind = get_bitcoind_client();
+    let mut total = 0;
+    for input in &tx.input {
+        let prev = &input.previous_output;
+        match bitcoind.get_raw_transaction(&prev.txid) {
+            Ok(parent) => match parent.output.get(prev.vout as usize) {
+                Some(out) => total += out.value.to_sat(),
+                None => {
+                    println!("⚠️  Parent {} has no output {}", prev.txid, prev.vout);
+                    return None;
+                }
+            },
+            Err(e) => {
+                println!("⚠️  Could not look up parent {}: {}", prev.txid, e);
+                return None;
+            }
+        }
+    }
+    Some(total)
+}
+
+/// Fee paid by a transaction, or `None` if its outputs spend more than its
+/// inputs provide (a negative fee, which no node would accept)
+pub fn transaction_fee(total_input: u64, total_output: u64) -> Option<u64> {
+    total_input.checked_sub(total_output)
+}
+
+/// Recover the commitment number BOLT 3 hides in nLockTime and nSequence.
+/// Returns `None` if the transaction does not carry the 0x20/0x80 prefixes.
+pub fn decode_commitment_number(
+    tx: &Transaction,
+    initiator_payment_basepoint: &PublicKey,
+    receiver_payment_basepoint: &PublicKey,
+) -> Option<u64> {
+    recover_commitment_number(
+        tx.lock_time,
+        tx.input.first()?.sequence,
+        initiator_payment_basepoint,
+        receiver_payment_basepoint,
+    )
+}
+
+/// Which spending path an input takes, judged from the shape of its witness
+pub fn describe_witness(witness: &Witness) -> &'static str {
+    let items: Vec<&[u8]> = witness.iter().collect();
+    let script = items.last().copied().unwrap_or_default();
+    let is_htlc_script = script.first() == Some(&opcodes::OP_DUP.to_u8());
+    let is_to_local_script = script.first() == Some(&opcodes::OP_IF.to_u8());
+    let is_multisig = script.last() == Some(&opcodes::OP_CHECKMULTISIG.to_u8());
+
+    match items.as_slice() {
+        [] => "no witness (legacy input)",
+        [_, pubkey] if pubkey.len() == 33 => "P2WPKH key spend",
+        [dummy, _, _, _] if dummy.is_empty() && is_multisig => {
+            "2-of-2 funding output (commitment or closing tx)"
+        }
+        [dummy, _, _, selector, _] if dummy.is_empty() && is_htlc_script => {
+            match selector.len() {
+                32 => "HTLC-success: received HTLC claimed with the preimage",
+                0 => "HTLC-timeout: offered HTLC reclaimed after cltv_expiry",
+                _ => "HTLC output (unrecognised branch)",
+            }
+        }
+        [_, selector, _] if is_to_local_script => match *selector {
+            [0x01] => "to_local revocation (penalty)",
+            [] => "to_local after to_self_delay",
+            _ => "to_local (unrecognised branch)",
+        },
+        [_, selector, _] if is_htlc_script => match selector.len() {
+            33 => "HTLC revocation (penalty)",
+            32 => "offered HTLC claimed by the counterparty with the preimage",
+            0 => "received HTLC reclaimed by the counterparty after cltv_expiry",
+            _ => "HTLC output (unrecognised branch)",
+        },
+        _ => "unrecognised witness",
+    }
+}
+
+/// The relative delays (`<n> OP_CHECKSEQUENCEVERIFY`) a script enforces
+pub fn csv_delays(script: &Script) -> Vec<i64> {
+    let instructions: Vec<_> = script.instructions().filter_map(Result::ok).collect();
+    instructions
+        .windows(2)
+        .filter(|pair| pair[1] == Instruction::Op(opcodes::OP_CSV))
+        .filter_map(|pair| pair[0].script_num())
+        .collect()
+}
+
+/// Interactive CLI function to print weight and fee information for a transaction
+pub fn run(
+    tx_hex: String,
+    prevout_values: Vec<u64>,
+    lookup: bool,
+    payment_basepoints: Option<(PublicKey, PublicKey)>,
+    network: Network,
+) {
+    let tx: Transaction = match parse_hex(&tx_hex)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| encode::deserialize(&bytes).map_err(|e| e.to_string()))
+    {
+        Ok(tx) => tx,
+        Err(e) => {
+            eprintln!("❌ Could not decode transaction: {}", e);
+            return;
+        }
+    };
+
+    let weight = tx.weight().to_wu();
+    let vsize = tx.vsize();
+
+    println!("\n📦 Transaction Info\n");
+    println!("Tx ID: {}", tx.compute_txid());
+    print_tx_link(&tx.compute_txid());
+    println!("Weight: {} wu", weight);
+    println!("Virtual Size: {} vB", vsize);
+
+    println!("\nInputs: {}", tx.input.len());
+    for (i, input) in tx.input.iter().enumerate() {
+        println!(
+            "  #{} {}:{} witness items: {}, witness size: {} bytes",
+            i,
+            input.previous_output.txid,
+            input.previous_output.vout,
+            input.witness.len(),
+            input.witness.size()
+        );
+        println!("     spends: {}", describe_witness(&input.witness));
+        if let Some(script) = input.witness.last() {
+            for delay in csv_delays(Script::from_bytes(script)) {
+                println!("     script enforces a {}-block relative delay (OP_CSV)", delay);
+            }
+        }
+    }
+
+    if let Some((initiator, receiver)) = payment_basepoints {
+        match decode_commitment_number(&tx, &initiator, &receiver) {
+            Some(number) => println!("\nCommitment Number: {}", number),
+            None => println!("\nNot a commitment transaction (no 0x20/0x80 locktime/sequence prefix)"),
+        }
+    }
+
+    let base_url = explorer_base_url();
+    let total_output: u64 = tx.output.iter().map(|out| out.value.to_sat()).sum();
+    println!("\nOutputs: {}", tx.output.len());
+    for (i, output) in tx.output.iter().enumerate() {
+        println!(
+            "  #{} {} sats -> {}",
+            i,
+            output.value.to_sat(),
+            display_script(&output.script_pubkey, network)
+        );
+        if let Some(address) = address_for_script(&output.script_pubkey, network) {
+            println!("     {}", address_url(&base_url, &address));
+        }
+    }
+    println!("Total Output Value: {} sats", total_output);
+
+    match total_input_value(&tx, &prevout_values, lookup) {
+        Some(total_input) => {
+            println!("\nTotal Input Value: {} sats", total_input);
+            match transaction_fee(total_input, total_output) {
+                Some(fee) => {
+                    println!("Fee: {} sats", fee);
+                    println!("Feerate: {:.2} sat/vB", fee as f64 / vsize as f64);
+                    println!("Feerate: {} sat/kw", fee * 1000 / weight);
+                }
+                None => println!(
+                    "❌ Invalid: outputs exceed inputs by {} sats (negative fee)",
+                    total_output - total_input
+                ),
+            }
+        }
+        None => {
+            println!("\nPass --prevout-values or --lookup to calculate the fee");
+        }
+    }
+    println!();
+}
@@ -0,0 +1,49 @@
+use crate::internal::bitcoind_client::get_bitcoind_client;
+
+/// Interactive CLI function to inspect the wallet's UTXOs.
+/// With `locked`, lists the outputs reserved by earlier `Funding` runs;
+/// `unlock_all` releases them again (e.g. after discarding a funding tx).
+pub fn run(locked: bool, unlock_all: bool) {
+    let bitcoind = get_bitcoind_client();
+
+    if unlock_all {
+        let outpoints = match bitcoind.list_lock_unspent() {
+            Ok(outpoints) => outpoints,
+            Err(e) => {
+                eprintln!("❌ Could not list locked outputs: {}", e);
+                return;
+            }
+        };
+        if outpoints.is_empty() {
+            println!("\nNo locked outputs\n");
+            return;
+        }
+        match bitcoind.unlock_unspent(&outpoints) {
+            Ok(()) => println!("\n🔓 Unlocked {} output(s)\n", outpoints.len()),
+            Err(e) => eprintln!("❌ Could not unlock outputs: {}", e),
+        }
+        return;
+    }
+
+    if locked {
+        match bitcoind.list_lock_unspent() {
+            Ok(outpoints) if outpoints.is_empty() => println!("\nNo locked outputs\n"),
+            Ok(outpoints) => {
+                println!("\n🔒 Locked outputs:");
+                for outpoint in outpoints {
+                    println!("  {}", outpoint);
+                }
+                println!();
+            }
+            Err(e) => eprintln!("❌ Could not list locked outputs: {}", e),
+        }
+        return;
+    }
+
+    let utxos = bitcoind.list_unspent();
+    println!("\nSpendable outputs:");
+    for utxo in utxos.0 {
+        println!("  {}:{} {} sats -> {}", utxo.txid, utxo.vout, utxo.amount, utxo.address);
+    }
+    println!();
+}
@@ -1,7 +1,11 @@
 #![allow(dead_code, unused_imports, unused_variables, unknown_lints, unused_must_use)]
-use bitcoin::Network;
+use bitcoin::consensus::encode;
+use bitcoin::merkle_tree::MerkleBlock;
+use bitcoin::bip158::BlockFilter;
+use bitcoin::{Address, Amount, Block, BlockHash, Network, OutPoint, Transaction, Txid};
+use std::str::FromStr;
 use serde_json;
-use crate::internal::convert::{ListUnspentResponse, SignedTx};
+use crate::internal::convert::{ListUnspentResponse, LockedUtxo, MempoolAcceptResult, SignedTx};
 
 #[derive(Clone)]
 pub struct BitcoindClient {
@@ -37,10 +41,20 @@ impl BitcoindClient {
             "params": params
         });
 
-        let response: serde_json::Value = ureq::post(&self.url)
+        // bitcoind answers RPC errors with an HTTP error status, but the body
+        // still carries the JSON error object we want to surface
+        let response: serde_json::Value = match ureq::post(&self.url)
             .set("Authorization", &format!("Basic {}", self.auth))
-            .send_json(&request_body)?
-            .into_json()?;
+            .send_json(&request_body)
+        {
+            Ok(response) => response.into_json()?,
+            Err(ureq::Error::Status(_, response)) => response.into_json()?,
+            Err(e) => return Err(e.into()),
+        };
+
+        if !response["error"].is_null() {
+            return Err(format!("{} failed: {}", method, response["error"]["message"]).into());
+        }
 
         let result = response["result"].clone();
         Ok(serde_json::from_value(result)?)
@@ -51,6 +65,45 @@ impl BitcoindClient {
             .unwrap()
     }
 
+    /// Lock wallet outputs so `listunspent` (and wallet coin selection) skips
+    /// them. Locks are persisted to the wallet, so they survive a restart.
+    pub fn lock_unspent(&self, outpoints: &[OutPoint]) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_unspent_lock(false, outpoints)
+    }
+
+    /// Release outputs locked with `lock_unspent`
+    pub fn unlock_unspent(&self, outpoints: &[OutPoint]) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_unspent_lock(true, outpoints)
+    }
+
+    fn set_unspent_lock(
+        &self,
+        unlock: bool,
+        outpoints: &[OutPoint],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let outpoints_json: Vec<serde_json::Value> = outpoints
+            .iter()
+            .map(|outpoint| serde_json::json!({ "txid": outpoint.txid.to_string(), "vout": outpoint.vout }))
+            .collect();
+        let changed: bool = self.call_method(
+            "lockunspent",
+            &vec![serde_json::json!(unlock), serde_json::json!(outpoints_json), serde_json::json!(true)],
+        )?;
+        if !changed {
+            return Err("lockunspent did not change the outputs' lock".into());
+        }
+        Ok(())
+    }
+
+    /// Outputs currently locked in the wallet
+    pub fn list_lock_unspent(&self) -> Result<Vec<OutPoint>, Box<dyn std::error::Error>> {
+        let locked: Vec<LockedUtxo> = self.call_method("listlockunspent", &vec![])?;
+        Ok(locked
+            .into_iter()
+            .map(|utxo| OutPoint { txid: utxo.txid, vout: utxo.vout })
+            .collect())
+    }
+
     pub fn sign_raw_transaction_with_wallet(&self, tx_hex: String) -> SignedTx {
         let tx_hex_json = serde_json::json!(tx_hex);
         let signed_tx: SignedTx = self
@@ -58,6 +111,139 @@ impl BitcoindClient {
             .unwrap();
         signed_tx
     }
+
+    pub fn get_raw_transaction(
+        &self,
+        txid: &Txid,
+    ) -> Result<Transaction, Box<dyn std::error::Error>> {
+        let txid_json = serde_json::json!(txid.to_string());
+        let tx_hex: String = self.call_method("getrawtransaction", &vec![txid_json])?;
+        Ok(encode::deserialize(&hex::decode(tx_hex)?)?)
+    }
+
+    pub fn send_raw_transaction(
+        &self,
+        tx: &Transaction,
+    ) -> Result<Txid, Box<dyn std::error::Error>> {
+        let tx_hex_json = serde_json::json!(encode::serialize_hex(tx));
+        let txid: String = self.call_method("sendrawtransaction", &vec![tx_hex_json])?;
+        Ok(Txid::from_str(&txid)?)
+    }
+
+    pub fn test_mempool_accept(
+        &self,
+        txs: &[Transaction],
+    ) -> Result<Vec<MempoolAcceptResult>, Box<dyn std::error::Error>> {
+        let tx_hexes: Vec<String> = txs.iter().map(encode::serialize_hex).collect();
+        let tx_hexes_json = serde_json::json!(tx_hexes);
+        self.call_method("testmempoolaccept", &vec![tx_hexes_json])
+    }
+
+    pub fn send_to_address(&self, address: &Address, amount_sat: u64) -> Txid {
+        let address_json = serde_json::json!(address.to_string());
+        let amount_json = serde_json::json!(Amount::from_sat(amount_sat).to_btc());
+        let txid: String = self
+            .call_method("sendtoaddress", &vec![address_json, amount_json])
+            .unwrap();
+        Txid::from_str(&txid).unwrap()
+    }
+
+    pub fn get_new_address(&self) -> Result<Address, Box<dyn std::error::Error>> {
+        let address: String = self.call_method("getnewaddress", &vec![])?;
+        Ok(Address::from_str(&address)?.assume_checked())
+    }
+
+    pub fn generate_to_address(
+        &self,
+        num_blocks: u64,
+        address: &Address,
+    ) -> Result<Vec<BlockHash>, Box<dyn std::error::Error>> {
+        let num_blocks_json = serde_json::json!(num_blocks);
+        let address_json = serde_json::json!(address.to_string());
+        let block_hashes: Vec<String> =
+            self.call_method("generatetoaddress", &vec![num_blocks_json, address_json])?;
+        let block_hashes = block_hashes
+            .iter()
+            .map(|hash| BlockHash::from_str(hash))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(block_hashes)
+    }
+
+    pub fn get_block_count(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        self.call_method("getblockcount", &vec![])
+    }
+
+    pub fn get_block_hash(&self, height: u64) -> Result<BlockHash, Box<dyn std::error::Error>> {
+        let height_json = serde_json::json!(height);
+        let block_hash: String = self.call_method("getblockhash", &vec![height_json])?;
+        Ok(BlockHash::from_str(&block_hash)?)
+    }
+
+    pub fn get_block(&self, block_hash: &BlockHash) -> Result<Block, Box<dyn std::error::Error>> {
+        let block_hash_json = serde_json::json!(block_hash.to_string());
+        let block_hex: String =
+            self.call_method("getblock", &vec![block_hash_json, serde_json::json!(0)])?;
+        Ok(encode::deserialize(&hex::decode(block_hex)?)?)
+    }
+
+    /// The BIP158 basic filter for a block (needs `blockfilterindex=1`)
+    pub fn get_block_filter(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<BlockFilter, Box<dyn std::error::Error>> {
+        let block_hash_json = serde_json::json!(block_hash.to_string());
+        let result: serde_json::Value = self.call_method("getblockfilter", &vec![block_hash_json])?;
+        let filter_hex = result["filter"].as_str().ok_or("getblockfilter returned no filter")?;
+        Ok(BlockFilter::new(&hex::decode(filter_hex)?))
+    }
+
+    pub fn get_tx_out_proof(
+        &self,
+        txids: &[Txid],
+        block_hash: &BlockHash,
+    ) -> Result<MerkleBlock, Box<dyn std::error::Error>> {
+        let txids: Vec<String> = txids.iter().map(|txid| txid.to_string()).collect();
+        let txids_json = serde_json::json!(txids);
+        let block_hash_json = serde_json::json!(block_hash.to_string());
+        let proof_hex: String =
+            self.call_method("gettxoutproof", &vec![txids_json, block_hash_json])?;
+        Ok(encode::deserialize(&hex::decode(proof_hex)?)?)
+    }
+
+    /// Mine one block containing exactly `txs`, bypassing mempool policy
+    /// (but not consensus rules)
+    pub fn generate_block(
+        &self,
+        address: &Address,
+        txs: &[Transaction],
+    ) -> Result<BlockHash, Box<dyn std::error::Error>> {
+        let address_json = serde_json::json!(address.to_string());
+        let tx_hexes: Vec<String> = txs.iter().map(encode::serialize_hex).collect();
+        let result: serde_json::Value =
+            self.call_method("generateblock", &vec![address_json, serde_json::json!(tx_hexes)])?;
+        let hash = result["hash"].as_str().ok_or("generateblock returned no hash")?;
+        Ok(BlockHash::from_str(hash)?)
+    }
+
+    /// Mark a block (and all its descendants) invalid, disconnecting it from the chain
+    pub fn invalidate_block(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let block_hash_json = serde_json::json!(block_hash.to_string());
+        self.call_method::<serde_json::Value>("invalidateblock", &vec![block_hash_json])?;
+        Ok(())
+    }
+
+    /// Undo a previous `invalidate_block`, letting the block compete for the tip again
+    pub fn reconsider_block(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let block_hash_json = serde_json::json!(block_hash.to_string());
+        self.call_method::<serde_json::Value>("reconsiderblock", &vec![block_hash_json])?;
+        Ok(())
+    }
 }
 
 pub fn get_bitcoind_client() -> BitcoindClient {
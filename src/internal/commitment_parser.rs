@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::{PublicKey, Secp256k1};
+use bitcoin::Transaction;
+
+use crate::scripts::{
+    create_offered_htlc_script, create_received_htlc_script, create_to_local_script,
+    create_to_remote_script,
+};
+use crate::types::{ChannelPublicKeys, CommitmentKeys, HTLCOutput, PaymentHash};
+
+/// What one output of a commitment transaction pays to, from the point of
+/// view of the party holding (and able to broadcast) it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitmentOutput {
+    /// The holder's balance, behind `to_self_delay` and the revocation key
+    ToLocal { amount_sat: u64 },
+    /// The counterparty's balance, to its payment basepoint
+    ToRemote { amount_sat: u64 },
+    /// An HTLC the holder offered, claimable by the counterparty with the preimage
+    OfferedHtlc { amount_sat: u64, payment_hash: PaymentHash, cltv_expiry: u32 },
+    /// An HTLC the holder received, claimable by the holder with the preimage
+    ReceivedHtlc { amount_sat: u64, payment_hash: PaymentHash, cltv_expiry: u32 },
+    /// None of the above: an anchor, or an HTLC or key we were not given
+    Unknown { amount_sat: u64, script_pubkey: ScriptBuf },
+}
+
+// The scriptPubKeys we can rebuild, and what paying to them means
+enum KnownScript<'a> {
+    ToLocal,
+    ToRemote,
+    OfferedHtlc(&'a HTLCOutput),
+    ReceivedHtlc(&'a HTLCOutput),
+}
+
+/// Classify each output of the commitment transaction held by the owner of
+/// `local_keys`, for the commitment with `per_commitment_point`.
+///
+/// to_local and to_remote are found by rebuilding their scripts from the
+/// basepoints. An HTLC output is P2WSH, so its payment hash is only in the
+/// hash of the script: `htlcs` are the candidates, each tried as both an
+/// offered and a received HTLC. An offered HTLC's script does not contain
+/// its CLTV either; it is taken from the candidate that matched.
+///
+/// Returns `(output index, output)` in transaction order.
+pub fn parse_commitment_transaction(
+    tx: &Transaction,
+    local_keys: &ChannelPublicKeys,
+    remote_keys: &ChannelPublicKeys,
+    per_commitment_point: &PublicKey,
+    to_self_delay: u16,
+    htlcs: &[HTLCOutput],
+) -> Vec<(u32, CommitmentOutput)> {
+    let commitment_keys = CommitmentKeys::from_basepoints(
+        per_commitment_point,
+        &local_keys.delayed_payment_basepoint,
+        &local_keys.htlc_basepoint,
+        &remote_keys.revocation_basepoint,
+        &remote_keys.htlc_basepoint,
+        &Secp256k1::new(),
+    );
+
+    let mut known: HashMap<ScriptBuf, KnownScript> = HashMap::new();
+    let to_local_script = create_to_local_script(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_delayed_payment_key,
+        to_self_delay,
+    );
+    known.insert(to_local_script.to_p2wsh(), KnownScript::ToLocal);
+    known.insert(create_to_remote_script(&remote_keys.payment_basepoint), KnownScript::ToRemote);
+
+    for htlc in htlcs {
+        let offered_script = create_offered_htlc_script(
+            &commitment_keys.revocation_key,
+            &commitment_keys.local_htlc_key,
+            &commitment_keys.remote_htlc_key,
+            &htlc.payment_hash,
+        );
+        known.insert(offered_script.to_p2wsh(), KnownScript::OfferedHtlc(htlc));
+
+        let received_script = create_received_htlc_script(
+            &commitment_keys.revocation_key,
+            &commitment_keys.local_htlc_key,
+            &commitment_keys.remote_htlc_key,
+            &htlc.payment_hash,
+            htlc.cltv_expiry,
+        );
+        known.insert(received_script.to_p2wsh(), KnownScript::ReceivedHtlc(htlc));
+    }
+
+    tx.output
+        .iter()
+        .enumerate()
+        .map(|(index, output)| {
+            let amount_sat = output.value.to_sat();
+            let parsed = match known.get(&output.script_pubkey) {
+                Some(KnownScript::ToLocal) => CommitmentOutput::ToLocal { amount_sat },
+                Some(KnownScript::ToRemote) => CommitmentOutput::ToRemote { amount_sat },
+                Some(KnownScript::OfferedHtlc(htlc)) => CommitmentOutput::OfferedHtlc {
+                    amount_sat,
+                    payment_hash: htlc.payment_hash,
+                    cltv_expiry: htlc.cltv_expiry,
+                },
+                Some(KnownScript::ReceivedHtlc(htlc)) => CommitmentOutput::ReceivedHtlc {
+                    amount_sat,
+                    payment_hash: htlc.payment_hash,
+                    cltv_expiry: htlc.cltv_expiry,
+                },
+                None => CommitmentOutput::Unknown {
+                    amount_sat,
+                    script_pubkey: output.script_pubkey.clone(),
+                },
+            };
+            (index as u32, parsed)
+        })
+        .collect()
+}
@@ -10,6 +10,15 @@ pub struct SignedTx {
   pub hex: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct MempoolAcceptResult {
+  #[serde(deserialize_with = "deserialize_txid")]
+  pub txid: Txid,
+  pub allowed: bool,
+  #[serde(rename = "reject-reason")]
+  pub reject_reason: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ListUnspentResponse(pub Vec<ListUnspentUtxo>);
 
@@ -24,6 +33,13 @@ pub struct ListUnspentUtxo {
   pub address: Address,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct LockedUtxo {
+  #[serde(deserialize_with = "deserialize_txid")]
+  pub txid: Txid,
+  pub vout: u32,
+}
+
 fn deserialize_txid<'de, D>(deserializer: D) -> Result<Txid, D::Error>
 where
   D: Deserializer<'de>,
@@ -0,0 +1,23 @@
+use bitcoin::{Address, Txid};
+use std::env;
+
+/// Used when `EXPLORER_URL` is not set
+pub const DEFAULT_EXPLORER_URL: &str = "https://mempool.space";
+
+/// Base URL of the block explorer links point to.
+///
+/// Set `EXPLORER_URL` to point somewhere else, e.g. `https://mempool.space/signet`
+/// or a local regtest esplora such as `http://localhost:3002`.
+pub fn explorer_base_url() -> String {
+    env::var("EXPLORER_URL").unwrap_or_else(|_| DEFAULT_EXPLORER_URL.to_string())
+}
+
+/// Link to a transaction on the explorer at `base_url`
+pub fn tx_url(base_url: &str, txid: &Txid) -> String {
+    format!("{}/tx/{}", base_url.trim_end_matches('/'), txid)
+}
+
+/// Link to an address on the explorer at `base_url`
+pub fn address_url(base_url: &str, address: &Address) -> String {
+    format!("{}/address/{}", base_url.trim_end_matches('/'), address)
+}
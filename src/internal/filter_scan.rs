@@ -0,0 +1,74 @@
+use std::ops::RangeInclusive;
+
+use bitcoin::bip158::BlockFilter;
+use bitcoin::{Block, BlockHash, Txid};
+
+use crate::internal::bitcoind_client::BitcoindClient;
+use crate::internal::script_index::{OwnedScript, ScriptIndex};
+
+/// A transaction paying to one or more of our scripts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanMatch {
+    pub height: u64,
+    pub block_hash: BlockHash,
+    pub txid: Txid,
+    /// Output index and classification of each output that is ours
+    pub outputs: Vec<(u32, OwnedScript)>,
+}
+
+/// Whether a block's BIP158 filter says it may pay to one of our scripts.
+///
+/// Filters have false positives (about 1 in 784931 per script) but never
+/// false negatives, so a `false` means the block can be skipped.
+pub fn filter_matches(filter: &BlockFilter, block_hash: &BlockHash, index: &ScriptIndex) -> bool {
+    // `match_any` treats an empty query as a match
+    if index.is_empty() {
+        return false;
+    }
+    filter
+        .match_any(block_hash, index.script_pubkeys().map(|script| script.as_bytes()))
+        .unwrap_or(true)
+}
+
+/// The transactions in `block` with outputs in `index`
+pub fn scan_block(block: &Block, height: u64, index: &ScriptIndex) -> Vec<ScanMatch> {
+    let block_hash = block.block_hash();
+    block
+        .txdata
+        .iter()
+        .filter_map(|tx| {
+            let outputs = index.owned_outputs(tx);
+            (!outputs.is_empty()).then(|| ScanMatch {
+                height,
+                block_hash,
+                txid: tx.compute_txid(),
+                outputs,
+            })
+        })
+        .collect()
+}
+
+/// Scan `heights` for outputs in `index`, downloading only the blocks whose
+/// compact filter matches. Returns the matches and how many blocks were
+/// actually fetched.
+pub fn scan_with_filters(
+    bitcoind: &BitcoindClient,
+    index: &ScriptIndex,
+    heights: RangeInclusive<u64>,
+) -> Result<(Vec<ScanMatch>, usize), Box<dyn std::error::Error>> {
+    let mut matches = Vec::new();
+    let mut blocks_fetched = 0;
+
+    for height in heights {
+        let block_hash = bitcoind.get_block_hash(height)?;
+        let filter = bitcoind.get_block_filter(&block_hash)?;
+        if !filter_matches(&filter, &block_hash, index) {
+            continue;
+        }
+
+        blocks_fetched += 1;
+        matches.extend(scan_block(&bitcoind.get_block(&block_hash)?, height, index));
+    }
+
+    Ok((matches, blocks_fetched))
+}
@@ -0,0 +1,61 @@
+use std::cell::Cell;
+
+use crate::internal::bitcoind_client::BitcoindClient;
+
+/// Where the current chain height comes from. Timelock checks go through
+/// this so tests can move the chain forward without mining blocks.
+pub trait HeightProvider {
+    /// Height of the current chain tip
+    fn current_height(&self) -> u64;
+}
+
+impl HeightProvider for BitcoindClient {
+    fn current_height(&self) -> u64 {
+        self.get_block_count().expect("bitcoind should answer getblockcount")
+    }
+}
+
+/// A chain tip that tests set and advance by hand
+pub struct MockHeightProvider {
+    height: Cell<u64>,
+}
+
+impl MockHeightProvider {
+    pub fn new(height: u64) -> Self {
+        MockHeightProvider { height: Cell::new(height) }
+    }
+
+    /// "Mine" `blocks` blocks instantly
+    pub fn advance(&self, blocks: u64) {
+        self.height.set(self.height.get() + blocks);
+    }
+
+    pub fn set_height(&self, height: u64) {
+        self.height.set(height);
+    }
+}
+
+impl HeightProvider for MockHeightProvider {
+    fn current_height(&self) -> u64 {
+        self.height.get()
+    }
+}
+
+/// Blocks to mine before a transaction with `nLockTime = cltv_expiry` (e.g.
+/// an HTLC-timeout) can enter the next block; 0 once it can be broadcast.
+pub fn blocks_until_cltv_expiry(provider: &impl HeightProvider, cltv_expiry: u32) -> u64 {
+    (cltv_expiry as u64).saturating_sub(provider.current_height())
+}
+
+/// Blocks to mine before an output confirmed at `confirmation_height` and
+/// locked with `OP_CSV to_self_delay` can be swept; 0 once it can be broadcast.
+pub fn blocks_until_csv_mature(
+    provider: &impl HeightProvider,
+    confirmation_height: u64,
+    to_self_delay: u16,
+) -> u64 {
+    // The sweep may be mined in block `confirmation_height + to_self_delay`
+    (confirmation_height + to_self_delay as u64)
+        .saturating_sub(1)
+        .saturating_sub(provider.current_height())
+}
@@ -24,15 +24,11 @@ use bitcoin::PublicKey;
 use bitcoin::{OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
 use internal::bitcoind_client::BitcoindClient;
 use internal::hex_utils;
+use internal::explorer::{address_url, explorer_base_url, tx_url};
+use internal::script_utils::{address_for_script, check_witness_standard, display_script};
 use std::env;
 
-pub fn get_outpoint(input_tx_id_str: String, vout: usize) -> OutPoint {
-
-    // Get an unspent output to spend
-    let mut tx_id_bytes = hex::decode(input_tx_id_str).expect("Valid hex string");
-    tx_id_bytes.reverse();
-    let input_txid = Txid::from_byte_array(tx_id_bytes.try_into().expect("Expected 32 bytes"));
-
+pub fn get_outpoint(input_txid: Txid, vout: usize) -> OutPoint {
     OutPoint {
             txid: input_txid,
             vout: vout as u32,
@@ -40,6 +36,7 @@ pub fn get_outpoint(input_tx_id_str: String, vout: usize) -> OutPoint {
 }
 
 pub fn get_unspent_output(bitcoind: BitcoindClient) -> TxIn {
+    // `listunspent` leaves out outputs reserved by earlier `Funding` runs
     let utxos = bitcoind.list_unspent();
     let utxo = utxos
         .0
@@ -74,3 +71,72 @@ pub fn sign_raw_transaction(bitcoind: BitcoindClient, tx: Transaction) -> Transa
 
     final_tx
 }
+
+/// Print an explorer link for `txid` (see `EXPLORER_URL`)
+pub fn print_tx_link(txid: &Txid) {
+    println!("Explorer: {}", tx_url(&explorer_base_url(), txid));
+}
+
+/// Print each output's value and address on the workshop's regtest network
+pub fn print_outputs(tx: &Transaction) {
+    let base_url = explorer_base_url();
+    println!("\nOutputs:");
+    for (i, output) in tx.output.iter().enumerate() {
+        println!(
+            "  #{} {} sats -> {}",
+            i,
+            output.value.to_sat(),
+            display_script(&output.script_pubkey, Network::Regtest)
+        );
+        if let Some(address) = address_for_script(&output.script_pubkey, Network::Regtest) {
+            println!("     {}", address_url(&base_url, &address));
+        }
+    }
+}
+
+/// Translate a `testmempoolaccept` reject-reason into a hint for the workshop
+pub fn rejection_hint(reason: &str) -> &'static str {
+    if reason.contains("missing-inputs") || reason.contains("missingorspent") {
+        "The output this transaction spends is unknown or already spent. Broadcast the parent transaction first, and check the txid you passed in."
+    } else if reason == "non-final" {
+        "The nLockTime is still in the future. Mine blocks until the chain reaches the locktime, then try again."
+    } else if reason.contains("non-BIP68-final") {
+        "A relative timelock (CSV) has not matured yet. Mine more blocks on top of the parent transaction."
+    } else if reason.contains("script-verify-flag-failed") || reason.contains("bad-witness") {
+        "The witness does not satisfy the output script. Check your signatures, witness order and script construction."
+    } else if reason.contains("dust") {
+        "An output is below the dust limit. Check the output amounts and the fee you subtracted."
+    } else if reason.contains("fee") {
+        "The fee is too low for this node's relay policy."
+    } else if reason.contains("already") || reason.contains("conflict") {
+        "This transaction (or one spending the same input) was already broadcast."
+    } else {
+        "Run `bitcoin-cli decoderawtransaction <tx_hex>` and compare it with the expected transaction."
+    }
+}
+
+/// Pre-flight a transaction with `testmempoolaccept` before it is broadcast
+/// and print whether bitcoind would accept it, with a hint if not.
+pub fn check_mempool_accept(bitcoind: &BitcoindClient, tx: &Transaction) {
+    // Catch oversized witnesses locally, with the offending item named
+    for (i, input) in tx.input.iter().enumerate() {
+        if let Err(e) = check_witness_standard(&input.witness) {
+            println!("\n⚠️  Input #{} has a non-standard witness: {:?}", i, e);
+        }
+    }
+
+    match bitcoind.test_mempool_accept(std::slice::from_ref(tx)) {
+        Ok(results) => match results.first() {
+            Some(result) if result.allowed => {
+                println!("\n🟢 bitcoind would accept this transaction");
+            }
+            Some(result) => {
+                let reason = result.reject_reason.clone().unwrap_or_default();
+                println!("\n🔴 bitcoind would reject this transaction: {}", reason);
+                println!("💡 {}", rejection_hint(&reason));
+            }
+            None => {}
+        },
+        Err(e) => println!("\n⚠️  Could not pre-flight transaction with bitcoind: {}", e),
+    }
+}
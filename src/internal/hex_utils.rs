@@ -1,5 +1,8 @@
 use bitcoin::secp256k1::PublicKey;
+use bitcoin::{BlockHash, Txid};
+use std::fmt;
 use std::fmt::Write;
+use std::str::FromStr;
 
 pub fn to_vec(hex: &str) -> Option<Vec<u8>> {
     let mut out = Vec::with_capacity(hex.len() / 2);
@@ -20,4 +23,82 @@ pub fn to_vec(hex: &str) -> Option<Vec<u8>> {
     }
 
     Some(out)
-}
\ No newline at end of file
+}
+
+/// Why a hex argument could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Not a hex digit, at `index` (0-based) in the input
+    InvalidHexCharacter { c: char, index: usize },
+    /// Hex strings need two digits per byte
+    OddLength(usize),
+    /// Valid hex, but the wrong number of bytes
+    WrongLength { expected: usize, actual: usize },
+    /// 33 bytes that are not a point on the curve
+    InvalidPubkey,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidHexCharacter { c, index } => {
+                write!(f, "invalid hex character {:?} at position {}", c, index)
+            }
+            ParseError::OddLength(len) => {
+                write!(f, "odd number of hex digits ({})", len)
+            }
+            ParseError::WrongLength { expected, actual } => {
+                write!(f, "expected {} bytes ({} hex digits), got {}", expected, expected * 2, actual)
+            }
+            ParseError::InvalidPubkey => write!(f, "not a valid compressed public key"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Decode a hex string, reporting where it went wrong
+pub fn parse_hex(s: &str) -> Result<Vec<u8>, ParseError> {
+    hex::decode(s).map_err(|e| match e {
+        hex::FromHexError::InvalidHexCharacter { c, index } => {
+            ParseError::InvalidHexCharacter { c, index }
+        }
+        _ => ParseError::OddLength(s.len()),
+    })
+}
+
+fn parse_array<const N: usize>(s: &str) -> Result<[u8; N], ParseError> {
+    let bytes = parse_hex(s)?;
+    let actual = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| ParseError::WrongLength { expected: N, actual })
+}
+
+/// Parse a txid as shown by bitcoind and block explorers (byte-reversed hex)
+pub fn parse_txid(s: &str) -> Result<Txid, ParseError> {
+    parse_array::<32>(s)?;
+    Ok(Txid::from_str(s).expect("64 hex digits is a valid txid"))
+}
+
+/// Parse a block hash as shown by bitcoind (byte-reversed hex, like a txid)
+pub fn parse_block_hash(s: &str) -> Result<BlockHash, ParseError> {
+    parse_array::<32>(s)?;
+    Ok(BlockHash::from_str(s).expect("64 hex digits is a valid block hash"))
+}
+
+/// Parse a 33-byte compressed public key
+pub fn parse_pubkey(s: &str) -> Result<PublicKey, ParseError> {
+    let bytes = parse_array::<33>(s)?;
+    PublicKey::from_slice(&bytes).map_err(|_| ParseError::InvalidPubkey)
+}
+
+/// Parse a 32-byte seed
+pub fn parse_seed(s: &str) -> Result<[u8; 32], ParseError> {
+    parse_array::<32>(s)
+}
+
+/// Parse a 32-byte payment preimage
+pub fn parse_preimage(s: &str) -> Result<[u8; 32], ParseError> {
+    parse_array::<32>(s)
+}
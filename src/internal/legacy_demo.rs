@@ -0,0 +1,97 @@
+// Why Lightning needed segwit.
+//
+// Before segwit, signatures lived in the scriptSig, and the txid covers the
+// scriptSig. Anyone relaying a transaction could re-encode a signature
+// (e.g. replace `s` with `n - s`, which verifies just the same) and so change
+// its txid without invalidating it. Every pre-signed child that named the
+// old txid (a commitment spending the funding output, an HTLC transaction
+// spending the commitment) then points at an output that never confirms.
+//
+// This module builds the pre-segwit version of a channel output, a P2SH
+// 2-of-2, so the breakage can be reproduced on regtest.
+
+use bitcoin::blockdata::opcodes::all as opcodes;
+use bitcoin::hashes::Hash;
+use bitcoin::script::{Builder, PushBytesBuf, ScriptBuf};
+use bitcoin::secp256k1::ecdsa::Signature;
+use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+use bitcoin::Transaction;
+use bitcoin::PublicKey as BitcoinPublicKey;
+
+use crate::scripts::funding::create_funding_script;
+
+/// The legacy channel output: the same 2-of-2 multisig as segwit funding,
+/// wrapped in P2SH. Returns `(redeem_script, script_pubkey)`.
+pub fn create_p2sh_funding_output(
+    pubkey1: &BitcoinPublicKey,
+    pubkey2: &BitcoinPublicKey,
+) -> (ScriptBuf, ScriptBuf) {
+    let redeem_script = create_funding_script(pubkey1, pubkey2);
+    let script_pubkey = redeem_script.to_p2sh();
+    (redeem_script, script_pubkey)
+}
+
+/// Sign a P2SH input with the legacy (pre-BIP143) sighash
+pub fn sign_p2sh_input(
+    tx: &Transaction,
+    input_index: usize,
+    redeem_script: &ScriptBuf,
+    secret_key: &SecretKey,
+) -> Vec<u8> {
+    let sighash = SighashCache::new(tx)
+        .legacy_signature_hash(input_index, redeem_script, EcdsaSighashType::All.to_u32())
+        .expect("Valid sighash");
+    let msg = Message::from_digest(sighash.to_byte_array());
+    let sig = Secp256k1::new().sign_ecdsa(&msg, secret_key);
+
+    let mut sig_bytes = sig.serialize_der().to_vec();
+    sig_bytes.push(EcdsaSighashType::All as u8);
+    sig_bytes
+}
+
+/// `OP_0 <sig1> <sig2> <redeem_script>`, with signatures in pubkey order
+pub fn p2sh_multisig_script_sig(signatures: [&[u8]; 2], redeem_script: &ScriptBuf) -> ScriptBuf {
+    let push = |bytes: &[u8]| PushBytesBuf::try_from(bytes.to_vec()).expect("Fits in a push");
+    Builder::new()
+        .push_opcode(opcodes::OP_PUSHBYTES_0)
+        .push_slice(push(signatures[0]))
+        .push_slice(push(signatures[1]))
+        .push_slice(push(redeem_script.as_bytes()))
+        .into_script()
+}
+
+/// Re-encode a DER signature (with sighash flag) as `(r, n - s)`.
+///
+/// Both encodings are valid under consensus rules for legacy inputs; relay
+/// policy prefers low `s`, but a miner will happily include either.
+pub fn malleate_signature(signature: &[u8]) -> Vec<u8> {
+    let (sighash_flag, der) = signature.split_last().expect("Non-empty signature");
+    let compact = Signature::from_der(der).expect("Valid DER").serialize_compact();
+
+    // n - s is the negation of s as a scalar
+    let s = SecretKey::from_slice(&compact[32..]).expect("s is a valid scalar");
+    let mut malleated = compact;
+    malleated[32..].copy_from_slice(&s.negate().secret_bytes());
+
+    let mut sig_bytes = Signature::from_compact(&malleated)
+        .expect("Valid compact signature")
+        .serialize_der()
+        .to_vec();
+    sig_bytes.push(*sighash_flag);
+    sig_bytes
+}
+
+/// What a third party can do to a pre-segwit transaction in flight: flip the
+/// `s` of every signature in the first input's P2SH multisig scriptSig. The
+/// transaction stays valid, but its txid changes.
+pub fn malleate_p2sh_multisig_tx(
+    tx: &Transaction,
+    signatures: [&[u8]; 2],
+    redeem_script: &ScriptBuf,
+) -> Transaction {
+    let malleated = [malleate_signature(signatures[0]), malleate_signature(signatures[1])];
+    let mut tx = tx.clone();
+    tx.input[0].script_sig = p2sh_multisig_script_sig([&malleated[0], &malleated[1]], redeem_script);
+    tx
+}
@@ -1,5 +1,14 @@
 
 pub mod bitcoind_client;
+pub mod commitment_parser;
 pub mod convert;
+pub mod explorer;
+pub mod filter_scan;
+pub mod height;
 pub mod helper;
-pub mod hex_utils;
\ No newline at end of file
+pub mod hex_utils;
+pub mod legacy_demo;
+pub mod script_index;
+pub mod script_utils;
+pub mod simulated_peer;
+pub mod spv;
\ No newline at end of file
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use bitcoin::script::{Script, ScriptBuf};
+use bitcoin::Transaction;
+
+use crate::scripts::{
+    create_offered_htlc_script, create_received_htlc_script, create_to_local_script,
+    create_to_remote_script,
+};
+use crate::types::{ChannelKeyManager, ChannelPublicKeys, CommitmentKeys, HTLCOutput};
+
+/// Which commitment output an indexed scriptPubKey belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnedScriptKind {
+    /// Our delayed balance on our own commitment
+    ToLocal,
+    /// Our balance on the counterparty's commitment
+    ToRemote,
+    OfferedHtlc,
+    ReceivedHtlc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OwnedScript {
+    pub kind: OwnedScriptKind,
+    /// `None` for to_remote, which (with `option_static_remotekey`) pays the
+    /// same script on every commitment
+    pub commitment_number: Option<u64>,
+}
+
+/// Every scriptPubKey a channel could pay us with, derived once up front.
+///
+/// Scanning a block is then one lookup per output instead of re-deriving the
+/// commitment keys and scripts for each transaction.
+#[derive(Debug, Default)]
+pub struct ScriptIndex {
+    scripts: HashMap<ScriptBuf, OwnedScript>,
+}
+
+impl ScriptIndex {
+    pub fn new() -> Self {
+        ScriptIndex::default()
+    }
+
+    /// Index our to_remote and our to_local for each of `commitment_numbers`
+    pub fn for_channel(
+        keys: &ChannelKeyManager,
+        remote_keys: &ChannelPublicKeys,
+        to_self_delay: u16,
+        commitment_numbers: RangeInclusive<u64>,
+    ) -> Self {
+        let local_keys = keys.to_public_keys();
+        let mut index = ScriptIndex::new();
+
+        index.insert(
+            create_to_remote_script(&local_keys.payment_basepoint),
+            OwnedScriptKind::ToRemote,
+            None,
+        );
+
+        for commitment_number in commitment_numbers {
+            let commitment_keys = CommitmentKeys::from_basepoints(
+                &keys.derive_per_commitment_point(commitment_number),
+                &local_keys.delayed_payment_basepoint,
+                &local_keys.htlc_basepoint,
+                &remote_keys.revocation_basepoint,
+                &remote_keys.htlc_basepoint,
+                &keys.secp_ctx,
+            );
+            let to_local_script = create_to_local_script(
+                &commitment_keys.revocation_key,
+                &commitment_keys.local_delayed_payment_key,
+                to_self_delay,
+            );
+            index.insert(
+                to_local_script.to_p2wsh(),
+                OwnedScriptKind::ToLocal,
+                Some(commitment_number),
+            );
+        }
+
+        index
+    }
+
+    /// Index the HTLC outputs of one commitment. HTLC scripts commit to the
+    /// payment hash, so they can only be added once the HTLCs are known.
+    pub fn add_htlcs(
+        &mut self,
+        commitment_number: u64,
+        commitment_keys: &CommitmentKeys,
+        offered_htlcs: &[HTLCOutput],
+        received_htlcs: &[HTLCOutput],
+    ) {
+        for htlc in offered_htlcs {
+            let script = create_offered_htlc_script(
+                &commitment_keys.revocation_key,
+                &commitment_keys.local_htlc_key,
+                &commitment_keys.remote_htlc_key,
+                &htlc.payment_hash,
+            );
+            self.insert(script.to_p2wsh(), OwnedScriptKind::OfferedHtlc, Some(commitment_number));
+        }
+        for htlc in received_htlcs {
+            let script = create_received_htlc_script(
+                &commitment_keys.revocation_key,
+                &commitment_keys.local_htlc_key,
+                &commitment_keys.remote_htlc_key,
+                &htlc.payment_hash,
+                htlc.cltv_expiry,
+            );
+            self.insert(script.to_p2wsh(), OwnedScriptKind::ReceivedHtlc, Some(commitment_number));
+        }
+    }
+
+    fn insert(&mut self, script_pubkey: ScriptBuf, kind: OwnedScriptKind, commitment_number: Option<u64>) {
+        self.scripts.insert(script_pubkey, OwnedScript { kind, commitment_number });
+    }
+
+    /// Every indexed scriptPubKey, e.g. to match against a block filter
+    pub fn script_pubkeys(&self) -> impl Iterator<Item = &ScriptBuf> {
+        self.scripts.keys()
+    }
+
+    pub fn classify(&self, script_pubkey: &Script) -> Option<OwnedScript> {
+        self.scripts.get(script_pubkey).copied()
+    }
+
+    /// The outputs of `tx` that pay us, with their output index
+    pub fn owned_outputs(&self, tx: &Transaction) -> Vec<(u32, OwnedScript)> {
+        tx.output
+            .iter()
+            .enumerate()
+            .filter_map(|(vout, output)| {
+                self.classify(&output.script_pubkey).map(|owned| (vout as u32, owned))
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scripts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+}
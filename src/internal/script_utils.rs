@@ -0,0 +1,54 @@
+use bitcoin::{Address, Network, Script, Witness};
+
+/// The address paying to `script`, or `None` for scripts with no address form (e.g. OP_RETURN)
+pub fn address_for_script(script: &Script, network: Network) -> Option<Address> {
+    Address::from_script(script, network).ok()
+}
+
+/// Render `script` as an address for printing, falling back to the raw script hex
+pub fn display_script(script: &Script, network: Network) -> String {
+    match address_for_script(script, network) {
+        Some(address) => address.to_string(),
+        None => format!("script {}", script.to_hex_string()),
+    }
+}
+
+/// Policy limit on the size of a P2WSH witness script
+pub const MAX_STANDARD_P2WSH_SCRIPT_SIZE: usize = 3600;
+/// Policy limit on the number of witness items, not counting the witness script
+pub const MAX_STANDARD_P2WSH_STACK_ITEMS: usize = 100;
+/// Policy limit on the size of each witness item, not counting the witness script
+pub const MAX_STANDARD_P2WSH_STACK_ITEM_SIZE: usize = 80;
+
+/// Why bitcoind would refuse to relay a P2WSH spend (`bad-witness-nonstandard`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StandardnessError {
+    ScriptTooLarge { size: usize },
+    TooManyStackItems { count: usize },
+    StackItemTooLarge { index: usize, size: usize },
+}
+
+/// Check a witness script against the P2WSH size limit
+pub fn check_witness_script_standard(script: &Script) -> Result<(), StandardnessError> {
+    if script.len() > MAX_STANDARD_P2WSH_SCRIPT_SIZE {
+        return Err(StandardnessError::ScriptTooLarge { size: script.len() });
+    }
+    Ok(())
+}
+
+/// Check a P2WSH witness (stack items followed by the witness script) against relay policy
+pub fn check_witness_standard(witness: &Witness) -> Result<(), StandardnessError> {
+    let script = witness.last().unwrap_or_default();
+    check_witness_script_standard(Script::from_bytes(script))?;
+
+    let stack_items = witness.len().saturating_sub(1);
+    if stack_items > MAX_STANDARD_P2WSH_STACK_ITEMS {
+        return Err(StandardnessError::TooManyStackItems { count: stack_items });
+    }
+    for (index, item) in witness.iter().take(stack_items).enumerate() {
+        if item.len() > MAX_STANDARD_P2WSH_STACK_ITEM_SIZE {
+            return Err(StandardnessError::StackItemTooLarge { index, size: item.len() });
+        }
+    }
+    Ok(())
+}
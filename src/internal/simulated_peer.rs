@@ -0,0 +1,168 @@
+use bitcoin::hashes::Hash;
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::ecdsa::Signature;
+use bitcoin::secp256k1::{self, Message, PublicKey, Secp256k1};
+use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+use bitcoin::{Amount, Network, Transaction};
+
+use crate::keys::commitment::derive_private_key;
+use crate::keys::derivation::new_keys_manager;
+use crate::keys::scheme::DerivationScheme;
+use crate::types::{ChannelKeyManager, ChannelPublicKeys, ChannelType};
+
+/// The counterparty in the workshop flows, derived from a fixed seed.
+///
+/// Its secrets stay private: the only way to use them is through the
+/// methods below, each of which stands for a message a real peer would
+/// send us. Anything that touches `keys` crosses the trust boundary.
+pub struct SimulatedPeer {
+    keys: ChannelKeyManager,
+}
+
+impl SimulatedPeer {
+    /// The seed every workshop flow uses for the counterparty
+    pub const SEED: [u8; 32] = [0x02; 32];
+
+    pub fn new() -> Self {
+        Self::from_seed(Self::SEED, 0)
+    }
+
+    pub fn from_seed(seed: [u8; 32], channel_index: u32) -> Self {
+        Self::from_scheme(&new_keys_manager(seed, Network::Bitcoin), channel_index)
+    }
+
+    /// A peer whose node derives its channel keys with `scheme`
+    pub fn from_scheme(scheme: &impl DerivationScheme, channel_index: u32) -> Self {
+        SimulatedPeer {
+            keys: scheme.derive_channel_keys(channel_index),
+        }
+    }
+
+    /// The basepoints sent in `open_channel` / `accept_channel`
+    pub fn public_keys(&self) -> ChannelPublicKeys {
+        self.keys.to_public_keys()
+    }
+
+    /// The per-commitment point for the peer's commitment `commitment_number`
+    pub fn per_commitment_point(&self, commitment_number: u64) -> PublicKey {
+        self.keys.derive_per_commitment_point(commitment_number)
+    }
+
+    /// `commitment_signed`: the peer's signature on the funding input of our commitment
+    pub fn sign_holder_commitment(
+        &self,
+        tx: &Transaction,
+        input_index: usize,
+        funding_script: &ScriptBuf,
+        funding_amount: u64,
+    ) -> Vec<u8> {
+        self.keys.sign_transaction_input_sighash_all(
+            tx,
+            input_index,
+            funding_script,
+            funding_amount,
+            &self.keys.funding_key,
+        )
+    }
+
+    /// `commitment_signed`: the peer's signature on one of our HTLC-timeout/success
+    /// transactions, using its HTLC key for our `per_commitment_point`
+    pub fn sign_htlc_transaction(
+        &self,
+        tx: &Transaction,
+        input_index: usize,
+        htlc_script: &ScriptBuf,
+        htlc_amount: u64,
+        per_commitment_point: &PublicKey,
+    ) -> Vec<u8> {
+        let htlc_privkey = derive_private_key(
+            &self.keys.htlc_basepoint_secret,
+            per_commitment_point,
+            &self.keys.secp_ctx,
+        );
+        self.keys.sign_transaction_input_sighash_all(
+            tx,
+            input_index,
+            htlc_script,
+            htlc_amount,
+            &htlc_privkey,
+        )
+    }
+
+    /// `sign_htlc_transaction` for the given channel type. On anchor channels
+    /// the peer signs with SINGLE|ANYONECANPAY, so we can later add inputs
+    /// and outputs to pay the fee without invalidating its signature.
+    pub fn sign_htlc_transaction_for_channel_type(
+        &self,
+        tx: &Transaction,
+        input_index: usize,
+        htlc_script: &ScriptBuf,
+        htlc_amount: u64,
+        per_commitment_point: &PublicKey,
+        channel_type: ChannelType,
+    ) -> Vec<u8> {
+        let sighash_type = channel_type.remote_htlc_sighash_type();
+        if sighash_type == EcdsaSighashType::All {
+            return self.sign_htlc_transaction(
+                tx,
+                input_index,
+                htlc_script,
+                htlc_amount,
+                per_commitment_point,
+            );
+        }
+
+        let htlc_privkey = derive_private_key(
+            &self.keys.htlc_basepoint_secret,
+            per_commitment_point,
+            &self.keys.secp_ctx,
+        );
+        let sighash = SighashCache::new(tx)
+            .p2wsh_signature_hash(input_index, htlc_script, Amount::from_sat(htlc_amount), sighash_type)
+            .expect("Valid sighash");
+        let msg = Message::from_digest(sighash.to_byte_array());
+        let mut signature = self.keys.secp_ctx.sign_ecdsa(&msg, &htlc_privkey).serialize_der().to_vec();
+        signature.push(sighash_type as u8);
+        signature
+    }
+
+    /// `revoke_and_ack`: reveal the secret for the peer's old commitment
+    pub fn revoke(&self, commitment_number: u64) -> [u8; 32] {
+        self.keys.build_commitment_secret(commitment_number)
+    }
+
+    /// Check our signature (with its sighash flag) on the peer's commitment,
+    /// as the peer would before accepting our `commitment_signed`
+    pub fn validate_commitment_signature(
+        &self,
+        tx: &Transaction,
+        input_index: usize,
+        funding_script: &ScriptBuf,
+        funding_amount: u64,
+        signature: &[u8],
+        our_funding_pubkey: &PublicKey,
+    ) -> Result<(), secp256k1::Error> {
+        let (sighash_flag, der) = signature.split_last().ok_or(secp256k1::Error::InvalidSignature)?;
+        if *sighash_flag != EcdsaSighashType::All as u8 {
+            return Err(secp256k1::Error::InvalidSignature);
+        }
+
+        let sighash = SighashCache::new(tx)
+            .p2wsh_signature_hash(
+                input_index,
+                funding_script,
+                Amount::from_sat(funding_amount),
+                EcdsaSighashType::All,
+            )
+            .map_err(|_| secp256k1::Error::InvalidMessage)?;
+        let msg = Message::from_digest(sighash.to_byte_array());
+
+        Secp256k1::verification_only().verify_ecdsa(&msg, &Signature::from_der(der)?, our_funding_pubkey)
+    }
+}
+
+impl Default for SimulatedPeer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,71 @@
+use bitcoin::merkle_tree::{MerkleBlock, MerkleBlockError};
+use bitcoin::{BlockHash, Txid};
+use std::fmt;
+
+use crate::internal::bitcoind_client::BitcoindClient;
+
+/// Reasons a funding transaction could not be proven to be in a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InclusionError {
+    /// The proof's header does not hash to the block we asked about
+    WrongBlock { expected: BlockHash, actual: BlockHash },
+    /// The partial merkle tree is malformed or does not commit to the header's merkle root
+    InvalidProof(MerkleBlockError),
+    /// The proof is valid but does not include the transaction
+    TxNotInProof(Txid),
+}
+
+impl fmt::Display for InclusionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InclusionError::WrongBlock { expected, actual } => {
+                write!(f, "proof is for block {} but expected {}", actual, expected)
+            }
+            InclusionError::InvalidProof(e) => write!(f, "invalid merkle proof: {}", e),
+            InclusionError::TxNotInProof(txid) => write!(f, "{} is not in the proof", txid),
+        }
+    }
+}
+
+impl std::error::Error for InclusionError {}
+
+/// Verify a merkle proof (as returned by `gettxoutproof`) shows `txid` is in `block_hash`.
+///
+/// Only the 80-byte header and the partial merkle tree are needed, so this
+/// works against a pruned or untrusted chain source as long as `block_hash`
+/// comes from a header chain we trust. Returns the transaction's position in the block.
+pub fn verify_merkle_proof(
+    txid: &Txid,
+    merkle_block: &MerkleBlock,
+    block_hash: &BlockHash,
+) -> Result<u32, InclusionError> {
+    // The proof must be anchored to the block we expect
+    let actual = merkle_block.header.block_hash();
+    if actual != *block_hash {
+        return Err(InclusionError::WrongBlock { expected: *block_hash, actual });
+    }
+
+    // Rebuild the merkle root from the partial tree and compare with the header
+    let mut matches = Vec::new();
+    let mut indexes = Vec::new();
+    merkle_block
+        .extract_matches(&mut matches, &mut indexes)
+        .map_err(InclusionError::InvalidProof)?;
+
+    // Check the transaction is one of the leaves the proof commits to
+    matches
+        .iter()
+        .position(|matched| matched == txid)
+        .map(|i| indexes[i])
+        .ok_or(InclusionError::TxNotInProof(*txid))
+}
+
+/// Fetch a merkle proof from bitcoind and verify the funding transaction is in `block_hash`
+pub fn verify_funding_inclusion(
+    bitcoind: &BitcoindClient,
+    txid: &Txid,
+    block_hash: &BlockHash,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    let merkle_block = bitcoind.get_tx_out_proof(&[*txid], block_hash)?;
+    Ok(verify_merkle_proof(txid, &merkle_block, block_hash)?)
+}
@@ -7,6 +7,9 @@ use ripemd::{Ripemd160};
 // Re-export commonly used external types
 pub use bitcoin::secp256k1::{Secp256k1, SecretKey, PublicKey, All};
 pub use bitcoin::Network;
+use bitcoin::{BlockHash, Txid};
+use internal::hex_utils::{parse_block_hash, parse_hex, parse_pubkey, parse_seed, parse_txid};
+use interactive::gen_vectors::{parse_htlc_spec, HtlcSpec, VectorParams};
 
 // Module declarations - pulling from exercises or solutions folder
 // 
@@ -84,26 +87,39 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Create a Funding Transaction for a Lightning channel
-    Funding,
+    Funding {
+        #[arg(long, help = "Negotiate option_support_large_channel (lifts the 2^24 sat cap)")]
+        large_channel: bool,
+        #[arg(long, help = "Add the funding output to this PSBT (base64 or binary) instead of using bitcoind's wallet")]
+        psbt: Option<String>,
+        #[arg(long, conflicts_with = "psbt", help = "Write the unsigned funding transaction as a PSBT to this file instead of signing with bitcoind's wallet")]
+        export_psbt: Option<String>,
+        #[arg(long, conflicts_with_all = ["psbt", "export_psbt"], help = "Import a funding PSBT (base64 or binary) signed by an external wallet")]
+        signed_psbt: Option<String>,
+        #[arg(long, conflicts_with_all = ["psbt", "export_psbt", "signed_psbt"], help = "Broadcast the funding transaction, then replace it by fee at this feerate (sat/kw)")]
+        rbf_feerate: Option<u64>,
+    },
     /// Create a commitment transaction for a Lightning channel
     Commitment {
-        #[arg(short = 't', long, help = "Funding Tx ID")]
-        funding_txid: String,
+        #[arg(short = 't', long, value_parser = parse_txid, help = "Funding Tx ID")]
+        funding_txid: Txid,
+        #[arg(long, value_parser = parse_block_hash, help = "Check a merkle proof that the funding tx is in this block first, as before channel_ready")]
+        funding_block: Option<BlockHash>,
     },
     /// Create a commitment transaction with HTLC for a Lightning channel
     Htlc {
-        #[arg(short = 't', long, help = "Funding Tx ID")]
-        funding_txid: String,
+        #[arg(short = 't', long, value_parser = parse_txid, help = "Funding Tx ID")]
+        funding_txid: Txid,
     },
     /// Create an HTLC Timeout for a Lightning channel
     HtlcTimeout {
-        #[arg(short = 't', long, help = "Commitment Tx ID")]
-        commitment_txid: String,
+        #[arg(short = 't', long, value_parser = parse_txid, help = "Commitment Tx ID")]
+        commitment_txid: Txid,
     },
     SimpleHtlc,
     SimpleHtlcClaim {
-        #[arg(short = 't', long, help = "Simple HTLC Tx ID")]
-        simple_htlc_txid: String,
+        #[arg(short = 't', long, value_parser = parse_txid, help = "Simple HTLC Tx ID")]
+        simple_htlc_txid: Txid,
     },
     /// Calculate SHA256 hash of hex input
     Sha256 {
@@ -121,6 +137,82 @@ enum Commands {
     ToHex {
         #[arg(short = 'd', long, help = "Input string to convert to hex")]
         input_string: String,
+    },
+
+    /// Hash an input with a selectable encoding and algorithm
+    Hash {
+        #[arg(short = 'd', long, help = "Input to hash")]
+        input: String,
+        #[arg(short = 'e', long, value_enum, default_value = "utf8", help = "How to read the input")]
+        encoding: interactive::hash::InputEncoding,
+        #[arg(short = 'a', long, value_enum, default_value = "sha256", help = "Hash algorithm")]
+        algo: interactive::hash::HashAlgo,
+        #[arg(long, help = "Treat input as a preimage (32 bytes if hex) and print SHA256 and RIPEMD160(SHA256)")]
+        payment_hash: bool,
+    },
+
+    /// Report weight, size and fee information for a raw transaction
+    TxInfo {
+        #[arg(short = 'x', long = "hex", help = "Raw transaction hex")]
+        tx_hex: String,
+        #[arg(short = 'v', long, value_delimiter = ',', help = "Comma-separated values (sats) of the outputs being spent")]
+        prevout_values: Vec<u64>,
+        #[arg(short = 'l', long, help = "Look up previous outputs with bitcoind")]
+        lookup: bool,
+        #[arg(long, value_parser = parse_pubkey, requires = "receiver_basepoint", help = "Channel initiator's payment basepoint, to decode the commitment number")]
+        initiator_basepoint: Option<PublicKey>,
+        #[arg(long, value_parser = parse_pubkey, requires = "initiator_basepoint", help = "Other side's payment basepoint")]
+        receiver_basepoint: Option<PublicKey>,
+        #[arg(short = 'n', long, default_value = "regtest", help = "Network used to display output addresses")]
+        network: Network,
+    },
+
+    /// Generate a BOLT 3 style vector set (commitment + HTLC txs) as JSON
+    GenVectors {
+        #[arg(long, value_parser = parse_seed, default_value = "0101010101010101010101010101010101010101010101010101010101010101", help = "Local node seed (hex)")]
+        local_seed: [u8; 32],
+        #[arg(long, value_parser = parse_seed, default_value = "0202020202020202020202020202020202020202020202020202020202020202", help = "Remote node seed (hex)")]
+        remote_seed: [u8; 32],
+        #[arg(long, value_parser = parse_txid, default_value = "8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be", help = "Funding Tx ID")]
+        funding_txid: Txid,
+        #[arg(long, default_value_t = 0, help = "Funding output index")]
+        funding_output_index: u32,
+        #[arg(long, default_value_t = 10_000_000, help = "Funding amount (sats)")]
+        funding_amount: u64,
+        #[arg(long, default_value_t = 42, help = "Commitment number")]
+        commitment_number: u64,
+        #[arg(long, default_value_t = 144, help = "to_self_delay")]
+        to_self_delay: u16,
+        #[arg(long, default_value_t = 546, help = "Dust limit (sats)")]
+        dust_limit: u64,
+        #[arg(long, default_value_t = 0, help = "Feerate (sat/kw)")]
+        feerate_per_kw: u64,
+        #[arg(long, help = "Local balance (msat)")]
+        to_local_msat: u64,
+        #[arg(long, help = "Remote balance (msat)")]
+        to_remote_msat: u64,
+        #[arg(long, value_parser = parse_htlc_spec, help = "HTLC we offered, as <amount_sat>:<cltv_expiry> (repeatable)")]
+        offered: Vec<HtlcSpec>,
+        #[arg(long, value_parser = parse_htlc_spec, help = "HTLC we received, as <amount_sat>:<cltv_expiry> (repeatable)")]
+        received: Vec<HtlcSpec>,
+        #[arg(short = 'o', long, help = "Write the JSON to this file instead of printing it")]
+        output: Option<String>,
+    },
+
+    /// List the wallet's spendable or locked UTXOs
+    Utxos {
+        #[arg(long, help = "Show the outputs reserved by earlier Funding runs instead")]
+        locked: bool,
+        #[arg(long, help = "Release every locked output")]
+        unlock_all: bool,
+    },
+
+    /// Disconnect the top blocks on regtest to demonstrate a reorg
+    SimulateReorg {
+        #[arg(short = 'n', long, default_value_t = 1, help = "Number of blocks to disconnect")]
+        depth: u64,
+        #[arg(long, help = "Only invalidate, do not mine a replacement chain")]
+        no_mine: bool,
     }
 }
 
@@ -128,27 +220,39 @@ fn main() {
     let cli = Cli::parse();
 
     match &cli.command {
-        Commands::Funding => {
-            interactive::funding::run();
+        Commands::Funding { large_channel, psbt, export_psbt, signed_psbt, rbf_feerate } => {
+            interactive::funding::run(
+                *large_channel,
+                psbt.clone(),
+                export_psbt.clone(),
+                signed_psbt.clone(),
+                *rbf_feerate,
+            );
         },
-        Commands::Commitment { funding_txid } => {
-            interactive::commitment::run(funding_txid.clone());
+        Commands::Commitment { funding_txid, funding_block } => {
+            interactive::commitment::run(*funding_txid, *funding_block);
         },
         Commands::Htlc { funding_txid } => {
-            interactive::htlc::run(funding_txid.clone());
+            interactive::htlc::run(*funding_txid);
         },
         Commands::HtlcTimeout { commitment_txid } => {
-            interactive::htlc_timeout::run(commitment_txid.clone());
+            interactive::htlc_timeout::run(*commitment_txid);
         },
         Commands::SimpleHtlc => {
             interactive::simple_htlc::run();
         },
         Commands::SimpleHtlcClaim { simple_htlc_txid } => {
-            interactive::simple_htlc_claim::run(simple_htlc_txid.clone());
+            interactive::simple_htlc_claim::run(*simple_htlc_txid);
         },
         Commands::Sha256 { input_string } => {
             let mut hasher = Sha256::new();
-            let data = hex::decode(input_string).unwrap();
+            let data = match parse_hex(input_string) {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("❌ Invalid hex input: {}", e);
+                    return;
+                }
+            };
             hasher.update(&data);
             let result = hasher.finalize();
             println!("SHA256 Hash: {:x}", result);
@@ -169,6 +273,46 @@ fn main() {
         Commands::ToHex { input_string } => {
             let data = hex::encode(input_string);
             println!("Hex: {:?}", data);
+        },
+
+        Commands::Hash { input, encoding, algo, payment_hash } => {
+            interactive::hash::run(input.clone(), *encoding, *algo, *payment_hash);
+        },
+
+        Commands::TxInfo { tx_hex, prevout_values, lookup, initiator_basepoint, receiver_basepoint, network } => {
+            let payment_basepoints = initiator_basepoint.zip(*receiver_basepoint);
+            interactive::tx_info::run(tx_hex.clone(), prevout_values.clone(), *lookup, payment_basepoints, *network);
+        },
+
+        Commands::GenVectors {
+            local_seed, remote_seed, funding_txid, funding_output_index, funding_amount,
+            commitment_number, to_self_delay, dust_limit, feerate_per_kw,
+            to_local_msat, to_remote_msat, offered, received, output,
+        } => {
+            let params = VectorParams {
+                local_seed: *local_seed,
+                remote_seed: *remote_seed,
+                funding_txid: *funding_txid,
+                funding_output_index: *funding_output_index,
+                funding_amount_sat: *funding_amount,
+                commitment_number: *commitment_number,
+                to_self_delay: *to_self_delay,
+                dust_limit_satoshis: *dust_limit,
+                feerate_per_kw: *feerate_per_kw,
+                to_local_msat: *to_local_msat,
+                to_remote_msat: *to_remote_msat,
+                offered_htlcs: offered.clone(),
+                received_htlcs: received.clone(),
+            };
+            interactive::gen_vectors::run(params, output.clone());
+        },
+
+        Commands::Utxos { locked, unlock_all } => {
+            interactive::utxos::run(*locked, *unlock_all);
+        },
+
+        Commands::SimulateReorg { depth, no_mine } => {
+            interactive::reorg::run(*depth, *no_mine);
         }
     }
 }
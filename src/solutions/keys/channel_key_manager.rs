@@ -100,6 +100,27 @@ impl ChannelKeyManager {
     }
 }
 
+/// helper (Not an exercise)
+/// BOLT 3 `derive_secret`: derive the secret for `commitment_number` from the
+/// secret of an index that shares all but its lowest `bits` bits with it.
+///
+/// A revealed secret whose index ends in `bits` zeros can derive every
+/// secret in that range, which is what lets a peer store O(log n) secrets.
+pub fn derive_commitment_secret(
+    base_secret: &[u8; 32],
+    bits: u32,
+    commitment_number: u64,
+) -> [u8; 32] {
+    let mut p = *base_secret;
+    for bit_position in (0..bits as usize).rev() {
+        if commitment_number & (1 << bit_position) == (1 << bit_position) {
+            p[bit_position / 8] ^= 1 << (bit_position & 7);
+            p = Sha256::hash(&p).to_byte_array();
+        }
+    }
+    p
+}
+
 impl ChannelKeyManager {
     // helper used for tests
     pub fn get_commitment_keys(
@@ -0,0 +1,110 @@
+use bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::secp256k1::{All, Secp256k1, SecretKey};
+
+use crate::types::{ChannelKeyManager, KeysManager};
+
+/// How a node turns its seed into per-channel secrets.
+///
+/// Implementations differ between Lightning nodes, but everything downstream
+/// of the `ChannelKeyManager` (commitment keys, scripts, signatures) is the
+/// same, so flows can be written once over `impl DerivationScheme`.
+pub trait DerivationScheme {
+    /// Short name of the implementation this scheme follows
+    fn name(&self) -> &'static str;
+
+    /// The basepoint secrets and commitment seed for channel `channel_index`
+    fn derive_channel_keys(&self, channel_index: u32) -> ChannelKeyManager;
+}
+
+/// LND-style: BIP32 derivation under `m/1017'/0'/<key_family>'/0/<channel_index>`
+impl DerivationScheme for KeysManager {
+    fn name(&self) -> &'static str {
+        "lnd"
+    }
+
+    fn derive_channel_keys(&self, channel_index: u32) -> ChannelKeyManager {
+        KeysManager::derive_channel_keys(self, channel_index)
+    }
+}
+
+/// HKDF info strings for the LDK-style scheme, one per derived secret
+pub const HKDF_INFO_NODE_SECRET: &[u8] = b"node secret";
+pub const HKDF_INFO_CHANNEL_MASTER: &[u8] = b"channel master";
+pub const HKDF_INFO_FUNDING: &[u8] = b"funding key";
+pub const HKDF_INFO_REVOCATION_BASE: &[u8] = b"revocation base";
+pub const HKDF_INFO_PAYMENT_BASE: &[u8] = b"payment base";
+pub const HKDF_INFO_DELAYED_PAYMENT_BASE: &[u8] = b"delayed payment base";
+pub const HKDF_INFO_HTLC_BASE: &[u8] = b"htlc base";
+pub const HKDF_INFO_COMMITMENT_SEED: &[u8] = b"commitment seed";
+
+/// helper (Not an exercise)
+/// RFC 5869 HKDF-SHA256, producing a single 32-byte block of output
+pub fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8]) -> [u8; 32] {
+    // Extract: PRK = HMAC(salt, IKM)
+    let mut engine = HmacEngine::<sha256::Hash>::new(salt);
+    engine.input(ikm);
+    let prk = Hmac::<sha256::Hash>::from_engine(engine);
+
+    // Expand: T(1) = HMAC(PRK, info || 0x01)
+    let mut engine = HmacEngine::<sha256::Hash>::new(&prk.to_byte_array());
+    engine.input(info);
+    engine.input(&[0x01]);
+    Hmac::<sha256::Hash>::from_engine(engine).to_byte_array()
+}
+
+/// LDK-style: every secret is an HKDF-SHA256 output rather than a BIP32 child.
+///
+/// ```text
+/// node_secret    = HKDF(salt = "",            IKM = seed,           info = "node secret")
+/// channel_master = HKDF(salt = "",            IKM = seed,           info = "channel master")
+/// channel key    = HKDF(salt = channel_index, IKM = channel_master, info = <key name>)
+/// ```
+///
+/// `channel_index` is 4 bytes big-endian; the key names are the `HKDF_INFO_*`
+/// constants.
+pub struct HkdfKeysManager {
+    pub secp_ctx: Secp256k1<All>,
+    seed: [u8; 32],
+}
+
+impl HkdfKeysManager {
+    pub fn new(seed: [u8; 32]) -> Self {
+        HkdfKeysManager { secp_ctx: Secp256k1::new(), seed }
+    }
+
+    /// The key behind the node's public identity
+    pub fn node_secret(&self) -> SecretKey {
+        Self::secret_key(hkdf_sha256(&[], &self.seed, HKDF_INFO_NODE_SECRET))
+    }
+
+    fn channel_master(&self) -> [u8; 32] {
+        hkdf_sha256(&[], &self.seed, HKDF_INFO_CHANNEL_MASTER)
+    }
+
+    fn secret_key(bytes: [u8; 32]) -> SecretKey {
+        SecretKey::from_slice(&bytes).expect("HKDF output is a valid secret key")
+    }
+}
+
+impl DerivationScheme for HkdfKeysManager {
+    fn name(&self) -> &'static str {
+        "ldk"
+    }
+
+    fn derive_channel_keys(&self, channel_index: u32) -> ChannelKeyManager {
+        let channel_master = self.channel_master();
+        let salt = channel_index.to_be_bytes();
+        let derive = |info: &[u8]| hkdf_sha256(&salt, &channel_master, info);
+
+        ChannelKeyManager {
+            funding_key: Self::secret_key(derive(HKDF_INFO_FUNDING)),
+            revocation_basepoint_secret: Self::secret_key(derive(HKDF_INFO_REVOCATION_BASE)),
+            payment_basepoint_secret: Self::secret_key(derive(HKDF_INFO_PAYMENT_BASE)),
+            delayed_payment_basepoint_secret: Self::secret_key(derive(HKDF_INFO_DELAYED_PAYMENT_BASE)),
+            htlc_basepoint_secret: Self::secret_key(derive(HKDF_INFO_HTLC_BASE)),
+            commitment_seed: derive(HKDF_INFO_COMMITMENT_SEED),
+            secp_ctx: self.secp_ctx.clone(),
+        }
+    }
+}
@@ -1,7 +1,9 @@
 pub mod funding;
 pub mod commitment;
 pub mod htlc;
+pub mod shutdown;
 
 pub use funding::*;
 pub use commitment::*;
-pub use htlc::*;
\ No newline at end of file
+pub use htlc::*;
+pub use shutdown::*;
\ No newline at end of file
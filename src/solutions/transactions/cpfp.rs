@@ -0,0 +1,97 @@
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
+
+/// An output spent by a CPFP child, with the weight its signature will add
+/// (see `ANCHOR_INPUT_SATISFACTION_WEIGHT` and
+/// `P2WPKH_INPUT_SATISFACTION_WEIGHT`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpfpInput {
+    pub outpoint: OutPoint,
+    pub value_sat: u64,
+    pub satisfaction_weight: u64,
+}
+
+/// Why a CPFP child could not be built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CpfpError {
+    /// The parent has no output at this index
+    OutputNotFound { output_index: u32 },
+    /// The inputs cannot cover the child's fee plus a non-dust output
+    InsufficientFunds { input_sat: u64, required_sat: u64 },
+}
+
+/// helper (Not an exercise)
+/// Build a child spending output `parent_output_index` of an unconfirmed
+/// `parent` (its change, or our anchor on a commitment) that pays enough
+/// fee for the parent and child together to reach
+/// `target_package_feerate_per_kw`.
+///
+/// An anchor is only 330 sats, so `wallet_inputs` can add confirmed wallet
+/// outputs to pay the fee; everything left goes to `destination_script`.
+/// The child pays at least the target for its own weight, even if the
+/// parent already meets it. Returns the unsigned child and the package
+/// feerate it achieves, in sat/kw.
+///
+/// Every input signals RBF, so the child can be bumped again. Its nSequence
+/// disables relative timelocks: a CSV-locked parent output (like `to_remote`
+/// on anchor channels) needs the sequence set before signing.
+pub fn create_cpfp_transaction(
+    parent: &Transaction,
+    parent_fee_sat: u64,
+    parent_output_index: u32,
+    parent_output_satisfaction_weight: u64,
+    wallet_inputs: &[CpfpInput],
+    destination_script: &ScriptBuf,
+    target_package_feerate_per_kw: u64,
+) -> Result<(Transaction, u64), CpfpError> {
+    let parent_output = parent
+        .output
+        .get(parent_output_index as usize)
+        .ok_or(CpfpError::OutputNotFound { output_index: parent_output_index })?;
+
+    let mut inputs = vec![CpfpInput {
+        outpoint: OutPoint::new(parent.compute_txid(), parent_output_index),
+        value_sat: parent_output.value.to_sat(),
+        satisfaction_weight: parent_output_satisfaction_weight,
+    }];
+    inputs.extend_from_slice(wallet_inputs);
+
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: inputs
+            .iter()
+            .map(|input| TxIn {
+                previous_output: input.outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            })
+            .collect(),
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: destination_script.clone(),
+        }],
+    };
+
+    let parent_weight = parent.weight().to_wu();
+    let child_weight =
+        tx.weight().to_wu() + inputs.iter().map(|input| input.satisfaction_weight).sum::<u64>();
+
+    // Round up, so the package never lands just under the target
+    let package_fee_sat = (target_package_feerate_per_kw * (parent_weight + child_weight)).div_ceil(1000);
+    let own_fee_sat = (target_package_feerate_per_kw * child_weight).div_ceil(1000);
+    let child_fee_sat = package_fee_sat.saturating_sub(parent_fee_sat).max(own_fee_sat);
+
+    let input_sat: u64 = inputs.iter().map(|input| input.value_sat).sum();
+    let required_sat = child_fee_sat + destination_script.minimal_non_dust().to_sat();
+    if input_sat < required_sat {
+        return Err(CpfpError::InsufficientFunds { input_sat, required_sat });
+    }
+    tx.output[0].value = Amount::from_sat(input_sat - child_fee_sat);
+
+    let package_feerate_per_kw = (parent_fee_sat + child_fee_sat) * 1000 / (parent_weight + child_weight);
+    Ok((tx, package_feerate_per_kw))
+}
@@ -1,3 +1,9 @@
+use crate::transactions::weights::{
+    commitment_tx_weight, htlc_success_weight, htlc_timeout_weight, ANCHOR_CPFP_WEIGHT,
+    DELAYED_OUTPUT_SWEEP_WEIGHT,
+};
+use crate::types::ChannelType;
+
 
 // FEE CALCULATIONS
 // ============================================================================
@@ -8,18 +14,16 @@ pub fn calculate_commitment_tx_fee(
     feerate_per_kw: u64,
     num_untrimmed_htlcs: usize,
 ) -> u64 {
-    let weight = 724 + (172 * num_untrimmed_htlcs);
-    (feerate_per_kw * weight as u64) / 1000
+    let weight = commitment_tx_weight(ChannelType::StaticRemoteKey, num_untrimmed_htlcs);
+    (feerate_per_kw * weight) / 1000
 }
 
 pub fn calculate_htlc_timeout_tx_fee(feerate_per_kw: u64) -> u64 {
-    const HTLC_TX_WEIGHT: u64 = 663;
-    (feerate_per_kw * HTLC_TX_WEIGHT) / 1000
+    (feerate_per_kw * htlc_timeout_weight(ChannelType::StaticRemoteKey)) / 1000
 }
 
 pub fn calculate_htlc_success_tx_fee(feerate_per_kw: u64) -> u64 {
-    const HTLC_TX_WEIGHT: u64 = 703;
-    (feerate_per_kw * HTLC_TX_WEIGHT) / 1000
+    (feerate_per_kw * htlc_success_weight(ChannelType::StaticRemoteKey)) / 1000
 }
 
 /// For non-anchor & non-V3 channels, an HTLC is considered "dust" if its amount is less than the dust limit
@@ -41,3 +45,262 @@ pub fn is_htlc_dust(
         
     htlc_amount_sat < dust_limit_satoshis + htlc_tx_fee
 }
+
+/// helper (Not an exercise)
+/// `is_htlc_dust` for a specific channel type.
+///
+/// With `option_anchors_zero_fee_htlc_tx` the HTLC-timeout and HTLC-success
+/// transactions pay no fee, so an HTLC is only compared against the dust limit.
+pub fn is_htlc_dust_for_channel_type(
+    htlc_amount_sat: u64,
+    dust_limit_satoshis: u64,
+    feerate_per_kw: u64,
+    outbound_htlc: bool,
+    channel_type: ChannelType,
+) -> bool {
+    let htlc_feerate_per_kw = channel_type.htlc_tx_feerate_per_kw(feerate_per_kw);
+    let weight = if outbound_htlc {
+        htlc_timeout_weight(channel_type)
+    } else {
+        htlc_success_weight(channel_type)
+    };
+
+    htlc_amount_sat < dust_limit_satoshis + (htlc_feerate_per_kw * weight) / 1000
+}
+
+/// Whether an HTLC is trimmed on each side's commitment transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HtlcTrimming {
+    pub trimmed_on_local: bool,
+    pub trimmed_on_remote: bool,
+}
+
+impl std::fmt::Display for HtlcTrimming {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let side = |trimmed: bool| if trimmed { "trimmed" } else { "present" };
+        write!(
+            f,
+            "{} on local, {} on remote",
+            side(self.trimmed_on_local),
+            side(self.trimmed_on_remote)
+        )
+    }
+}
+
+/// helper (Not an exercise)
+/// Evaluate dust separately for each side's commitment.
+///
+/// An HTLC we offered is an offered HTLC on our commitment (claimed with
+/// HTLC-timeout) but a received HTLC on theirs (claimed with HTLC-success),
+/// and each commitment uses its owner's dust limit. So the same HTLC can be
+/// trimmed on one commitment and present on the other.
+///
+/// On zero-fee-HTLC anchor channels only the dust limits matter.
+pub fn htlc_trimming(
+    htlc_amount_sat: u64,
+    local_dust_limit_satoshis: u64,
+    remote_dust_limit_satoshis: u64,
+    feerate_per_kw: u64,
+    offered_by_local: bool,
+    channel_type: ChannelType,
+) -> HtlcTrimming {
+    HtlcTrimming {
+        trimmed_on_local: is_htlc_dust_for_channel_type(
+            htlc_amount_sat,
+            local_dust_limit_satoshis,
+            feerate_per_kw,
+            offered_by_local,
+            channel_type,
+        ),
+        trimmed_on_remote: is_htlc_dust_for_channel_type(
+            htlc_amount_sat,
+            remote_dust_limit_satoshis,
+            feerate_per_kw,
+            !offered_by_local,
+            channel_type,
+        ),
+    }
+}
+
+/// BOLT 2: the lowest feerate a node may propose (253 sat/kw ≈ 1 sat/vB).
+pub const FEERATE_FLOOR_PER_KW: u64 = 253;
+
+/// Why a counterparty's `update_fee` was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateFeeError {
+    /// Below what our fee estimator considers safe to get the commitment confirmed
+    TooLow { feerate_per_kw: u64, min_feerate_per_kw: u64 },
+    /// Above what our fee estimator considers reasonable (fee siphoning)
+    TooHigh { feerate_per_kw: u64, max_feerate_per_kw: u64 },
+    /// The funder cannot pay the new commitment fee and keep its reserve
+    CannotAffordFee { fee_sat: u64, funder_balance_sat: u64, channel_reserve_sat: u64 },
+}
+
+/// helper (Not an exercise)
+/// Check a counterparty-proposed feerate before accepting `update_fee`.
+///
+/// The feerate must lie within our fee estimator's window, and the funder
+/// must still cover the commitment fee out of its balance without dipping
+/// into its channel reserve.
+pub fn validate_update_fee(
+    feerate_per_kw: u64,
+    min_feerate_per_kw: u64,
+    max_feerate_per_kw: u64,
+    funder_balance_sat: u64,
+    channel_reserve_sat: u64,
+    num_untrimmed_htlcs: usize,
+) -> Result<(), UpdateFeeError> {
+    let min_feerate_per_kw = min_feerate_per_kw.max(FEERATE_FLOOR_PER_KW);
+    if feerate_per_kw < min_feerate_per_kw {
+        return Err(UpdateFeeError::TooLow { feerate_per_kw, min_feerate_per_kw });
+    }
+    if feerate_per_kw > max_feerate_per_kw {
+        return Err(UpdateFeeError::TooHigh { feerate_per_kw, max_feerate_per_kw });
+    }
+
+    let fee_sat = calculate_commitment_tx_fee(feerate_per_kw, num_untrimmed_htlcs);
+    if fee_sat + channel_reserve_sat > funder_balance_sat {
+        return Err(UpdateFeeError::CannotAffordFee {
+            fee_sat,
+            funder_balance_sat,
+            channel_reserve_sat,
+        });
+    }
+
+    Ok(())
+}
+
+/// Value of each of the two anchor outputs, paid for by the funder
+pub const ANCHOR_OUTPUT_VALUE_SATOSHI: u64 = 330;
+
+/// What it costs us to close a channel unilaterally, broken down by step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForceCloseCost {
+    /// Fee of the commitment transaction itself
+    pub commitment_fee_sat: u64,
+    /// Fees of the HTLC-timeout and HTLC-success transactions
+    pub htlc_fees_sat: u64,
+    /// Fee of the CPFP child that spends our anchor to bump the commitment
+    /// (anchor channels only). The anchor's own value comes back in the child.
+    pub anchor_cpfp_fee_sat: u64,
+    /// Fees to sweep `to_local` and each second-stage output once their delay expires
+    pub sweep_fees_sat: u64,
+}
+
+impl ForceCloseCost {
+    pub fn total_sat(&self) -> u64 {
+        self.commitment_fee_sat + self.htlc_fees_sat + self.anchor_cpfp_fee_sat + self.sweep_fees_sat
+    }
+}
+
+/// helper (Not an exercise)
+/// Estimate the on-chain cost of force-closing with `num_offered_htlcs` and
+/// `num_received_htlcs` untrimmed HTLCs at `feerate_per_kw`.
+///
+/// On anchor channels the commitment and zero-fee HTLC transactions are
+/// bumped to the target feerate by us, so they are costed at `feerate_per_kw`
+/// too, plus the CPFP child that carries the commitment's bump. A
+/// cooperative close only pays for one small transaction.
+pub fn estimate_force_close_cost(
+    channel_type: ChannelType,
+    num_offered_htlcs: usize,
+    num_received_htlcs: usize,
+    feerate_per_kw: u64,
+) -> ForceCloseCost {
+    let num_htlcs = num_offered_htlcs + num_received_htlcs;
+
+    let commitment_fee_sat = (feerate_per_kw * commitment_tx_weight(channel_type, num_htlcs)) / 1000;
+    let htlc_fees_sat = num_offered_htlcs as u64 * (feerate_per_kw * htlc_timeout_weight(channel_type)) / 1000
+        + num_received_htlcs as u64 * (feerate_per_kw * htlc_success_weight(channel_type)) / 1000;
+    let anchor_cpfp_fee_sat = match channel_type {
+        ChannelType::StaticRemoteKey => 0,
+        ChannelType::AnchorsZeroFeeHtlcTx => (feerate_per_kw * ANCHOR_CPFP_WEIGHT) / 1000,
+    };
+    // Our to_local plus one delayed output per second-stage transaction
+    let sweep_fees_sat = (1 + num_htlcs as u64) * (feerate_per_kw * DELAYED_OUTPUT_SWEEP_WEIGHT) / 1000;
+
+    ForceCloseCost {
+        commitment_fee_sat,
+        htlc_fees_sat,
+        anchor_cpfp_fee_sat,
+        sweep_fees_sat,
+    }
+}
+
+/// Where every millisatoshi of a commitment's balances ends up on-chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitmentFeeBreakdown {
+    /// Our balance in whole satoshis, less the fee if we are the funder
+    pub to_local_sat: u64,
+    /// Their balance in whole satoshis, less the fee if they are the funder
+    pub to_remote_sat: u64,
+    /// Untrimmed HTLC outputs, each rounded down to whole satoshis
+    pub htlcs_sat: u64,
+    /// The commitment transaction fee paid by the funder
+    pub commitment_fee_sat: u64,
+    /// A to_local or to_remote below the dust limit, trimmed and left to fees
+    pub trimmed_sat: u64,
+    /// Sub-satoshi remainders dropped by rounding, which BOLT 3 leaves to fees
+    pub rounded_to_fees_msat: u64,
+}
+
+impl CommitmentFeeBreakdown {
+    /// Everything the commitment pays to miners
+    pub fn total_fees_msat(&self) -> u64 {
+        (self.commitment_fee_sat + self.trimmed_sat) * 1000 + self.rounded_to_fees_msat
+    }
+}
+
+/// helper (Not an exercise)
+/// Convert msat balances and untrimmed HTLCs into the satoshi amounts of a
+/// commitment transaction, keeping track of what rounding gives to fees.
+///
+/// BOLT 3 rounds each output down to whole satoshis, so the outputs and fee
+/// alone can fall up to a few satoshis short of the channel's msat balances.
+/// `rounded_to_fees_msat` is exactly that shortfall. A to_local or to_remote
+/// left below `dust_limit_satoshis` once the fee is paid is trimmed too, and
+/// counted in `trimmed_sat` instead, so the books balance:
+/// `(to_local + to_remote + htlcs + fee + trimmed) * 1000 + rounded_to_fees_msat`
+/// equals the msat total that went in.
+pub fn commitment_fee_breakdown(
+    to_local_msat: u64,
+    to_remote_msat: u64,
+    htlc_amounts_msat: &[u64],
+    dust_limit_satoshis: u64,
+    feerate_per_kw: u64,
+    local_is_funder: bool,
+) -> CommitmentFeeBreakdown {
+    let htlcs_sat: u64 = htlc_amounts_msat.iter().map(|amount| amount / 1000).sum();
+    let rounded_to_fees_msat = to_local_msat % 1000
+        + to_remote_msat % 1000
+        + htlc_amounts_msat.iter().map(|amount| amount % 1000).sum::<u64>();
+
+    // The funder pays the fee out of its rounded-down balance, up to all of it
+    let fee = calculate_commitment_tx_fee(feerate_per_kw, htlc_amounts_msat.len());
+    let (funder_sat, fundee_sat) = if local_is_funder {
+        (to_local_msat / 1000, to_remote_msat / 1000)
+    } else {
+        (to_remote_msat / 1000, to_local_msat / 1000)
+    };
+    let commitment_fee_sat = fee.min(funder_sat);
+    let funder_sat = funder_sat - commitment_fee_sat;
+    let (to_local_sat, to_remote_sat) = if local_is_funder {
+        (funder_sat, fundee_sat)
+    } else {
+        (fundee_sat, funder_sat)
+    };
+
+    // Outputs below the dust limit are left off the transaction
+    let trim = |value_sat: u64| if value_sat < dust_limit_satoshis { 0 } else { value_sat };
+    let trimmed_sat = (to_local_sat - trim(to_local_sat)) + (to_remote_sat - trim(to_remote_sat));
+    let (to_local_sat, to_remote_sat) = (trim(to_local_sat), trim(to_remote_sat));
+
+    CommitmentFeeBreakdown {
+        to_local_sat,
+        to_remote_sat,
+        htlcs_sat,
+        commitment_fee_sat,
+        trimmed_sat,
+        rounded_to_fees_msat,
+    }
+}
@@ -7,6 +7,8 @@ use bitcoin::Txid;
 use bitcoin::PublicKey as BitcoinPublicKey;
 
 use crate::scripts::funding::create_funding_script;
+use crate::transactions::fees::FEERATE_FLOOR_PER_KW;
+use crate::types::ChannelPublicKeys;
 
 /// Exercise 6: Create funding transaction
 pub fn create_funding_transaction(
@@ -48,3 +50,342 @@ pub fn create_funding_transaction(
     }
 }
 
+
+/// Change below this is not worth an output and is left to the fee instead
+pub const FUNDING_CHANGE_DUST_LIMIT_SATOSHIS: u64 = 546;
+
+/// Weight a signed P2WPKH input adds beyond its empty scriptSig: the
+/// witness `<sig> <pubkey>` plus the segwit marker and flag
+pub const P2WPKH_INPUT_SATISFACTION_WEIGHT: u64 = 111;
+
+/// Weight a signed P2PKH input adds: its 107-byte scriptSig, as in BOLT 3
+/// Appendix B
+pub const P2PKH_INPUT_SATISFACTION_WEIGHT: u64 = 428;
+
+/// Why a funding transaction with change could not be built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FundingChangeError {
+    /// The input cannot cover the funding amount plus the fee
+    InsufficientFunds { input_amount_sat: u64, required_sat: u64 },
+}
+
+/// helper (Not an exercise)
+/// `create_funding_transaction` for an input larger than the channel: the
+/// remainder, less a fee at `feerate_per_kw`, goes back to `change_script`.
+///
+/// The fee covers the unsigned transaction plus `input_satisfaction_weight`
+/// for the signature the input still needs (see the `*_SATISFACTION_WEIGHT`
+/// constants). Change below `FUNDING_CHANGE_DUST_LIMIT_SATOSHIS` is dropped
+/// and goes to the fee. The funding output stays at index 0.
+#[allow(clippy::too_many_arguments)]
+pub fn create_funding_transaction_with_change(
+    input: OutPoint,
+    input_amount_sat: u64,
+    input_satisfaction_weight: u64,
+    funding_amount_sat: u64,
+    local_funding_pubkey: &BitcoinPublicKey,
+    remote_funding_pubkey: &BitcoinPublicKey,
+    change_script: &ScriptBuf,
+    feerate_per_kw: u64,
+) -> Result<Transaction, FundingChangeError> {
+    let mut tx = create_funding_transaction(
+        input.txid,
+        input.vout,
+        funding_amount_sat,
+        local_funding_pubkey,
+        remote_funding_pubkey,
+    );
+    let fee_for = |tx: &Transaction| {
+        feerate_per_kw * (tx.weight().to_wu() + input_satisfaction_weight) / 1000
+    };
+
+    // Price the transaction with a change output first
+    tx.output.push(TxOut {
+        value: Amount::ZERO,
+        script_pubkey: change_script.clone(),
+    });
+    let fee = fee_for(&tx);
+    let required_sat = funding_amount_sat + fee;
+    if input_amount_sat < required_sat {
+        tx.output.pop();
+        let required_sat = funding_amount_sat + fee_for(&tx);
+        if input_amount_sat < required_sat {
+            return Err(FundingChangeError::InsufficientFunds { input_amount_sat, required_sat });
+        }
+        return Ok(tx);
+    }
+
+    let change_sat = input_amount_sat - required_sat;
+    if change_sat < FUNDING_CHANGE_DUST_LIMIT_SATOSHIS {
+        tx.output.pop();
+    } else {
+        tx.output[1].value = Amount::from_sat(change_sat);
+    }
+    Ok(tx)
+}
+
+
+/// BIP125: a replacement must pay for its own relay at this feerate
+/// (1 sat/vbyte), on top of the fee of the transaction it replaces
+pub const INCREMENTAL_RELAY_FEERATE_PER_KW: u64 = 250;
+
+/// Why a funding transaction could not be fee-bumped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FundingBumpError {
+    /// The input cannot cover the funding amount plus the new fee
+    InsufficientFunds { input_amount_sat: u64, required_sat: u64 },
+    /// The new fee does not beat the old one by the incremental relay fee
+    FeeBumpTooSmall { old_fee_sat: u64, new_fee_sat: u64, min_fee_sat: u64 },
+}
+
+/// helper (Not an exercise)
+/// Rebuild an unconfirmed funding transaction (as made by
+/// `create_funding_transaction_with_change`) to pay `feerate_per_kw`, so it
+/// can replace the original under BIP125. Nodes only accept the replacement
+/// if the original signalled replaceability: that builder leaves nSequence
+/// at `Sequence::MAX` (as in BOLT 3 Appendix B), so set
+/// `Sequence::ENABLE_RBF_NO_LOCKTIME` on its inputs before signing.
+///
+/// Every input signals replaceability and has its signature cleared, so the
+/// result must be signed again. The funding output keeps its amount and
+/// index; the extra fee comes out of the change, which is dropped if it
+/// falls below `FUNDING_CHANGE_DUST_LIMIT_SATOSHIS`. The txid changes, so
+/// any commitment transaction signed against the old one is void.
+pub fn bump_funding_transaction_fee(
+    tx: &Transaction,
+    input_amount_sat: u64,
+    input_satisfaction_weight: u64,
+    feerate_per_kw: u64,
+) -> Result<Transaction, FundingBumpError> {
+    let output_sum = |tx: &Transaction| tx.output.iter().map(|output| output.value.to_sat()).sum::<u64>();
+    let fee_for = |tx: &Transaction| {
+        feerate_per_kw * (tx.weight().to_wu() + input_satisfaction_weight) / 1000
+    };
+    let old_fee_sat = input_amount_sat.saturating_sub(output_sum(tx));
+
+    let mut bumped = tx.clone();
+    for input in &mut bumped.input {
+        input.sequence = Sequence::ENABLE_RBF_NO_LOCKTIME;
+        input.script_sig = ScriptBuf::new();
+        input.witness = Witness::new();
+    }
+
+    let funding_amount_sat = bumped.output[0].value.to_sat();
+    let with_change_sat = funding_amount_sat + fee_for(&bumped);
+    match input_amount_sat.checked_sub(with_change_sat) {
+        Some(change_sat) if bumped.output.len() > 1 && change_sat >= FUNDING_CHANGE_DUST_LIMIT_SATOSHIS => {
+            bumped.output[1].value = Amount::from_sat(change_sat);
+        }
+        _ => {
+            bumped.output.truncate(1);
+            let required_sat = funding_amount_sat + fee_for(&bumped);
+            if input_amount_sat < required_sat {
+                return Err(FundingBumpError::InsufficientFunds { input_amount_sat, required_sat });
+            }
+        }
+    }
+
+    let new_fee_sat = input_amount_sat - output_sum(&bumped);
+    let min_fee_sat = old_fee_sat
+        + INCREMENTAL_RELAY_FEERATE_PER_KW * (bumped.weight().to_wu() + input_satisfaction_weight) / 1000;
+    if new_fee_sat < min_fee_sat {
+        return Err(FundingBumpError::FeeBumpTooSmall { old_fee_sat, new_fee_sat, min_fee_sat });
+    }
+
+    Ok(bumped)
+}
+
+
+/// BOLT 2: without `option_support_large_channel`, `funding_satoshis` must be below 2^24.
+pub const MAX_FUNDING_SATOSHIS_NO_WUMBO: u64 = (1 << 24) - 1;
+
+/// Why a channel's funding amount was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FundingAmountError {
+    /// A channel needs some capacity
+    Zero,
+    /// Above the limit for the negotiated features
+    TooLarge { funding_satoshis: u64, max_funding_satoshis: u64 },
+}
+
+/// helper (Not an exercise)
+/// Check a channel's size against the BOLT 2 limits. `large_channel` is
+/// set when both peers signalled `option_support_large_channel` ("wumbo").
+pub fn check_funding_amount(
+    funding_satoshis: u64,
+    large_channel: bool,
+) -> Result<(), FundingAmountError> {
+    let max_funding_satoshis = if large_channel {
+        Amount::MAX_MONEY.to_sat()
+    } else {
+        MAX_FUNDING_SATOSHIS_NO_WUMBO
+    };
+
+    if funding_satoshis == 0 {
+        return Err(FundingAmountError::Zero);
+    }
+    if funding_satoshis > max_funding_satoshis {
+        return Err(FundingAmountError::TooLarge { funding_satoshis, max_funding_satoshis });
+    }
+
+    Ok(())
+}
+
+/// The fields of an incoming `open_channel` that we check before accepting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenChannelParams {
+    pub funding_satoshis: u64,
+    pub dust_limit_satoshis: u64,
+    pub channel_reserve_satoshis: u64,
+    pub to_self_delay: u16,
+    pub feerate_per_kw: u64,
+}
+
+/// The limits we are willing to accept a channel under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelOpenLimits {
+    pub min_funding_satoshis: u64,
+    pub max_dust_limit_satoshis: u64,
+    pub max_to_self_delay: u16,
+    pub min_feerate_per_kw: u64,
+    pub max_feerate_per_kw: u64,
+    /// Both peers signalled `option_support_large_channel`
+    pub large_channel: bool,
+}
+
+/// Why an `open_channel` was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelOpenError {
+    /// Too small to be worth the on-chain fees
+    FundingTooSmall { funding_satoshis: u64, min_funding_satoshis: u64 },
+    /// Above the limit for the negotiated features
+    FundingTooLarge { funding_satoshis: u64, max_funding_satoshis: u64 },
+    /// Their outputs below this would be trimmed from our commitment
+    DustLimitTooHigh { dust_limit_satoshis: u64, max_dust_limit_satoshis: u64 },
+    /// A reserve they could never spend down to
+    ReserveBelowDustLimit { channel_reserve_satoshis: u64, dust_limit_satoshis: u64 },
+    /// Our funds would be locked longer than we accept
+    ToSelfDelayTooLarge { to_self_delay: u16, max_to_self_delay: u16 },
+    /// Outside our fee estimator's window
+    FeerateOutOfRange { feerate_per_kw: u64, min_feerate_per_kw: u64, max_feerate_per_kw: u64 },
+}
+
+/// helper (Not an exercise)
+/// Check the parameters of an incoming `open_channel` against our limits,
+/// returning the first one we cannot accept.
+pub fn validate_open_channel(
+    params: &OpenChannelParams,
+    limits: &ChannelOpenLimits,
+) -> Result<(), ChannelOpenError> {
+    let min_funding_satoshis = limits.min_funding_satoshis.max(1);
+    if params.funding_satoshis < min_funding_satoshis {
+        return Err(ChannelOpenError::FundingTooSmall {
+            funding_satoshis: params.funding_satoshis,
+            min_funding_satoshis,
+        });
+    }
+    if let Err(FundingAmountError::TooLarge { funding_satoshis, max_funding_satoshis }) =
+        check_funding_amount(params.funding_satoshis, limits.large_channel)
+    {
+        return Err(ChannelOpenError::FundingTooLarge { funding_satoshis, max_funding_satoshis });
+    }
+
+    if params.dust_limit_satoshis > limits.max_dust_limit_satoshis {
+        return Err(ChannelOpenError::DustLimitTooHigh {
+            dust_limit_satoshis: params.dust_limit_satoshis,
+            max_dust_limit_satoshis: limits.max_dust_limit_satoshis,
+        });
+    }
+    if params.channel_reserve_satoshis < params.dust_limit_satoshis {
+        return Err(ChannelOpenError::ReserveBelowDustLimit {
+            channel_reserve_satoshis: params.channel_reserve_satoshis,
+            dust_limit_satoshis: params.dust_limit_satoshis,
+        });
+    }
+
+    if params.to_self_delay > limits.max_to_self_delay {
+        return Err(ChannelOpenError::ToSelfDelayTooLarge {
+            to_self_delay: params.to_self_delay,
+            max_to_self_delay: limits.max_to_self_delay,
+        });
+    }
+
+    let min_feerate_per_kw = limits.min_feerate_per_kw.max(FEERATE_FLOOR_PER_KW);
+    if params.feerate_per_kw < min_feerate_per_kw || params.feerate_per_kw > limits.max_feerate_per_kw {
+        return Err(ChannelOpenError::FeerateOutOfRange {
+            feerate_per_kw: params.feerate_per_kw,
+            min_feerate_per_kw,
+            max_feerate_per_kw: limits.max_feerate_per_kw,
+        });
+    }
+
+    Ok(())
+}
+
+/// The funding pubkey and basepoints from an `open_channel` or
+/// `accept_channel`, as the 33 raw bytes each field carries on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceivedChannelKeys {
+    pub funding_pubkey: [u8; 33],
+    pub revocation_basepoint: [u8; 33],
+    pub payment_basepoint: [u8; 33],
+    pub delayed_payment_basepoint: [u8; 33],
+    pub htlc_basepoint: [u8; 33],
+}
+
+/// Why a counterparty's keys were refused. Fields are named as in BOLT 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BasepointError {
+    /// Not a valid compressed secp256k1 point
+    InvalidPoint { field: &'static str },
+    /// Two of their keys are the same point
+    Duplicate { field: &'static str, other_field: &'static str },
+    /// One of their keys is one of ours
+    SameAsOurs { field: &'static str },
+}
+
+/// helper (Not an exercise)
+/// Parse the keys a counterparty sent while opening a channel.
+///
+/// Every key must be a valid compressed point, distinct from their other
+/// keys and from all of ours: a peer reusing one of our basepoints could,
+/// for instance, make our own revocation key spendable by them.
+pub fn validate_counterparty_keys(
+    received: &ReceivedChannelKeys,
+    ours: &ChannelPublicKeys,
+) -> Result<ChannelPublicKeys, BasepointError> {
+    let fields: [(&'static str, &[u8; 33]); 5] = [
+        ("funding_pubkey", &received.funding_pubkey),
+        ("revocation_basepoint", &received.revocation_basepoint),
+        ("payment_basepoint", &received.payment_basepoint),
+        ("delayed_payment_basepoint", &received.delayed_payment_basepoint),
+        ("htlc_basepoint", &received.htlc_basepoint),
+    ];
+    let our_keys = [
+        ours.funding_pubkey,
+        ours.revocation_basepoint,
+        ours.payment_basepoint,
+        ours.delayed_payment_basepoint,
+        ours.htlc_basepoint,
+    ];
+
+    let mut keys: Vec<(&'static str, PublicKey)> = Vec::with_capacity(fields.len());
+    for (field, bytes) in fields {
+        let key = PublicKey::from_slice(bytes).map_err(|_| BasepointError::InvalidPoint { field })?;
+        if let Some(&(other_field, _)) = keys.iter().find(|(_, other)| *other == key) {
+            return Err(BasepointError::Duplicate { field, other_field });
+        }
+        if our_keys.contains(&key) {
+            return Err(BasepointError::SameAsOurs { field });
+        }
+        keys.push((field, key));
+    }
+
+    Ok(ChannelPublicKeys {
+        funding_pubkey: keys[0].1,
+        revocation_basepoint: keys[1].1,
+        payment_basepoint: keys[2].1,
+        delayed_payment_basepoint: keys[3].1,
+        htlc_basepoint: keys[4].1,
+    })
+}
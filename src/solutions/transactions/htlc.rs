@@ -7,7 +7,49 @@ use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
 use crate::keys::derive_revocation_public_key;
 use crate::scripts::create_to_local_script;
 use crate::transactions::fees::{calculate_htlc_success_tx_fee, calculate_htlc_timeout_tx_fee};
-use crate::types::{CommitmentKeys, ChannelKeyManager};
+use crate::transactions::sanity::{check_htlc_success_tx_encoding, check_htlc_timeout_tx_encoding};
+use crate::types::{ChannelType, CommitmentKeys, ChannelKeyManager};
+
+/// helper (Not an exercise)
+/// Witness spending an offered HTLC output through an HTLC-timeout transaction.
+///
+/// Witness stack: [0, remote_htlc_sig, local_htlc_sig, 0 (false), htlc_script]
+/// The leading empty element is consumed by the OP_CHECKMULTISIG off-by-one bug.
+/// The layout is the same with anchors, but the remote signature then ends in
+/// SINGLE|ANYONECANPAY (see `ChannelType::remote_htlc_sighash_type`) and the
+/// input needs `ChannelType::htlc_input_sequence`.
+pub fn create_htlc_timeout_witness(
+    remote_htlc_signature: &[u8],
+    local_htlc_signature: &[u8],
+    htlc_script: &ScriptBuf,
+) -> Witness {
+    Witness::from_slice(&[
+        &[][..],
+        remote_htlc_signature,
+        local_htlc_signature,
+        &[][..],
+        htlc_script.as_bytes(),
+    ])
+}
+
+/// helper (Not an exercise)
+/// Witness spending a received HTLC output through an HTLC-success transaction.
+///
+/// Witness stack: [0, remote_htlc_sig, local_htlc_sig, payment_preimage, htlc_script]
+pub fn create_htlc_success_witness(
+    remote_htlc_signature: &[u8],
+    local_htlc_signature: &[u8],
+    payment_preimage: &[u8; 32],
+    htlc_script: &ScriptBuf,
+) -> Witness {
+    Witness::from_slice(&[
+        &[][..],
+        remote_htlc_signature,
+        local_htlc_signature,
+        &payment_preimage[..],
+        htlc_script.as_bytes(),
+    ])
+}
 
 /// Exercise 23: Create HTLC-timeout transaction
 pub fn create_htlc_timeout_transaction(
@@ -55,7 +97,34 @@ pub fn create_htlc_timeout_transaction(
     }
 }
 
+/// helper (Not an exercise)
+/// `create_htlc_timeout_transaction` for the given channel type. With anchors
+/// the transaction is zero-fee (fees are attached later with
+/// SINGLE|ANYONECANPAY) and its input uses sequence 1 to satisfy the HTLC
+/// script's `1 OP_CSV`.
+pub fn create_htlc_timeout_transaction_for_channel_type(
+    htlc_outpoint: OutPoint,
+    htlc_amount: u64,
+    cltv_expiry: u32,
+    local_keys: &CommitmentKeys,
+    to_self_delay: u16,
+    feerate_per_kw: u64,
+    channel_type: ChannelType,
+) -> Transaction {
+    let mut tx = create_htlc_timeout_transaction(
+        htlc_outpoint,
+        htlc_amount,
+        cltv_expiry,
+        local_keys,
+        to_self_delay,
+        channel_type.htlc_tx_feerate_per_kw(feerate_per_kw),
+    );
+    tx.input[0].sequence = channel_type.htlc_input_sequence();
+    tx
+}
+
 /// Exercise 24: Finalize HTLC-timeout transaction
+#[allow(clippy::too_many_arguments)]
 pub fn finalize_htlc_timeout(
     keys_manager: ChannelKeyManager,
     tx: Transaction,
@@ -64,7 +133,13 @@ pub fn finalize_htlc_timeout(
     htlc_amount: u64,
     remote_htlc_signature: Vec<u8>,
     local_htlc_privkey: SecretKey,
+    channel_type: ChannelType,
 ) -> Transaction {
+    // Refuse to sign a transaction that breaks the BOLT 3 encoding for the
+    // channel's negotiated type (provided)
+    if let Err(e) = check_htlc_timeout_tx_encoding(&tx, channel_type) {
+        panic!("Refusing to sign malformed HTLC-timeout transaction: {:?}", e);
+    }
 
     // Sign the transaction input with the local HTLC private key
     let local_htlc_signature = keys_manager.sign_transaction_input_sighash_all(
@@ -76,13 +151,8 @@ pub fn finalize_htlc_timeout(
     );
 
     // Build witness: [0, remote_sig, local_sig, 0 (false for timeout), script]
-    let witness = Witness::from_slice(&[
-        &[][..], 
-        &remote_htlc_signature[..],
-        &local_htlc_signature[..],
-        &[][..],
-        htlc_script.as_bytes(),
-    ]);
+    let witness =
+        create_htlc_timeout_witness(&remote_htlc_signature, &local_htlc_signature, htlc_script);
 
     // Attach witness to transaction
     let mut signed_tx = tx;
@@ -133,7 +203,30 @@ pub fn create_htlc_success_transaction(
     }
 }
 
+/// helper (Not an exercise)
+/// `create_htlc_success_transaction` for the given channel type: zero-fee
+/// with input sequence 1 on anchor channels, as for HTLC-timeout.
+pub fn create_htlc_success_transaction_for_channel_type(
+    htlc_outpoint: OutPoint,
+    htlc_amount: u64,
+    local_keys: &CommitmentKeys,
+    to_self_delay: u16,
+    feerate_per_kw: u64,
+    channel_type: ChannelType,
+) -> Transaction {
+    let mut tx = create_htlc_success_transaction(
+        htlc_outpoint,
+        htlc_amount,
+        local_keys,
+        to_self_delay,
+        channel_type.htlc_tx_feerate_per_kw(feerate_per_kw),
+    );
+    tx.input[0].sequence = channel_type.htlc_input_sequence();
+    tx
+}
+
 /// Exercise 27: Finalize HTLC-success transaction
+#[allow(clippy::too_many_arguments)]
 pub fn finalize_htlc_success(
     keys_manager: ChannelKeyManager,
     tx: Transaction,
@@ -143,7 +236,13 @@ pub fn finalize_htlc_success(
     remote_htlc_signature: Vec<u8>,
     local_htlc_privkey: SecretKey,
     payment_preimage: [u8; 32],
+    channel_type: ChannelType,
 ) -> Transaction {
+    // Refuse to sign a transaction that breaks the BOLT 3 encoding for the
+    // channel's negotiated type (provided)
+    if let Err(e) = check_htlc_success_tx_encoding(&tx, channel_type) {
+        panic!("Refusing to sign malformed HTLC-success transaction: {:?}", e);
+    }
 
     // Sign the transaction input with the local HTLC private key
     let local_htlc_signature = keys_manager.sign_transaction_input_sighash_all(
@@ -155,13 +254,12 @@ pub fn finalize_htlc_success(
     );
 
     // Build witness: [0, remote_sig, local_sig, preimage, script]
-    let witness = Witness::from_slice(&[
-        &[][..],
-        &remote_htlc_signature[..],
-        &local_htlc_signature[..],
-        &payment_preimage[..],
-        htlc_script.as_bytes(),
-    ]);
+    let witness = create_htlc_success_witness(
+        &remote_htlc_signature,
+        &local_htlc_signature,
+        &payment_preimage,
+        htlc_script,
+    );
 
     // Attach witness to transaction
     let mut signed_tx = tx;
@@ -1,9 +1,22 @@
+pub mod claim;
+pub mod closing;
 pub mod fees;
 pub mod funding;
 pub mod commitment;
+pub mod cpfp;
 pub mod htlc;
+pub mod justice;
+pub mod sanity;
+pub mod sweep;
+pub mod weights;
 
+pub use claim::*;
+pub use closing::*;
 pub use fees::*;
 pub use funding::*;
 pub use commitment::*;
+pub use cpfp::*;
 pub use htlc::*;
+pub use justice::*;
+pub use sanity::*;
+pub use sweep::*;
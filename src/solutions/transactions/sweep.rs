@@ -0,0 +1,371 @@
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::{PublicKey, SecretKey};
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, PubkeyHash, Sequence, Transaction, TxIn, TxOut, Witness};
+
+use crate::keys::commitment::{derive_private_key, derive_revocation_private_key};
+use crate::transactions::claim::{value_after_fee, ClaimError};
+use crate::transactions::commitment::relative_locktime_sequence;
+use crate::transactions::justice::RevokedHtlcOutput;
+use crate::transactions::weights::{
+    DELAYED_OUTPUT_INPUT_WEIGHT, DELAYED_OUTPUT_SWEEP_WEIGHT, OFFERED_HTLC_PENALTY_INPUT_WEIGHT,
+    RECEIVED_HTLC_PENALTY_INPUT_WEIGHT, REVOKED_TO_LOCAL_INPUT_WEIGHT, SWEEP_TX_BASE_WEIGHT,
+    TO_REMOTE_INPUT_WEIGHT, TO_REMOTE_SWEEP_WEIGHT,
+};
+use crate::scripts::create_to_local_script;
+use crate::types::{ChannelKeyManager, CommitmentKeys};
+
+/// helper (Not an exercise)
+/// Sweep the `to_local` output of our own commitment once it has been
+/// confirmed for `to_self_delay` blocks, e.g. after we force-closed.
+///
+/// The delayed payment key is derived from our delayed payment basepoint
+/// and the commitment's `per_commitment_point`, and the input's nSequence
+/// is set to `to_self_delay` so `OP_CHECKSEQUENCEVERIFY` is satisfied. The
+/// fee, at `feerate_per_kw`, comes out of the swept amount, which must leave
+/// a non-dust output.
+#[allow(clippy::too_many_arguments)]
+pub fn create_to_local_claim_transaction(
+    to_local_outpoint: OutPoint,
+    to_local_value: u64,
+    to_local_script: &ScriptBuf,
+    per_commitment_point: &PublicKey,
+    to_self_delay: u16,
+    keys: &ChannelKeyManager,
+    destination_script: &ScriptBuf,
+    feerate_per_kw: u64,
+) -> Result<Transaction, ClaimError> {
+    let fee = feerate_per_kw * DELAYED_OUTPUT_SWEEP_WEIGHT / 1000;
+    let output_sat = value_after_fee(to_local_value, fee, destination_script)?;
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: to_local_outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: relative_locktime_sequence(to_self_delay as u32)
+                .expect("A u16 delay always fits in nSequence"),
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(output_sat),
+            script_pubkey: destination_script.clone(),
+        }],
+    };
+
+    let delayed_privkey = derive_private_key(
+        &keys.delayed_payment_basepoint_secret,
+        per_commitment_point,
+        &keys.secp_ctx,
+    );
+    let signature = keys.sign_transaction_input_sighash_all(
+        &tx,
+        0,
+        to_local_script,
+        to_local_value,
+        &delayed_privkey,
+    );
+
+    // <local_delayedsig> <> <to_local_script>
+    tx.input[0].witness = Witness::from_slice(&[&signature[..], &[], to_local_script.as_bytes()]);
+    Ok(tx)
+}
+
+/// helper (Not an exercise)
+/// Claim our `to_remote` output from a counterparty commitment, e.g. after
+/// they force-closed.
+///
+/// With `option_static_remotekey` the output is a P2WPKH paying straight to
+/// our payment basepoint, so there is no delay and no per-commitment tweak.
+/// The fee, at `feerate_per_kw`, comes out of the claimed amount, which must
+/// leave a non-dust output.
+pub fn create_to_remote_claim_transaction(
+    to_remote_outpoint: OutPoint,
+    to_remote_value: u64,
+    keys: &ChannelKeyManager,
+    destination_script: &ScriptBuf,
+    feerate_per_kw: u64,
+) -> Result<Transaction, ClaimError> {
+    let fee = feerate_per_kw * TO_REMOTE_SWEEP_WEIGHT / 1000;
+    let output_sat = value_after_fee(to_remote_value, fee, destination_script)?;
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: to_remote_outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(output_sat),
+            script_pubkey: destination_script.clone(),
+        }],
+    };
+
+    // BIP143 signs a P2WPKH input with the equivalent P2PKH script as scriptCode
+    let payment_pubkey = PublicKey::from_secret_key(&keys.secp_ctx, &keys.payment_basepoint_secret);
+    let script_code = ScriptBuf::new_p2pkh(&PubkeyHash::hash(&payment_pubkey.serialize()));
+    let signature = keys.sign_transaction_input_sighash_all(
+        &tx,
+        0,
+        &script_code,
+        to_remote_value,
+        &keys.payment_basepoint_secret,
+    );
+
+    // <sig> <pubkey>
+    tx.input[0].witness = Witness::from_slice(&[&signature[..], &payment_pubkey.serialize()[..]]);
+    Ok(tx)
+}
+
+/// helper (Not an exercise)
+/// Sweep the output of one of our confirmed HTLC-timeout or HTLC-success
+/// transactions once `to_self_delay` has passed.
+///
+/// Second-stage HTLC transactions pay into the same script as `to_local`,
+/// built from the keys of the commitment they spend, so this is a
+/// `to_local` claim of `htlc_tx`'s single output.
+pub fn create_second_stage_claim_transaction(
+    htlc_tx: &Transaction,
+    commitment_keys: &CommitmentKeys,
+    to_self_delay: u16,
+    keys: &ChannelKeyManager,
+    destination_script: &ScriptBuf,
+    feerate_per_kw: u64,
+) -> Result<Transaction, ClaimError> {
+    let script = create_to_local_script(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_delayed_payment_key,
+        to_self_delay,
+    );
+    create_to_local_claim_transaction(
+        OutPoint::new(htlc_tx.compute_txid(), 0),
+        htlc_tx.output[0].value.to_sat(),
+        &script,
+        &commitment_keys.per_commitment_point,
+        to_self_delay,
+        keys,
+        destination_script,
+        feerate_per_kw,
+    )
+}
+
+/// An output we can claim, with what is needed to sign for it
+#[derive(Debug, Clone)]
+pub enum ClaimableOutput {
+    /// Our `to_local`, or the output of one of our HTLC-timeout/success
+    /// transactions, once `to_self_delay` has passed
+    Delayed {
+        outpoint: OutPoint,
+        value_sat: u64,
+        to_local_script: ScriptBuf,
+        per_commitment_point: PublicKey,
+        to_self_delay: u16,
+    },
+    /// Our `to_remote` on a counterparty commitment
+    ToRemote { outpoint: OutPoint, value_sat: u64 },
+    /// The `to_local` (or second-stage HTLC output) of a commitment the
+    /// counterparty revoked
+    RevokedToLocal {
+        outpoint: OutPoint,
+        value_sat: u64,
+        to_local_script: ScriptBuf,
+        per_commitment_secret: SecretKey,
+    },
+    /// An HTLC output on a commitment the counterparty revoked
+    RevokedHtlc {
+        htlc: RevokedHtlcOutput,
+        per_commitment_secret: SecretKey,
+    },
+}
+
+impl ClaimableOutput {
+    pub fn outpoint(&self) -> OutPoint {
+        match self {
+            ClaimableOutput::Delayed { outpoint, .. }
+            | ClaimableOutput::ToRemote { outpoint, .. }
+            | ClaimableOutput::RevokedToLocal { outpoint, .. } => *outpoint,
+            ClaimableOutput::RevokedHtlc { htlc, .. } => htlc.outpoint,
+        }
+    }
+
+    pub fn value_sat(&self) -> u64 {
+        match self {
+            ClaimableOutput::Delayed { value_sat, .. }
+            | ClaimableOutput::ToRemote { value_sat, .. }
+            | ClaimableOutput::RevokedToLocal { value_sat, .. } => *value_sat,
+            ClaimableOutput::RevokedHtlc { htlc, .. } => htlc.amount_sat,
+        }
+    }
+
+    /// Weight the signed input adds to a sweep transaction
+    pub fn input_weight(&self) -> u64 {
+        match self {
+            ClaimableOutput::Delayed { .. } => DELAYED_OUTPUT_INPUT_WEIGHT,
+            ClaimableOutput::ToRemote { .. } => TO_REMOTE_INPUT_WEIGHT,
+            ClaimableOutput::RevokedToLocal { .. } => REVOKED_TO_LOCAL_INPUT_WEIGHT,
+            ClaimableOutput::RevokedHtlc { htlc, .. } if htlc.offered => {
+                OFFERED_HTLC_PENALTY_INPUT_WEIGHT
+            }
+            ClaimableOutput::RevokedHtlc { .. } => RECEIVED_HTLC_PENALTY_INPUT_WEIGHT,
+        }
+    }
+
+    fn sequence(&self) -> Sequence {
+        match self {
+            ClaimableOutput::Delayed { to_self_delay, .. } => {
+                relative_locktime_sequence(*to_self_delay as u32)
+                    .expect("A u16 delay always fits in nSequence")
+            }
+            _ => Sequence::MAX,
+        }
+    }
+
+    // The scriptCode the input's signature commits to, and the key it is signed with
+    fn signing_script_and_key(&self, keys: &ChannelKeyManager) -> (ScriptBuf, SecretKey) {
+        match self {
+            ClaimableOutput::Delayed {
+                to_local_script,
+                per_commitment_point,
+                ..
+            } => (
+                to_local_script.clone(),
+                derive_private_key(
+                    &keys.delayed_payment_basepoint_secret,
+                    per_commitment_point,
+                    &keys.secp_ctx,
+                ),
+            ),
+            ClaimableOutput::ToRemote { .. } => {
+                let payment_pubkey =
+                    PublicKey::from_secret_key(&keys.secp_ctx, &keys.payment_basepoint_secret);
+                (
+                    ScriptBuf::new_p2pkh(&PubkeyHash::hash(&payment_pubkey.serialize())),
+                    keys.payment_basepoint_secret,
+                )
+            }
+            ClaimableOutput::RevokedToLocal {
+                to_local_script,
+                per_commitment_secret,
+                ..
+            } => (
+                to_local_script.clone(),
+                derive_revocation_private_key(
+                    &keys.revocation_basepoint_secret,
+                    per_commitment_secret,
+                    &keys.secp_ctx,
+                ),
+            ),
+            ClaimableOutput::RevokedHtlc {
+                htlc,
+                per_commitment_secret,
+            } => (
+                htlc.htlc_script.clone(),
+                derive_revocation_private_key(
+                    &keys.revocation_basepoint_secret,
+                    per_commitment_secret,
+                    &keys.secp_ctx,
+                ),
+            ),
+        }
+    }
+
+    fn witness(
+        &self,
+        signature: &[u8],
+        signing_key: &SecretKey,
+        keys: &ChannelKeyManager,
+    ) -> Witness {
+        let pubkey = PublicKey::from_secret_key(&keys.secp_ctx, signing_key);
+        match self {
+            // <local_delayedsig> <> <to_local_script>
+            ClaimableOutput::Delayed {
+                to_local_script, ..
+            } => Witness::from_slice(&[signature, &[], to_local_script.as_bytes()]),
+            // <sig> <pubkey>
+            ClaimableOutput::ToRemote { .. } => {
+                Witness::from_slice(&[signature, &pubkey.serialize()[..]])
+            }
+            // <revocation_sig> 1 <to_local_script>
+            ClaimableOutput::RevokedToLocal {
+                to_local_script, ..
+            } => Witness::from_slice(&[signature, &[0x01], to_local_script.as_bytes()]),
+            // <revocation_sig> <revocationpubkey> <htlc_script>
+            ClaimableOutput::RevokedHtlc { htlc, .. } => Witness::from_slice(&[
+                signature,
+                &pubkey.serialize()[..],
+                htlc.htlc_script.as_bytes(),
+            ]),
+        }
+    }
+}
+
+/// helper (Not an exercise)
+/// Claim several outputs in one transaction paying `destination_script`.
+///
+/// Each input gets the nSequence and witness its output needs, as in the
+/// single-output claim and justice transactions, so delayed outputs can
+/// only be batched once all of their delays have passed. The fee, at
+/// `feerate_per_kw`, comes out of the single output, which must not end up
+/// dust.
+pub fn create_batched_sweep_transaction(
+    outputs: &[ClaimableOutput],
+    keys: &ChannelKeyManager,
+    destination_script: &ScriptBuf,
+    feerate_per_kw: u64,
+) -> Result<Transaction, ClaimError> {
+    if outputs.is_empty() {
+        return Err(ClaimError::NoOutputs);
+    }
+    let weight = SWEEP_TX_BASE_WEIGHT
+        + outputs
+            .iter()
+            .map(ClaimableOutput::input_weight)
+            .sum::<u64>();
+    let total_sat: u64 = outputs.iter().map(ClaimableOutput::value_sat).sum();
+    let fee = feerate_per_kw * weight / 1000;
+    let output_sat = value_after_fee(total_sat, fee, destination_script)?;
+
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: outputs
+            .iter()
+            .map(|output| TxIn {
+                previous_output: output.outpoint(),
+                script_sig: ScriptBuf::new(),
+                sequence: output.sequence(),
+                witness: Witness::new(),
+            })
+            .collect(),
+        output: vec![TxOut {
+            value: Amount::from_sat(output_sat),
+            script_pubkey: destination_script.clone(),
+        }],
+    };
+
+    // Sign every input first: each signature commits to all the inputs
+    let witnesses: Vec<Witness> = outputs
+        .iter()
+        .enumerate()
+        .map(|(i, output)| {
+            let (script_code, signing_key) = output.signing_script_and_key(keys);
+            let signature = keys.sign_transaction_input_sighash_all(
+                &tx,
+                i,
+                &script_code,
+                output.value_sat(),
+                &signing_key,
+            );
+            output.witness(&signature, &signing_key, keys)
+        })
+        .collect();
+    for (input, witness) in tx.input.iter_mut().zip(witnesses) {
+        input.witness = witness;
+    }
+
+    Ok(tx)
+}
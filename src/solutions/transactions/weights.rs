@@ -0,0 +1,101 @@
+use crate::types::ChannelType;
+
+// TRANSACTION WEIGHTS (BOLT 3 "Fees")
+// ============================================================================
+//
+// The expected weights BOLT 3 uses to compute fees. Both peers must use the
+// same numbers, or they will disagree on the fee (and on which HTLCs are dust).
+
+/// Commitment transaction with no HTLC outputs
+pub const COMMITMENT_BASE_WEIGHT: u64 = 724;
+/// Commitment transaction with no HTLC outputs, including the two anchor outputs
+pub const COMMITMENT_BASE_WEIGHT_ANCHORS: u64 = 1124;
+/// Each untrimmed HTLC output adds this much to the commitment transaction
+pub const HTLC_OUTPUT_WEIGHT: u64 = 172;
+/// HTLC-timeout transaction
+pub const HTLC_TIMEOUT_WEIGHT: u64 = 663;
+/// HTLC-timeout transaction spending an anchors HTLC output (`1 OP_CSV`)
+pub const HTLC_TIMEOUT_WEIGHT_ANCHORS: u64 = 666;
+/// HTLC-success transaction
+pub const HTLC_SUCCESS_WEIGHT: u64 = 703;
+/// HTLC-success transaction spending an anchors HTLC output (`1 OP_CSV`)
+pub const HTLC_SUCCESS_WEIGHT_ANCHORS: u64 = 706;
+
+/// Weight of a commitment transaction before any HTLC outputs are added
+pub fn commitment_base_weight(channel_type: ChannelType) -> u64 {
+    match channel_type {
+        ChannelType::StaticRemoteKey => COMMITMENT_BASE_WEIGHT,
+        ChannelType::AnchorsZeroFeeHtlcTx => COMMITMENT_BASE_WEIGHT_ANCHORS,
+    }
+}
+
+/// Weight each untrimmed HTLC output adds (the same for every channel type)
+pub fn htlc_output_weight() -> u64 {
+    HTLC_OUTPUT_WEIGHT
+}
+
+/// Weight of an HTLC-timeout transaction
+pub fn htlc_timeout_weight(channel_type: ChannelType) -> u64 {
+    match channel_type {
+        ChannelType::StaticRemoteKey => HTLC_TIMEOUT_WEIGHT,
+        ChannelType::AnchorsZeroFeeHtlcTx => HTLC_TIMEOUT_WEIGHT_ANCHORS,
+    }
+}
+
+/// Weight of an HTLC-success transaction
+pub fn htlc_success_weight(channel_type: ChannelType) -> u64 {
+    match channel_type {
+        ChannelType::StaticRemoteKey => HTLC_SUCCESS_WEIGHT,
+        ChannelType::AnchorsZeroFeeHtlcTx => HTLC_SUCCESS_WEIGHT_ANCHORS,
+    }
+}
+
+/// Weight of a commitment transaction carrying `num_untrimmed_htlcs` HTLC outputs
+pub fn commitment_tx_weight(channel_type: ChannelType, num_untrimmed_htlcs: usize) -> u64 {
+    commitment_base_weight(channel_type) + htlc_output_weight() * num_untrimmed_htlcs as u64
+}
+
+/// Sweeping a delayed output (`to_local`, or an HTLC-timeout/success output)
+/// to a P2WPKH after its `to_self_delay` expires: 328 for the 1-in/1-out
+/// transaction, 2 for the segwit marker and flag, and 154 for the witness
+/// `<local_delayedsig> <> <to_local script>`.
+pub const DELAYED_OUTPUT_SWEEP_WEIGHT: u64 = 484;
+
+/// Justice transaction sweeping a revoked `to_local` to a P2WPKH: as
+/// `DELAYED_OUTPUT_SWEEP_WEIGHT`, but the witness pushes `1` instead of an
+/// empty item to select the revocation branch.
+pub const JUSTICE_TO_LOCAL_WEIGHT: u64 = 485;
+
+/// A sweep transaction without its inputs: version, input and output counts,
+/// locktime, segwit marker and flag, and one P2WPKH output
+pub const SWEEP_TX_BASE_WEIGHT: u64 = 166;
+/// Revoked offered HTLC input: outpoint, sequence and an empty scriptSig
+/// (164), plus the witness `<revocation_sig> <revocationpubkey> <script>` (243)
+pub const OFFERED_HTLC_PENALTY_INPUT_WEIGHT: u64 = 407;
+/// Revoked received HTLC input; its script is 6 bytes longer (the CLTV)
+pub const RECEIVED_HTLC_PENALTY_INPUT_WEIGHT: u64 = 413;
+
+/// Per-input weights for batching claims into one sweep transaction, on top
+/// of `SWEEP_TX_BASE_WEIGHT`: outpoint, sequence and an empty scriptSig
+/// (164) plus the witness. A delayed output spends with
+/// `<local_delayedsig> <> <to_local script>` (154)
+pub const DELAYED_OUTPUT_INPUT_WEIGHT: u64 = 318;
+/// A revoked `to_local`: as `DELAYED_OUTPUT_INPUT_WEIGHT` with `1` pushed
+/// instead of the empty item
+pub const REVOKED_TO_LOCAL_INPUT_WEIGHT: u64 = 319;
+/// Our P2WPKH `to_remote`, with the witness `<sig> <pubkey>` (109)
+pub const TO_REMOTE_INPUT_WEIGHT: u64 = 273;
+
+/// Sweeping our P2WPKH `to_remote` from a counterparty commitment: 330 as
+/// for `DELAYED_OUTPUT_SWEEP_WEIGHT`, plus the witness `<sig> <pubkey>` (109)
+pub const TO_REMOTE_SWEEP_WEIGHT: u64 = 439;
+
+/// Weight a signed anchor input adds: the witness `<sig> <anchor script>`
+/// (116) plus the segwit marker and flag, as for
+/// `P2WPKH_INPUT_SATISFACTION_WEIGHT`
+pub const ANCHOR_INPUT_SATISFACTION_WEIGHT: u64 = 118;
+
+/// A CPFP child spending just our anchor to a P2WPKH, weighed as
+/// `create_cpfp_transaction` does: 328 for the unsigned 1-in/1-out
+/// transaction plus `ANCHOR_INPUT_SATISFACTION_WEIGHT`
+pub const ANCHOR_CPFP_WEIGHT: u64 = 328 + ANCHOR_INPUT_SATISFACTION_WEIGHT;
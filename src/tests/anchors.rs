@@ -0,0 +1,324 @@
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::types::{ChannelKeyManager, ChannelType, CommitmentKeys, PaymentHash};
+use crate::*;
+use crate::tests::fixtures::{test_pubkey, test_secret};
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::ecdsa::Signature;
+use bitcoin::secp256k1::Message;
+use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+use bitcoin::hashes::Hash;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+
+// 1 OP_CHECKSEQUENCEVERIFY OP_DROP OP_ENDIF
+const ANCHORS_SUFFIX: [u8; 4] = [0x51, 0xb2, 0x75, 0x68];
+
+fn assert_anchors_variant(legacy: &[u8], anchors: &[u8]) {
+    let (body, suffix) = anchors.split_at(anchors.len() - ANCHORS_SUFFIX.len());
+    assert_eq!(suffix, ANCHORS_SUFFIX);
+    // Everything up to the final OP_ENDIF is unchanged
+    assert_eq!(body, &legacy[..legacy.len() - 1]);
+}
+
+#[test]
+fn test_offered_htlc_script_anchors_csv() {
+    let payment_hash = PaymentHash::from_preimage(&[0x02; 32]);
+    let (revocation, local, remote) = (test_pubkey(1), test_pubkey(2), test_pubkey(3));
+
+    let legacy = create_offered_htlc_script_for_channel_type(
+        &revocation,
+        &local,
+        &remote,
+        &payment_hash,
+        ChannelType::StaticRemoteKey,
+    );
+    let anchors = create_offered_htlc_script_for_channel_type(
+        &revocation,
+        &local,
+        &remote,
+        &payment_hash,
+        ChannelType::AnchorsZeroFeeHtlcTx,
+    );
+
+    assert_eq!(
+        legacy,
+        create_offered_htlc_script(&revocation, &local, &remote, &payment_hash)
+    );
+    assert_anchors_variant(legacy.as_bytes(), anchors.as_bytes());
+}
+
+#[test]
+fn test_received_htlc_script_anchors_csv() {
+    let payment_hash = PaymentHash::from_preimage(&[0x01; 32]);
+    let (revocation, local, remote) = (test_pubkey(1), test_pubkey(2), test_pubkey(3));
+
+    let legacy = create_received_htlc_script_for_channel_type(
+        &revocation,
+        &local,
+        &remote,
+        &payment_hash,
+        501,
+        ChannelType::StaticRemoteKey,
+    );
+    let anchors = create_received_htlc_script_for_channel_type(
+        &revocation,
+        &local,
+        &remote,
+        &payment_hash,
+        501,
+        ChannelType::AnchorsZeroFeeHtlcTx,
+    );
+
+    assert_eq!(
+        legacy,
+        create_received_htlc_script(&revocation, &local, &remote, &payment_hash, 501)
+    );
+    assert_anchors_variant(legacy.as_bytes(), anchors.as_bytes());
+}
+
+#[test]
+fn test_channel_type_htlc_tx_parameters() {
+    let legacy = ChannelType::StaticRemoteKey;
+    let anchors = ChannelType::AnchorsZeroFeeHtlcTx;
+
+    assert_eq!(legacy.htlc_input_sequence(), Sequence::ZERO);
+    assert_eq!(anchors.htlc_input_sequence(), Sequence::from_consensus(1));
+
+    assert_eq!(legacy.htlc_tx_feerate_per_kw(5000), 5000);
+    assert_eq!(anchors.htlc_tx_feerate_per_kw(5000), 0);
+
+    assert_eq!(legacy.remote_htlc_sighash_type(), EcdsaSighashType::All);
+    assert_eq!(
+        anchors.remote_htlc_sighash_type(),
+        EcdsaSighashType::SinglePlusAnyoneCanPay
+    );
+}
+
+fn anchors_htlc_keys() -> CommitmentKeys {
+    CommitmentKeys::from_keys(test_pubkey(1), test_pubkey(2), test_pubkey(3), test_pubkey(4), test_pubkey(5))
+}
+
+fn htlc_signer() -> ChannelKeyManager {
+    new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0)
+}
+
+#[test]
+fn test_finalize_anchors_htlc_transactions() {
+    let anchors = ChannelType::AnchorsZeroFeeHtlcTx;
+    let commitment_keys = anchors_htlc_keys();
+    let outpoint = OutPoint::new(Txid::all_zeros(), 0);
+    let preimage = [0x02; 32];
+    let payment_hash = PaymentHash::from_preimage(&preimage);
+    let remote_sig = vec![0x30; 72];
+    let htlc_privkey = test_secret(0x04);
+
+    // Zero-fee HTLC-timeout whose input waits out the anchors `1 OP_CSV`
+    let timeout_tx = create_htlc_timeout_transaction_for_channel_type(
+        outpoint,
+        10_000,
+        500,
+        &commitment_keys,
+        144,
+        5000,
+        anchors,
+    );
+    assert_eq!(timeout_tx.output[0].value.to_sat(), 10_000);
+
+    let offered_script = create_offered_htlc_script_for_channel_type(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_htlc_key,
+        &commitment_keys.remote_htlc_key,
+        &payment_hash,
+        anchors,
+    );
+    let signed = finalize_htlc_timeout(
+        htlc_signer(),
+        timeout_tx,
+        0,
+        &offered_script,
+        10_000,
+        remote_sig.clone(),
+        htlc_privkey,
+        anchors,
+    );
+    assert_eq!(signed.input[0].sequence, Sequence::from_consensus(1));
+    assert_eq!(signed.input[0].witness.len(), 5);
+
+    // Same for HTLC-success
+    let success_tx = create_htlc_success_transaction_for_channel_type(
+        outpoint,
+        10_000,
+        &commitment_keys,
+        144,
+        5000,
+        anchors,
+    );
+    assert_eq!(success_tx.input[0].sequence, Sequence::from_consensus(1));
+    assert_eq!(success_tx.output[0].value.to_sat(), 10_000);
+
+    let received_script = create_received_htlc_script_for_channel_type(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_htlc_key,
+        &commitment_keys.remote_htlc_key,
+        &payment_hash,
+        500,
+        anchors,
+    );
+    let signed = finalize_htlc_success(
+        htlc_signer(),
+        success_tx,
+        0,
+        &received_script,
+        10_000,
+        remote_sig,
+        htlc_privkey,
+        preimage,
+        anchors,
+    );
+    assert_eq!(signed.input[0].witness.nth(3).unwrap(), &preimage[..]);
+}
+
+#[test]
+fn test_legacy_htlc_builders_match_exercises() {
+    let commitment_keys = anchors_htlc_keys();
+    let outpoint = OutPoint::new(Txid::all_zeros(), 0);
+    let legacy = ChannelType::StaticRemoteKey;
+
+    let timeout_tx = create_htlc_timeout_transaction_for_channel_type(
+        outpoint,
+        10_000,
+        500,
+        &commitment_keys,
+        144,
+        5000,
+        legacy,
+    );
+    let success_tx = create_htlc_success_transaction_for_channel_type(
+        outpoint,
+        10_000,
+        &commitment_keys,
+        144,
+        5000,
+        legacy,
+    );
+
+    assert_eq!(
+        timeout_tx,
+        create_htlc_timeout_transaction(outpoint, 10_000, 500, &commitment_keys, 144, 5000)
+    );
+    assert_eq!(
+        success_tx,
+        create_htlc_success_transaction(outpoint, 10_000, &commitment_keys, 144, 5000)
+    );
+}
+
+#[test]
+fn test_peer_anchors_htlc_signature_survives_added_fee_input() {
+    let anchors = ChannelType::AnchorsZeroFeeHtlcTx;
+    let ours = htlc_signer();
+    let peer = SimulatedPeer::new();
+    let per_commitment_point = ours.derive_per_commitment_point(7);
+    let commitment_keys = CommitmentKeys::from_basepoints(
+        &per_commitment_point,
+        &ours.to_public_keys().delayed_payment_basepoint,
+        &ours.to_public_keys().htlc_basepoint,
+        &peer.public_keys().revocation_basepoint,
+        &peer.public_keys().htlc_basepoint,
+        &ours.secp_ctx,
+    );
+    let offered_script = create_offered_htlc_script_for_channel_type(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_htlc_key,
+        &commitment_keys.remote_htlc_key,
+        &PaymentHash::from_preimage(&[0x02; 32]),
+        anchors,
+    );
+    let tx = create_htlc_timeout_transaction_for_channel_type(
+        OutPoint::new(Txid::all_zeros(), 0),
+        10_000,
+        500,
+        &commitment_keys,
+        144,
+        5000,
+        anchors,
+    );
+    let signature = peer.sign_htlc_transaction_for_channel_type(
+        &tx,
+        0,
+        &offered_script,
+        10_000,
+        &per_commitment_point,
+        anchors,
+    );
+    let (flag, der) = signature.split_last().unwrap();
+    assert_eq!(*flag, EcdsaSighashType::SinglePlusAnyoneCanPay as u8);
+
+    // Attach a wallet input and change output to pay the fee, as the
+    // broadcaster of a zero-fee HTLC transaction does
+    let mut bumped = tx.clone();
+    bumped.input.push(TxIn {
+        previous_output: OutPoint::new(Txid::all_zeros(), 1),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+    });
+    bumped.output.push(TxOut {
+        value: Amount::from_sat(50_000),
+        script_pubkey: ScriptBuf::new_op_return([0x42]),
+    });
+
+    let sighash = SighashCache::new(&bumped)
+        .p2wsh_signature_hash(
+            0,
+            &offered_script,
+            Amount::from_sat(10_000),
+            EcdsaSighashType::SinglePlusAnyoneCanPay,
+        )
+        .unwrap();
+    let msg = Message::from_digest(sighash.to_byte_array());
+    assert!(Secp256k1::verification_only()
+        .verify_ecdsa(&msg, &Signature::from_der(der).unwrap(), &commitment_keys.remote_htlc_key)
+        .is_ok());
+}
+
+// An HTLC-timeout spending an anchors-style offered HTLC, with the given input sequence
+fn finalize_timeout_with_sequence(sequence: Sequence, channel_type: ChannelType) -> Transaction {
+    let commitment_keys = anchors_htlc_keys();
+    let mut timeout_tx = create_htlc_timeout_transaction(
+        OutPoint::new(Txid::all_zeros(), 0),
+        10_000,
+        500,
+        &commitment_keys,
+        144,
+        0,
+    );
+    timeout_tx.input[0].sequence = sequence;
+    let offered_script = create_offered_htlc_script_for_channel_type(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_htlc_key,
+        &commitment_keys.remote_htlc_key,
+        &PaymentHash::from_preimage(&[0x02; 32]),
+        channel_type,
+    );
+    finalize_htlc_timeout(
+        htlc_signer(),
+        timeout_tx,
+        0,
+        &offered_script,
+        10_000,
+        vec![0x30; 72],
+        test_secret(0x04),
+        channel_type,
+    )
+}
+
+#[test]
+#[should_panic(expected = "HtlcSequence")]
+fn test_finalize_refuses_anchors_sequence_on_legacy_channel() {
+    finalize_timeout_with_sequence(Sequence::from_consensus(1), ChannelType::StaticRemoteKey);
+}
+
+#[test]
+#[should_panic(expected = "HtlcSequence")]
+fn test_finalize_refuses_legacy_sequence_on_anchors_channel() {
+    finalize_timeout_with_sequence(Sequence::ZERO, ChannelType::AnchorsZeroFeeHtlcTx);
+}
@@ -0,0 +1,162 @@
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::transactions::funding::{
+    validate_counterparty_keys, validate_open_channel, BasepointError, ChannelOpenError,
+    ChannelOpenLimits, OpenChannelParams, ReceivedChannelKeys, MAX_FUNDING_SATOSHIS_NO_WUMBO,
+};
+use crate::types::ChannelPublicKeys;
+use crate::{new_keys_manager, Network};
+
+const LIMITS: ChannelOpenLimits = ChannelOpenLimits {
+    min_funding_satoshis: 100_000,
+    max_dust_limit_satoshis: 1_000,
+    max_to_self_delay: 2_016,
+    min_feerate_per_kw: 0,
+    max_feerate_per_kw: 50_000,
+    large_channel: false,
+};
+
+fn params() -> OpenChannelParams {
+    OpenChannelParams {
+        funding_satoshis: 5_000_000,
+        dust_limit_satoshis: 546,
+        channel_reserve_satoshis: 50_000,
+        to_self_delay: 144,
+        feerate_per_kw: 2_500,
+    }
+}
+
+#[test]
+fn test_reasonable_open_channel_accepted() {
+    assert_eq!(validate_open_channel(&params(), &LIMITS), Ok(()));
+}
+
+#[test]
+fn test_funding_limits() {
+    let small = OpenChannelParams { funding_satoshis: 99_999, ..params() };
+    assert_eq!(
+        validate_open_channel(&small, &LIMITS),
+        Err(ChannelOpenError::FundingTooSmall { funding_satoshis: 99_999, min_funding_satoshis: 100_000 })
+    );
+
+    let large = OpenChannelParams { funding_satoshis: 1 << 24, ..params() };
+    assert_eq!(
+        validate_open_channel(&large, &LIMITS),
+        Err(ChannelOpenError::FundingTooLarge {
+            funding_satoshis: 1 << 24,
+            max_funding_satoshis: MAX_FUNDING_SATOSHIS_NO_WUMBO,
+        })
+    );
+    let wumbo = ChannelOpenLimits { large_channel: true, ..LIMITS };
+    assert_eq!(validate_open_channel(&large, &wumbo), Ok(()));
+}
+
+#[test]
+fn test_dust_limit_and_reserve() {
+    let high_dust = OpenChannelParams { dust_limit_satoshis: 1_001, ..params() };
+    assert_eq!(
+        validate_open_channel(&high_dust, &LIMITS),
+        Err(ChannelOpenError::DustLimitTooHigh { dust_limit_satoshis: 1_001, max_dust_limit_satoshis: 1_000 })
+    );
+
+    let low_reserve = OpenChannelParams { channel_reserve_satoshis: 545, ..params() };
+    assert_eq!(
+        validate_open_channel(&low_reserve, &LIMITS),
+        Err(ChannelOpenError::ReserveBelowDustLimit { channel_reserve_satoshis: 545, dust_limit_satoshis: 546 })
+    );
+}
+
+#[test]
+fn test_to_self_delay_too_large() {
+    let delay = OpenChannelParams { to_self_delay: 2_017, ..params() };
+    assert_eq!(
+        validate_open_channel(&delay, &LIMITS),
+        Err(ChannelOpenError::ToSelfDelayTooLarge { to_self_delay: 2_017, max_to_self_delay: 2_016 })
+    );
+}
+
+#[test]
+fn test_feerate_out_of_range() {
+    // The estimator's minimum is raised to the BOLT 2 floor of 253 sat/kw
+    let low = OpenChannelParams { feerate_per_kw: 252, ..params() };
+    assert_eq!(
+        validate_open_channel(&low, &LIMITS),
+        Err(ChannelOpenError::FeerateOutOfRange {
+            feerate_per_kw: 252,
+            min_feerate_per_kw: 253,
+            max_feerate_per_kw: 50_000,
+        })
+    );
+
+    let high = OpenChannelParams { feerate_per_kw: 50_001, ..params() };
+    assert!(matches!(
+        validate_open_channel(&high, &LIMITS),
+        Err(ChannelOpenError::FeerateOutOfRange { feerate_per_kw: 50_001, .. })
+    ));
+}
+
+fn our_keys() -> ChannelPublicKeys {
+    new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0).to_public_keys()
+}
+
+// What the simulated peer would put on the wire
+fn received_keys() -> ReceivedChannelKeys {
+    let keys = SimulatedPeer::new().public_keys();
+    ReceivedChannelKeys {
+        funding_pubkey: keys.funding_pubkey.serialize(),
+        revocation_basepoint: keys.revocation_basepoint.serialize(),
+        payment_basepoint: keys.payment_basepoint.serialize(),
+        delayed_payment_basepoint: keys.delayed_payment_basepoint.serialize(),
+        htlc_basepoint: keys.htlc_basepoint.serialize(),
+    }
+}
+
+#[test]
+fn test_counterparty_keys_accepted() {
+    assert_eq!(
+        validate_counterparty_keys(&received_keys(), &our_keys()),
+        Ok(SimulatedPeer::new().public_keys())
+    );
+}
+
+#[test]
+fn test_counterparty_key_not_a_point() {
+    // Wrong prefix byte
+    let mut received = received_keys();
+    received.htlc_basepoint[0] = 0x04;
+    assert_eq!(
+        validate_counterparty_keys(&received, &our_keys()),
+        Err(BasepointError::InvalidPoint { field: "htlc_basepoint" })
+    );
+
+    // x-coordinate not on the curve
+    let mut received = received_keys();
+    received.funding_pubkey = [0x02; 33];
+    received.funding_pubkey[1..].copy_from_slice(&[0xff; 32]);
+    assert_eq!(
+        validate_counterparty_keys(&received, &our_keys()),
+        Err(BasepointError::InvalidPoint { field: "funding_pubkey" })
+    );
+}
+
+#[test]
+fn test_counterparty_keys_must_be_distinct() {
+    let mut received = received_keys();
+    received.delayed_payment_basepoint = received.payment_basepoint;
+    assert_eq!(
+        validate_counterparty_keys(&received, &our_keys()),
+        Err(BasepointError::Duplicate {
+            field: "delayed_payment_basepoint",
+            other_field: "payment_basepoint",
+        })
+    );
+}
+
+#[test]
+fn test_counterparty_keys_must_differ_from_ours() {
+    let mut received = received_keys();
+    received.revocation_basepoint = our_keys().revocation_basepoint.serialize();
+    assert_eq!(
+        validate_counterparty_keys(&received, &our_keys()),
+        Err(BasepointError::SameAsOurs { field: "revocation_basepoint" })
+    );
+}
@@ -0,0 +1,78 @@
+use crate::transactions::closing::create_closing_transaction;
+use crate::*;
+use crate::tests::fixtures::test_pubkey;
+use bitcoin::hashes::Hash;
+use bitcoin::script::ScriptBuf;
+use bitcoin::{OutPoint, Transaction, Txid};
+
+const FUNDING_AMOUNT: u64 = 10_000_000;
+const CLOSING_FEE: u64 = 5_000;
+const DUST_LIMIT: u64 = 546;
+
+fn shutdown_script(byte: u8) -> ScriptBuf {
+    create_to_remote_script(&test_pubkey(byte))
+}
+
+fn close(local_amount: u64, local_is_funder: bool) -> Transaction {
+    create_closing_transaction(
+        OutPoint::new(Txid::all_zeros(), 0),
+        local_amount,
+        FUNDING_AMOUNT - local_amount,
+        &shutdown_script(0x01),
+        &shutdown_script(0x02),
+        CLOSING_FEE,
+        local_is_funder,
+        DUST_LIMIT,
+    )
+}
+
+fn paid_to(tx: &Transaction, script: &ScriptBuf) -> Option<u64> {
+    tx.output
+        .iter()
+        .find(|output| &output.script_pubkey == script)
+        .map(|output| output.value.to_sat())
+}
+
+#[test]
+fn test_funder_pays_closing_fee() {
+    let tx = close(7_000_000, true);
+    assert_eq!(paid_to(&tx, &shutdown_script(0x01)), Some(7_000_000 - CLOSING_FEE));
+    assert_eq!(paid_to(&tx, &shutdown_script(0x02)), Some(3_000_000));
+
+    let tx = close(7_000_000, false);
+    assert_eq!(paid_to(&tx, &shutdown_script(0x01)), Some(7_000_000));
+    assert_eq!(paid_to(&tx, &shutdown_script(0x02)), Some(3_000_000 - CLOSING_FEE));
+}
+
+#[test]
+fn test_closing_outputs_in_bip69_order() {
+    for local_amount in [2_000_000, 5_000_000, 8_000_000] {
+        let tx = close(local_amount, true);
+        assert!(tx.output.windows(2).all(|pair| {
+            (pair[0].value, &pair[0].script_pubkey) <= (pair[1].value, &pair[1].script_pubkey)
+        }));
+    }
+
+    // Equal amounts fall back to comparing scriptPubKeys
+    let tx = close(FUNDING_AMOUNT / 2 + CLOSING_FEE / 2, true);
+    assert_eq!(tx.output[0].value, tx.output[1].value);
+    assert!(tx.output[0].script_pubkey < tx.output[1].script_pubkey);
+}
+
+#[test]
+fn test_dust_output_trimmed() {
+    // Our output is dust once the fee comes out of it
+    let tx = close(CLOSING_FEE + DUST_LIMIT - 1, true);
+    assert_eq!(tx.output.len(), 1);
+    assert_eq!(paid_to(&tx, &shutdown_script(0x01)), None);
+
+    let tx = close(CLOSING_FEE + DUST_LIMIT, true);
+    assert_eq!(paid_to(&tx, &shutdown_script(0x01)), Some(DUST_LIMIT));
+}
+
+#[test]
+fn test_closing_tx_has_no_obscured_commitment_number() {
+    let tx = close(7_000_000, true);
+    assert_eq!(tx.lock_time.to_consensus_u32(), 0);
+    assert_eq!(tx.input[0].sequence.to_consensus_u32(), 0xFFFF_FFFF);
+}
@@ -0,0 +1,50 @@
+use crate::*;
+use crate::tests::fixtures::test_pubkey;
+
+// OP_CHECKLOCKTIMEVERIFY
+const OP_CLTV: u8 = 0xb1;
+
+fn received_script_bytes(cltv_expiry: u32) -> Vec<u8> {
+    create_received_htlc_script(&test_pubkey(1), &test_pubkey(2), &test_pubkey(3), &PaymentHash::from_hash_bytes([0x42; 32]), cltv_expiry).to_bytes()
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[test]
+fn test_cltv_expiry_minimal_push() {
+    let cases: [(u32, &[u8]); 6] = [
+        (1, &[0x51]),                                   // OP_1
+        (16, &[0x60]),                                  // OP_16
+        (17, &[0x01, 0x11]),                            // first value needing a data push
+        (128, &[0x02, 0x80, 0x00]),                     // sign bit forces an extra byte
+        (500, &[0x02, 0xf4, 0x01]),                     // BOLT 3 Appendix C, HTLC #0
+        (499_999_999, &[0x04, 0xff, 0x64, 0xcd, 0x1d]), // last block height
+    ];
+
+    for (cltv_expiry, expected) in cases {
+        assert_eq!(
+            cltv_expiry_push(cltv_expiry).as_bytes(),
+            expected,
+            "cltv_expiry {}",
+            cltv_expiry
+        );
+
+        let mut push_then_cltv = expected.to_vec();
+        push_then_cltv.push(OP_CLTV);
+        assert!(
+            contains(&received_script_bytes(cltv_expiry), &push_then_cltv),
+            "received HTLC script does not push cltv_expiry {} minimally",
+            cltv_expiry
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "is a timestamp, not a block height")]
+fn test_time_based_cltv_expiry_rejected() {
+    received_script_bytes(LOCKTIME_THRESHOLD);
+}
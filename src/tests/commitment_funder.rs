@@ -0,0 +1,118 @@
+use crate::types::CommitmentKeys;
+use crate::*;
+use crate::tests::fixtures::test_pubkey;
+use bitcoin::{OutPoint, Transaction, Txid};
+use std::str::FromStr;
+
+const FUNDER_BALANCE: u64 = 7_000_000;
+const FUNDEE_BALANCE: u64 = 3_000_000;
+const FEERATE_PER_KW: u64 = 15_000;
+const COMMITMENT_NUMBER: u64 = 42;
+
+fn funding_outpoint() -> OutPoint {
+    OutPoint {
+        txid: Txid::from_str("8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be")
+            .unwrap(),
+        vout: 0,
+    }
+}
+
+// One party's commitment for the shared state. The funder's keys and
+// basepoint use 0x1_ bytes, the fundee's 0x2_.
+fn build_commitment(local_is_funder: bool, to_local: u64, to_remote: u64) -> Transaction {
+    let (local, remote) = if local_is_funder { (0x10, 0x20) } else { (0x20, 0x10) };
+    let commitment_keys = CommitmentKeys::from_keys(
+        test_pubkey(local + 1),
+        test_pubkey(local + 2),
+        test_pubkey(local + 3),
+        test_pubkey(remote + 3),
+        test_pubkey(remote + 4),
+    );
+
+    create_commitment_transaction_with_funder(
+        funding_outpoint(),
+        to_local,
+        to_remote,
+        &commitment_keys,
+        &test_pubkey(local + 5),
+        &test_pubkey(remote + 5),
+        COMMITMENT_NUMBER,
+        144,
+        546,
+        FEERATE_PER_KW,
+        &[],
+        &[],
+        local_is_funder,
+    )
+}
+
+fn output_values(tx: &Transaction) -> Vec<u64> {
+    tx.output.iter().map(|output| output.value.to_sat()).collect()
+}
+
+#[test]
+fn test_fee_comes_from_funder_on_both_commitments() {
+    let fee = calculate_commitment_tx_fee(FEERATE_PER_KW, 0);
+    let funder_tx = build_commitment(true, FUNDER_BALANCE, FUNDEE_BALANCE);
+    let fundee_tx = build_commitment(false, FUNDEE_BALANCE, FUNDER_BALANCE);
+
+    // The fundee's balance is untouched on either side's commitment
+    assert_eq!(output_values(&funder_tx), vec![FUNDEE_BALANCE, FUNDER_BALANCE - fee]);
+    assert_eq!(output_values(&fundee_tx), vec![FUNDEE_BALANCE, FUNDER_BALANCE - fee]);
+}
+
+#[test]
+fn test_both_commitments_share_obscured_commitment_number() {
+    let funder_tx = build_commitment(true, FUNDER_BALANCE, FUNDEE_BALANCE);
+    let fundee_tx = build_commitment(false, FUNDEE_BALANCE, FUNDER_BALANCE);
+
+    assert_eq!(funder_tx.lock_time, fundee_tx.lock_time);
+    assert_eq!(funder_tx.input[0].sequence, fundee_tx.input[0].sequence);
+}
+
+#[test]
+fn test_local_funder_matches_create_commitment_transaction() {
+    let commitment_keys = CommitmentKeys::from_keys(
+        test_pubkey(0x11),
+        test_pubkey(0x12),
+        test_pubkey(0x13),
+        test_pubkey(0x23),
+        test_pubkey(0x24),
+    );
+    let expected = create_commitment_transaction(
+        funding_outpoint(),
+        FUNDER_BALANCE,
+        FUNDEE_BALANCE,
+        &commitment_keys,
+        &test_pubkey(0x15),
+        &test_pubkey(0x25),
+        COMMITMENT_NUMBER,
+        144,
+        546,
+        FEERATE_PER_KW,
+        &[],
+        &[],
+    );
+
+    assert_eq!(build_commitment(true, FUNDER_BALANCE, FUNDEE_BALANCE), expected);
+}
+
+#[test]
+fn test_remote_funder_output_trimmed_when_it_cannot_cover_fee() {
+    let fee = calculate_commitment_tx_fee(FEERATE_PER_KW, 0);
+    let fundee_tx = build_commitment(false, FUNDEE_BALANCE, fee);
+
+    assert_eq!(output_values(&fundee_tx), vec![FUNDEE_BALANCE]);
+}
+
+#[test]
+fn test_sub_dust_fundee_output_trimmed_on_both_commitments() {
+    let fee = calculate_commitment_tx_fee(FEERATE_PER_KW, 0);
+    // Below the 546 sat dust limit before any fee is considered
+    let fundee_balance = 200;
+    let funder_tx = build_commitment(true, FUNDER_BALANCE, fundee_balance);
+    let fundee_tx = build_commitment(false, fundee_balance, FUNDER_BALANCE);
+
+    assert_eq!(output_values(&funder_tx), vec![FUNDER_BALANCE - fee]);
+    assert_eq!(output_values(&fundee_tx), vec![FUNDER_BALANCE - fee]);
+}
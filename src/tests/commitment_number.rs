@@ -0,0 +1,88 @@
+use crate::transactions::commitment::recover_commitment_number;
+use crate::*;
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::{OutPoint, Sequence, Transaction, TxIn, Txid, Witness};
+
+// BOLT 3 Appendix C payment basepoints; the local node is the initiator
+fn basepoints() -> (PublicKey, PublicKey) {
+    let pubkey = |hex_str: &str| PublicKey::from_slice(&hex::decode(hex_str).unwrap()).unwrap();
+    (
+        pubkey("034f355bdcb7cc0af728ef3cceb9615d90684bb5b2ca5f859ab0f0b704075871aa"),
+        pubkey("032c0b7cf95324a07d05398b240174dc0c2be444d96b159aa6c7f7b1e668680991"),
+    )
+}
+
+#[test]
+fn test_recover_bolt3_commitment_number() {
+    // nLockTime 542251326 and nSequence 2150346808 on every Appendix C commitment
+    let (initiator, receiver) = basepoints();
+    assert_eq!(
+        recover_commitment_number(
+            LockTime::from_consensus(542_251_326),
+            Sequence(2_150_346_808),
+            &initiator,
+            &receiver,
+        ),
+        Some(42)
+    );
+}
+
+#[test]
+fn test_recover_undoes_obscuring() {
+    let (initiator, receiver) = basepoints();
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![],
+    };
+
+    for commitment_number in [0, 1, 42, 0xffffff, 0x1000000, (1 << 48) - 1] {
+        set_obscured_commitment_number(&mut tx, commitment_number, &initiator, &receiver);
+        assert_eq!(
+            recover_commitment_number(tx.lock_time, tx.input[0].sequence, &initiator, &receiver),
+            Some(commitment_number)
+        );
+    }
+}
+
+#[test]
+fn test_recover_needs_commitment_prefixes() {
+    let (initiator, receiver) = basepoints();
+    assert_eq!(
+        recover_commitment_number(LockTime::ZERO, Sequence(2_150_346_808), &initiator, &receiver),
+        None
+    );
+    assert_eq!(
+        recover_commitment_number(
+            LockTime::from_consensus(542_251_326),
+            Sequence::MAX,
+            &initiator,
+            &receiver,
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_recover_with_swapped_basepoints_is_wrong() {
+    // The obscure factor hashes the initiator's basepoint first
+    let (initiator, receiver) = basepoints();
+    assert_ne!(
+        recover_commitment_number(
+            LockTime::from_consensus(542_251_326),
+            Sequence(2_150_346_808),
+            &receiver,
+            &initiator,
+        ),
+        Some(42)
+    );
+}
@@ -0,0 +1,139 @@
+use crate::internal::commitment_parser::{parse_commitment_transaction, CommitmentOutput};
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::types::{ChannelKeyManager, CommitmentKeys, HTLCOutput};
+use crate::*;
+use bitcoin::hashes::Hash;
+use bitcoin::{OutPoint, Transaction, Txid};
+
+const TO_SELF_DELAY: u16 = 144;
+const COMMITMENT_NUMBER: u64 = 42;
+
+fn local_keys() -> ChannelKeyManager {
+    new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0)
+}
+
+fn offered() -> HTLCOutput {
+    HTLCOutput { amount_sat: 20_000, payment_hash: PaymentHash::from_hash_bytes([0x01; 32]), cltv_expiry: 500 }
+}
+
+fn received() -> HTLCOutput {
+    HTLCOutput { amount_sat: 30_000, payment_hash: PaymentHash::from_hash_bytes([0x02; 32]), cltv_expiry: 510 }
+}
+
+fn holder_commitment(keys: &ChannelKeyManager) -> Transaction {
+    let local = keys.to_public_keys();
+    let remote = SimulatedPeer::new().public_keys();
+    let commitment_keys = CommitmentKeys::from_basepoints(
+        &keys.derive_per_commitment_point(COMMITMENT_NUMBER),
+        &local.delayed_payment_basepoint,
+        &local.htlc_basepoint,
+        &remote.revocation_basepoint,
+        &remote.htlc_basepoint,
+        &keys.secp_ctx,
+    );
+    create_commitment_transaction(
+        OutPoint::new(Txid::all_zeros(), 0),
+        7_000_000,
+        3_000_000,
+        &commitment_keys,
+        &local.payment_basepoint,
+        &remote.payment_basepoint,
+        COMMITMENT_NUMBER,
+        TO_SELF_DELAY,
+        546,
+        253,
+        &[offered()],
+        &[received()],
+    )
+}
+
+fn parse(
+    keys: &ChannelKeyManager,
+    tx: &Transaction,
+    commitment_number: u64,
+    htlcs: &[HTLCOutput],
+) -> Vec<CommitmentOutput> {
+    parse_commitment_transaction(
+        tx,
+        &keys.to_public_keys(),
+        &SimulatedPeer::new().public_keys(),
+        &keys.derive_per_commitment_point(commitment_number),
+        TO_SELF_DELAY,
+        htlcs,
+    )
+    .into_iter()
+    .map(|(_, output)| output)
+    .collect()
+}
+
+#[test]
+fn test_parse_classifies_every_output() {
+    let keys = local_keys();
+    let tx = holder_commitment(&keys);
+    let unrelated = HTLCOutput { amount_sat: 1_000, payment_hash: PaymentHash::from_hash_bytes([0x03; 32]), cltv_expiry: 520 };
+    let parsed = parse(&keys, &tx, COMMITMENT_NUMBER, &[received(), unrelated, offered()]);
+
+    assert_eq!(parsed.len(), 4);
+    assert!(parsed.contains(&CommitmentOutput::OfferedHtlc {
+        amount_sat: 20_000,
+        payment_hash: PaymentHash::from_hash_bytes([0x01; 32]),
+        cltv_expiry: 500,
+    }));
+    assert!(parsed.contains(&CommitmentOutput::ReceivedHtlc {
+        amount_sat: 30_000,
+        payment_hash: PaymentHash::from_hash_bytes([0x02; 32]),
+        cltv_expiry: 510,
+    }));
+    assert!(parsed.contains(&CommitmentOutput::ToRemote { amount_sat: 3_000_000 }));
+    assert!(parsed.iter().any(|output| matches!(output, CommitmentOutput::ToLocal { .. })));
+}
+
+#[test]
+fn test_parse_keeps_output_order() {
+    let keys = local_keys();
+    let tx = holder_commitment(&keys);
+    let parsed = parse_commitment_transaction(
+        &tx,
+        &keys.to_public_keys(),
+        &SimulatedPeer::new().public_keys(),
+        &keys.derive_per_commitment_point(COMMITMENT_NUMBER),
+        TO_SELF_DELAY,
+        &[offered(), received()],
+    );
+
+    for (i, (index, output)) in parsed.iter().enumerate() {
+        assert_eq!(*index, i as u32);
+        let amount_sat = match output {
+            CommitmentOutput::ToLocal { amount_sat }
+            | CommitmentOutput::ToRemote { amount_sat }
+            | CommitmentOutput::OfferedHtlc { amount_sat, .. }
+            | CommitmentOutput::ReceivedHtlc { amount_sat, .. }
+            | CommitmentOutput::Unknown { amount_sat, .. } => *amount_sat,
+        };
+        assert_eq!(amount_sat, tx.output[i].value.to_sat());
+    }
+}
+
+#[test]
+fn test_htlc_without_candidate_is_unknown() {
+    let keys = local_keys();
+    let tx = holder_commitment(&keys);
+    let parsed = parse(&keys, &tx, COMMITMENT_NUMBER, &[offered()]);
+
+    let unknown: Vec<_> =
+        parsed.iter().filter(|output| matches!(output, CommitmentOutput::Unknown { .. })).collect();
+    assert_eq!(unknown.len(), 1);
+    assert!(matches!(unknown[0], CommitmentOutput::Unknown { amount_sat: 30_000, .. }));
+}
+
+#[test]
+fn test_wrong_per_commitment_point_leaves_only_to_remote() {
+    // Every script but to_remote is tweaked by the per-commitment point
+    let keys = local_keys();
+    let tx = holder_commitment(&keys);
+    let parsed = parse(&keys, &tx, COMMITMENT_NUMBER + 1, &[offered(), received()]);
+
+    let known: Vec<_> =
+        parsed.iter().filter(|output| !matches!(output, CommitmentOutput::Unknown { .. })).collect();
+    assert_eq!(known, [&CommitmentOutput::ToRemote { amount_sat: 3_000_000 }]);
+}
@@ -0,0 +1,108 @@
+use crate::transactions::cpfp::{create_cpfp_transaction, CpfpError, CpfpInput};
+use crate::transactions::fees::ANCHOR_OUTPUT_VALUE_SATOSHI;
+use crate::transactions::funding::P2WPKH_INPUT_SATISFACTION_WEIGHT;
+use crate::transactions::weights::ANCHOR_INPUT_SATISFACTION_WEIGHT;
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness, WPubkeyHash};
+
+const CHANGE_SAT: u64 = 50_000;
+
+fn p2wpkh(byte: u8) -> ScriptBuf {
+    ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([byte; 20]))
+}
+
+// A signed parent with a P2WPKH change output and an anchor
+fn parent() -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::from_byte_array([0x11; 32]), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::from_slice(&[vec![0x30; 72], vec![0x02; 33]]),
+        }],
+        output: vec![
+            TxOut { value: Amount::from_sat(CHANGE_SAT), script_pubkey: p2wpkh(1) },
+            TxOut {
+                value: Amount::from_sat(ANCHOR_OUTPUT_VALUE_SATOSHI),
+                script_pubkey: ScriptBuf::new_p2wsh(&bitcoin::WScriptHash::from_byte_array([0x22; 32])),
+            },
+        ],
+    }
+}
+
+fn wallet_input() -> CpfpInput {
+    CpfpInput {
+        outpoint: OutPoint::new(Txid::from_byte_array([0x33; 32]), 1),
+        value_sat: 20_000,
+        satisfaction_weight: P2WPKH_INPUT_SATISFACTION_WEIGHT,
+    }
+}
+
+fn child_weight(child: &Transaction, satisfaction_weights: &[u64]) -> u64 {
+    child.weight().to_wu() + satisfaction_weights.iter().sum::<u64>()
+}
+
+#[test]
+fn test_child_brings_package_to_target() {
+    let parent = parent();
+    let (child, package_feerate) =
+        create_cpfp_transaction(&parent, 200, 0, P2WPKH_INPUT_SATISFACTION_WEIGHT, &[], &p2wpkh(9), 5_000)
+            .unwrap();
+
+    assert_eq!(child.input[0].previous_output, OutPoint::new(parent.compute_txid(), 0));
+    assert!(child.input[0].sequence.is_rbf());
+
+    let child_fee = CHANGE_SAT - child.output[0].value.to_sat();
+    let package_weight = parent.weight().to_wu() + child_weight(&child, &[P2WPKH_INPUT_SATISFACTION_WEIGHT]);
+    assert_eq!(package_feerate, (200 + child_fee) * 1000 / package_weight);
+    assert!((200 + child_fee) * 1000 >= 5_000 * package_weight);
+    // The child pays for the parent too, so it is well above the target alone
+    assert!(child_fee * 1000 > 5_000 * child_weight(&child, &[P2WPKH_INPUT_SATISFACTION_WEIGHT]));
+}
+
+#[test]
+fn test_child_pays_own_weight_when_parent_already_meets_target() {
+    let (child, package_feerate) =
+        create_cpfp_transaction(&parent(), 10_000, 0, P2WPKH_INPUT_SATISFACTION_WEIGHT, &[], &p2wpkh(9), 1_000)
+            .unwrap();
+
+    let child_fee = CHANGE_SAT - child.output[0].value.to_sat();
+    assert_eq!(child_fee, child_weight(&child, &[P2WPKH_INPUT_SATISFACTION_WEIGHT]));
+    assert!(package_feerate > 1_000);
+}
+
+#[test]
+fn test_anchor_needs_wallet_input() {
+    let parent = parent();
+    assert!(matches!(
+        create_cpfp_transaction(&parent, 200, 1, ANCHOR_INPUT_SATISFACTION_WEIGHT, &[], &p2wpkh(9), 2_500),
+        Err(CpfpError::InsufficientFunds { input_sat: ANCHOR_OUTPUT_VALUE_SATOSHI, .. })
+    ));
+
+    let (child, package_feerate) = create_cpfp_transaction(
+        &parent,
+        200,
+        1,
+        ANCHOR_INPUT_SATISFACTION_WEIGHT,
+        &[wallet_input()],
+        &p2wpkh(9),
+        2_500,
+    )
+    .unwrap();
+    assert_eq!(child.input.len(), 2);
+    assert_eq!(child.input[1].previous_output, wallet_input().outpoint);
+    assert!(package_feerate >= 2_500);
+}
+
+#[test]
+fn test_missing_parent_output_rejected() {
+    assert_eq!(
+        create_cpfp_transaction(&parent(), 200, 2, P2WPKH_INPUT_SATISFACTION_WEIGHT, &[], &p2wpkh(9), 2_500),
+        Err(CpfpError::OutputNotFound { output_index: 2 })
+    );
+}
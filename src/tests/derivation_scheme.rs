@@ -0,0 +1,141 @@
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::keys::scheme::{hkdf_sha256, DerivationScheme, HkdfKeysManager};
+use crate::types::CommitmentKeys;
+use crate::*;
+use bitcoin::hashes::Hash;
+use bitcoin::PublicKey as BitcoinPublicKey;
+use bitcoin::{OutPoint, Txid};
+
+const FUNDING_AMOUNT: u64 = 10_000_000;
+const TO_SELF_DELAY: u16 = 144;
+
+// Downstream code only needs the trait
+fn funding_pubkey(scheme: &impl DerivationScheme, channel_index: u32) -> PublicKey {
+    scheme.derive_channel_keys(channel_index).to_public_keys().funding_pubkey
+}
+
+// Build and sign our commitment with keys from `scheme`, and check that the
+// peer accepts the signature and that our secrets control our outputs
+fn check_commitment_with_scheme(scheme: &impl DerivationScheme) {
+    let keys = scheme.derive_channel_keys(0);
+    let local = keys.to_public_keys();
+    let peer = SimulatedPeer::new();
+    let remote = peer.public_keys();
+
+    let per_commitment_point = keys.derive_per_commitment_point(42);
+    let commitment_keys = CommitmentKeys::from_basepoints(
+        &per_commitment_point,
+        &local.delayed_payment_basepoint,
+        &local.htlc_basepoint,
+        &remote.revocation_basepoint,
+        &remote.htlc_basepoint,
+        &keys.secp_ctx,
+    );
+    let tx = create_commitment_transaction(
+        OutPoint::new(Txid::all_zeros(), 0),
+        7_000_000,
+        3_000_000,
+        &commitment_keys,
+        &local.payment_basepoint,
+        &remote.payment_basepoint,
+        42,
+        TO_SELF_DELAY,
+        546,
+        253,
+        &[],
+        &[],
+    );
+
+    let funding_script = create_funding_script(
+        &BitcoinPublicKey::new(local.funding_pubkey),
+        &BitcoinPublicKey::new(remote.funding_pubkey),
+    );
+    let signature =
+        keys.sign_transaction_input_sighash_all(&tx, 0, &funding_script, FUNDING_AMOUNT, &keys.funding_key);
+    assert_eq!(
+        peer.validate_commitment_signature(&tx, 0, &funding_script, FUNDING_AMOUNT, &signature, &local.funding_pubkey),
+        Ok(()),
+        "{} scheme",
+        scheme.name()
+    );
+
+    let delayed_privkey =
+        derive_private_key(&keys.delayed_payment_basepoint_secret, &per_commitment_point, &keys.secp_ctx);
+    assert_eq!(
+        PublicKey::from_secret_key(&keys.secp_ctx, &delayed_privkey),
+        commitment_keys.local_delayed_payment_key
+    );
+    let to_local = create_to_local_script(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_delayed_payment_key,
+        TO_SELF_DELAY,
+    );
+    assert!(tx.output.iter().any(|output| output.script_pubkey == to_local.to_p2wsh()));
+}
+
+#[test]
+fn test_keys_manager_is_the_lnd_scheme() {
+    let keys_manager = new_keys_manager([0x01; 32], Network::Bitcoin);
+
+    assert_eq!(keys_manager.name(), "lnd");
+    assert_eq!(
+        funding_pubkey(&keys_manager, 3),
+        keys_manager.derive_channel_keys(3).to_public_keys().funding_pubkey
+    );
+}
+
+#[test]
+fn test_simulated_peer_from_scheme_matches_seed() {
+    let keys_manager = new_keys_manager(SimulatedPeer::SEED, Network::Bitcoin);
+
+    assert_eq!(
+        SimulatedPeer::from_scheme(&keys_manager, 0).public_keys(),
+        SimulatedPeer::new().public_keys()
+    );
+}
+
+#[test]
+fn test_hkdf_sha256_rfc5869_case_1() {
+    let okm = hkdf_sha256(
+        &hex::decode("000102030405060708090a0b0c").unwrap(),
+        &[0x0b; 22],
+        &hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap(),
+    );
+
+    // The first 32 of the RFC's 42 output bytes
+    assert_eq!(
+        hex::encode(okm),
+        "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf"
+    );
+}
+
+#[test]
+fn test_hkdf_scheme_derives_distinct_keys() {
+    let seed = [0x01; 32];
+    let scheme = HkdfKeysManager::new(seed);
+    assert_eq!(scheme.name(), "ldk");
+
+    // Deterministic per channel, distinct across channels and from the LND scheme
+    assert_eq!(funding_pubkey(&scheme, 0), funding_pubkey(&HkdfKeysManager::new(seed), 0));
+    assert_ne!(funding_pubkey(&scheme, 0), funding_pubkey(&scheme, 1));
+    assert_ne!(funding_pubkey(&scheme, 0), funding_pubkey(&new_keys_manager(seed, Network::Bitcoin), 0));
+
+    let keys = scheme.derive_channel_keys(0);
+    let secrets = [
+        scheme.node_secret(),
+        keys.funding_key,
+        keys.revocation_basepoint_secret,
+        keys.payment_basepoint_secret,
+        keys.delayed_payment_basepoint_secret,
+        keys.htlc_basepoint_secret,
+    ];
+    for (i, a) in secrets.iter().enumerate() {
+        assert!(secrets[i + 1..].iter().all(|b| a != b));
+    }
+}
+
+#[test]
+fn test_both_schemes_build_valid_commitments() {
+    check_commitment_with_scheme(&new_keys_manager([0x01; 32], Network::Bitcoin));
+    check_commitment_with_scheme(&HkdfKeysManager::new([0x01; 32]));
+}
@@ -0,0 +1,82 @@
+use crate::transactions::fees::{htlc_trimming, is_htlc_dust, is_htlc_dust_for_channel_type, HtlcTrimming};
+use crate::types::ChannelType;
+
+// At 5000 sat/kw, HTLC-timeout costs 3_315 sat and HTLC-success costs 3_515 sat
+const FEERATE_PER_KW: u64 = 5000;
+
+#[test]
+fn test_offered_htlc_trimmed_only_on_remote() {
+    // Same dust limit on both sides: the remote's HTLC-success tx is heavier,
+    // so 546 + 3_515 = 4_061 > 4_000 trims it there, while 546 + 3_315 = 3_861 keeps it on ours
+    let trimming = htlc_trimming(4_000, 546, 546, FEERATE_PER_KW, true, ChannelType::StaticRemoteKey);
+
+    assert_eq!(
+        trimming,
+        HtlcTrimming { trimmed_on_local: false, trimmed_on_remote: true }
+    );
+    assert_eq!(trimming.to_string(), "present on local, trimmed on remote");
+}
+
+#[test]
+fn test_offered_htlc_trimmed_only_on_local() {
+    // A higher local dust limit flips it: 1_000 + 3_315 = 4_315 vs 354 + 3_515 = 3_869
+    let trimming = htlc_trimming(4_000, 1_000, 354, FEERATE_PER_KW, true, ChannelType::StaticRemoteKey);
+
+    assert_eq!(trimming.to_string(), "trimmed on local, present on remote");
+}
+
+#[test]
+fn test_received_htlc_uses_success_fee_locally() {
+    // Received by us: HTLC-success on our commitment, HTLC-timeout on theirs
+    let trimming = htlc_trimming(4_000, 546, 546, FEERATE_PER_KW, false, ChannelType::StaticRemoteKey);
+
+    assert_eq!(trimming.to_string(), "trimmed on local, present on remote");
+}
+
+#[test]
+fn test_htlc_above_both_thresholds_is_present_on_both() {
+    let trimming = htlc_trimming(10_000, 546, 546, FEERATE_PER_KW, true, ChannelType::StaticRemoteKey);
+
+    assert_eq!(trimming.to_string(), "present on local, present on remote");
+}
+
+#[test]
+fn test_legacy_dust_matches_is_htlc_dust() {
+    for amount in [546, 3_860, 3_861, 4_060, 4_061, 10_000] {
+        for outbound in [true, false] {
+            assert_eq!(
+                is_htlc_dust_for_channel_type(amount, 546, FEERATE_PER_KW, outbound, ChannelType::StaticRemoteKey),
+                is_htlc_dust(amount, 546, FEERATE_PER_KW, outbound),
+                "amount {} outbound {}",
+                amount,
+                outbound
+            );
+        }
+    }
+}
+
+#[test]
+fn test_anchors_trim_only_below_dust_limit() {
+    // Zero-fee HTLC transactions: the feerate no longer raises the threshold
+    for outbound in [true, false] {
+        assert!(is_htlc_dust_for_channel_type(545, 546, FEERATE_PER_KW, outbound, ChannelType::AnchorsZeroFeeHtlcTx));
+        assert!(!is_htlc_dust_for_channel_type(546, 546, FEERATE_PER_KW, outbound, ChannelType::AnchorsZeroFeeHtlcTx));
+    }
+}
+
+#[test]
+fn test_trim_sets_legacy_vs_anchors() {
+    let amounts = [500, 1_000, 3_500, 4_000, 5_000];
+    let trimmed = |channel_type: ChannelType| -> Vec<u64> {
+        amounts
+            .iter()
+            .copied()
+            .filter(|&amount| htlc_trimming(amount, 546, 546, FEERATE_PER_KW, true, channel_type).trimmed_on_local)
+            .collect()
+    };
+
+    // Legacy: dust limit plus the 3_315 sat HTLC-timeout fee
+    assert_eq!(trimmed(ChannelType::StaticRemoteKey), vec![500, 1_000, 3_500]);
+    // Anchors: dust limit only
+    assert_eq!(trimmed(ChannelType::AnchorsZeroFeeHtlcTx), vec![500]);
+}
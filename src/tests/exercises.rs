@@ -1,5 +1,5 @@
 use crate::transactions::create_funding_transaction;
-use crate::types::{ChannelKeyManager, KeyFamily, KeysManager};
+use crate::types::{ChannelKeyManager, ChannelType, KeyFamily, KeysManager};
 use crate::*;
 use bitcoin::bip32::{DerivationPath, Xpriv};
 use bitcoin::consensus::encode::serialize_hex;
@@ -235,10 +235,8 @@ fn test_06_create_funding_transaction() {
 
     // Funding transaction details
     let txid_hex = "8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be";
-    let mut txid_bytes = hex::decode(txid_hex).unwrap();
-    txid_bytes.reverse();
-
-    let input_txid = bitcoin::Txid::from_slice(&txid_bytes).unwrap();
+    // Txid::from_str takes the txid as displayed and handles the byte order
+    let input_txid = Txid::from_str(txid_hex).unwrap();
 
     let input_vout = 0;
     let funding_amount_sat = 500000;
@@ -286,10 +284,8 @@ fn test_07_sign_transaction_input_sighash_all() {
 
     // Funding transaction details
     let txid_hex = "8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be";
-    let mut txid_bytes = hex::decode(txid_hex).unwrap();
-    txid_bytes.reverse(); // Bitcoin uses little-endian for txids
-
-    let input_txid = bitcoin::Txid::from_slice(&txid_bytes).unwrap();
+    // Txid::from_str takes the txid as displayed and handles the byte order
+    let input_txid = Txid::from_str(txid_hex).unwrap();
 
     let input_vout = 0;
     let funding_amount_sat = 500000;
@@ -1187,26 +1183,40 @@ fn test_21_finalize_holder_commitment() {
     let local_sig_first = true;
 
     // Finalize the holder commitment
-    let signed_tx = finalize_holder_commitment(
+    let signed = finalize_holder_commitment_detailed(
         channel_keys,
         unsigned_tx,
         0,
         &funding_script,
         funding_amount,
-        remote_signature,
+        remote_signature.clone(),
         local_sig_first,
-    );
+    )
+    .unwrap();
+    let signed_tx = &signed.tx;
 
     // BOLT 3 expected complete transaction
     let expected_tx_hex = "02000000000101bef67e4e2fb9ddeeb3461973cd4c62abb35050b1add772995b820b584a488489000000000038b02b8002c0c62d0000000000160014cc1b07838e387deacd0e5232e1e8b49f4c29e48454a56a00000000002200204adb4e2f00643db396dd120d4e7dc17625f5f2c11a40d857accc862d6b7dd80e04004730440220616210b2cc4d3afb601013c373bbd8aac54febd9f15400379a8cb65ce7deca60022034236c010991beb7ff770510561ae8dc885b8d38d1947248c38f2ae05564714201483045022100c3127b33dcc741dd6b05b1e63cbd1a9a7d816f37af9b6756fa2376b056f032370220408b96279808fe57eb7e463710804cdf4f108388bc5cf722d8c848d2c7f9f3b001475221023da092f6980e58d2c037173180e9a465476026ee50f96695963e8efe436f54eb21030e9f7b623d2ccc7c9bd44d66d5ce21ce504c0acf6385a132cec6d3c39fa711c152ae3e195220";
 
     // Serialize and compare
-    let actual_tx_hex = hex::encode(bitcoin::consensus::serialize(&signed_tx));
+    let actual_tx_hex = hex::encode(bitcoin::consensus::serialize(signed_tx));
 
     assert_eq!(
         actual_tx_hex, expected_tx_hex,
         "Finalized commitment transaction should match BOLT 3 test vector"
     );
+
+    // BOLT 3 expected local signature (with SIGHASH_ALL appended)
+    let expected_local_signature = hex::decode(
+        "30440220616210b2cc4d3afb601013c373bbd8aac54febd9f15400379a8cb65ce7deca60022034236c010991beb7ff770510561ae8dc885b8d38d1947248c38f2ae05564714201"
+    ).unwrap();
+
+    assert_eq!(signed.local_sig, expected_local_signature, "Local signature should match BOLT 3 test vector");
+    assert_eq!(signed.remote_sig, remote_signature);
+    assert_eq!(signed.funding_script, funding_script);
+    assert_eq!(signed.weight, signed_tx.weight().to_wu());
+    // 15000 sat/kw on the 724 weight units BOLT 3 charges a commitment with no HTLCs
+    assert_eq!(signed.fee, 10_860, "Fee should match BOLT 3 test vector");
 }
 
 #[test]
@@ -1229,7 +1239,7 @@ fn test_22_create_offered_htlc_script() {
 
     // BOLT 3 test vector: HTLC #2 uses payment_hash = SHA256(0x0202...02)
     let preimage = [0x02u8; 32];
-    let payment_hash = Sha256::hash(&preimage).to_byte_array();
+    let payment_hash = PaymentHash::from_preimage(&preimage);
 
     // Create offered HTLC script
     let script = create_offered_htlc_script(
@@ -1333,7 +1343,7 @@ fn test_23_create_htlc_timeout_transaction() {
 
     // BOLT 3 HTLC #2 offered script (payment_hash = SHA256(0x0202...02))
     let preimage = [0x02u8; 32];
-    let payment_hash = Sha256::hash(&preimage).to_byte_array();
+    let payment_hash = PaymentHash::from_preimage(&preimage);
 
     let htlc_script = create_offered_htlc_script(
         &revocation_pubkey,
@@ -1353,13 +1363,8 @@ fn test_23_create_htlc_timeout_transaction() {
     ).unwrap();
 
     // Create witness for HTLC timeout: [0, remote_sig, local_sig, 0 (false), htlc_script]
-    let htlc_timeout_witness = Witness::from_slice(&[
-        &[][..],
-        &remote_htlc_signature[..],
-        &local_htlc_signature[..],
-        &[][..],
-        htlc_script.as_bytes(),
-    ]);
+    let htlc_timeout_witness =
+        create_htlc_timeout_witness(&remote_htlc_signature, &local_htlc_signature, &htlc_script);
 
     // Attach witness to create signed transaction
     let mut signed_htlc_timeout_tx = unsigned_htlc_timeout_tx;
@@ -1461,7 +1466,7 @@ fn test_24_finalize_htlc_timeout() {
 
     // BOLT 3 HTLC #2 offered script (payment_hash = SHA256(0x0202...02))
     let preimage = [0x02u8; 32];
-    let payment_hash = Sha256::hash(&preimage).to_byte_array();
+    let payment_hash = PaymentHash::from_preimage(&preimage);
 
     let htlc_script = create_offered_htlc_script(
         &revocation_pubkey,
@@ -1484,6 +1489,7 @@ fn test_24_finalize_htlc_timeout() {
         htlc_amount,
         remote_htlc_signature,
         local_htlc_privkey,
+        ChannelType::StaticRemoteKey,
     );
 
     // BOLT 3 expected HTLC timeout transaction
@@ -1518,7 +1524,7 @@ fn test_25_create_received_htlc_script() {
 
     // BOLT 3 test vector: HTLC #0 uses preimage = 0x0000...00
     let preimage = [0x00u8; 32];
-    let payment_hash = Sha256::hash(&preimage).to_byte_array();
+    let payment_hash = PaymentHash::from_preimage(&preimage);
     let cltv_expiry = 500;
 
     // Create received HTLC script
@@ -1623,7 +1629,7 @@ fn test_26_create_htlc_success_transaction() {
 
     // BOLT 3 HTLC #0 received script (preimage = 0x0000...00, cltv_expiry = 500)
     let payment_preimage = [0x00u8; 32];
-    let payment_hash = Sha256::hash(&payment_preimage).to_byte_array();
+    let payment_hash = PaymentHash::from_preimage(&payment_preimage);
 
     let htlc_script = create_received_htlc_script(
         &revocation_pubkey,
@@ -1644,13 +1650,12 @@ fn test_26_create_htlc_success_transaction() {
     ).unwrap();
 
     // Create witness for HTLC success: [0, remote_sig, local_sig, payment_preimage, htlc_script]
-    let htlc_success_witness = Witness::from_slice(&[
-        &[][..],
-        &remote_htlc_signature[..],
-        &local_htlc_signature[..],
-        &payment_preimage[..],
-        htlc_script.as_bytes(),
-    ]);
+    let htlc_success_witness = create_htlc_success_witness(
+        &remote_htlc_signature,
+        &local_htlc_signature,
+        &payment_preimage,
+        &htlc_script,
+    );
 
     // Attach witness to create signed transaction
     let mut signed_htlc_success_tx = unsigned_htlc_success_tx;
@@ -1751,7 +1756,7 @@ fn test_27_finalize_htlc_success() {
 
     // BOLT 3 HTLC #0 received script (preimage = 0x0000...00, cltv_expiry = 500)
     let payment_preimage = [0x00u8; 32];
-    let payment_hash = Sha256::hash(&payment_preimage).to_byte_array();
+    let payment_hash = PaymentHash::from_preimage(&payment_preimage);
 
     let htlc_script = create_received_htlc_script(
         &revocation_pubkey,
@@ -1776,6 +1781,7 @@ fn test_27_finalize_htlc_success() {
         remote_htlc_signature,
         local_htlc_privkey,
         payment_preimage,
+        ChannelType::StaticRemoteKey,
     );
 
     // BOLT 3 expected HTLC success transaction
@@ -1833,12 +1839,12 @@ fn test_28_create_htlc_outputs() {
     let offered_htlcs = vec![
         HTLCOutput {
             amount_sat: 2000,
-            payment_hash: Sha256::hash(&[0x02u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x02u8; 32]),
             cltv_expiry: 502,
         },
         HTLCOutput {
             amount_sat: 3000,
-            payment_hash: Sha256::hash(&[0x03u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x03u8; 32]),
             cltv_expiry: 503,
         },
     ];
@@ -1847,17 +1853,17 @@ fn test_28_create_htlc_outputs() {
     let received_htlcs = vec![
         HTLCOutput {
             amount_sat: 1000,
-            payment_hash: Sha256::hash(&[0x00u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x00u8; 32]),
             cltv_expiry: 500,
         },
         HTLCOutput {
             amount_sat: 2000,
-            payment_hash: Sha256::hash(&[0x01u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x01u8; 32]),
             cltv_expiry: 501,
         },
         HTLCOutput {
             amount_sat: 4000,
-            payment_hash: Sha256::hash(&[0x04u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x04u8; 32]),
             cltv_expiry: 504,
         },
     ];
@@ -2062,12 +2068,12 @@ fn test_29_create_commitment_transaction_with_htlcs() {
     let offered_htlcs = vec![
         HTLCOutput {
             amount_sat: 2000,
-            payment_hash: Sha256::hash(&[0x02u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x02u8; 32]),
             cltv_expiry: 502,
         },
         HTLCOutput {
             amount_sat: 3000,
-            payment_hash: Sha256::hash(&[0x03u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x03u8; 32]),
             cltv_expiry: 503,
         },
     ];
@@ -2075,17 +2081,17 @@ fn test_29_create_commitment_transaction_with_htlcs() {
     let received_htlcs = vec![
         HTLCOutput {
             amount_sat: 1000,
-            payment_hash: Sha256::hash(&[0x00u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x00u8; 32]),
             cltv_expiry: 500,
         },
         HTLCOutput {
             amount_sat: 2000,
-            payment_hash: Sha256::hash(&[0x01u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x01u8; 32]),
             cltv_expiry: 501,
         },
         HTLCOutput {
             amount_sat: 4000,
-            payment_hash: Sha256::hash(&[0x04u8; 32]).to_byte_array(),
+            payment_hash: PaymentHash::from_preimage(&[0x04u8; 32]),
             cltv_expiry: 504,
         },
     ];
@@ -0,0 +1,27 @@
+use crate::internal::explorer::{address_url, tx_url};
+use bitcoin::{Address, Txid};
+use std::str::FromStr;
+
+const TXID: &str = "8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be";
+const ADDRESS: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+#[test]
+fn test_tx_url() {
+    let txid = Txid::from_str(TXID).unwrap();
+
+    assert_eq!(tx_url("https://mempool.space", &txid), format!("https://mempool.space/tx/{}", TXID));
+    assert_eq!(
+        tx_url("https://mempool.space/signet/", &txid),
+        format!("https://mempool.space/signet/tx/{}", TXID)
+    );
+}
+
+#[test]
+fn test_address_url() {
+    let address = Address::from_str(ADDRESS).unwrap().assume_checked();
+
+    assert_eq!(
+        address_url("http://localhost:3002", &address),
+        format!("http://localhost:3002/address/{}", ADDRESS)
+    );
+}
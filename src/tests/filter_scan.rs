@@ -0,0 +1,87 @@
+use crate::internal::filter_scan::{filter_matches, scan_block};
+use crate::internal::script_index::{OwnedScriptKind, ScriptIndex};
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::*;
+use bitcoin::bip158::BlockFilter;
+use bitcoin::blockdata::constants::genesis_block;
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, Block, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness, WPubkeyHash};
+
+const TO_SELF_DELAY: u16 = 144;
+
+fn channel_index() -> ScriptIndex {
+    let keys = new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0);
+    ScriptIndex::for_channel(&keys, &SimulatedPeer::new().public_keys(), TO_SELF_DELAY, 0..=9)
+}
+
+fn foreign_script(byte: u8) -> ScriptBuf {
+    ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([byte; 20]))
+}
+
+fn paying_to(script_pubkeys: &[ScriptBuf]) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::from_byte_array([0x11; 32]), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: script_pubkeys
+            .iter()
+            .map(|script_pubkey| TxOut {
+                value: Amount::from_sat(10_000),
+                script_pubkey: script_pubkey.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn block_with(txdata: Vec<Transaction>) -> (Block, BlockFilter) {
+    let mut block = genesis_block(Network::Regtest);
+    block.txdata.extend(txdata);
+    let filter = BlockFilter::new_script_filter(&block, |_| Ok(foreign_script(0xee)))
+        .expect("filter builds");
+    (block, filter)
+}
+
+#[test]
+fn test_filter_skips_block_without_our_scripts() {
+    let index = channel_index();
+    let (block, filter) = block_with(vec![paying_to(&[foreign_script(1), foreign_script(2)])]);
+
+    assert!(!filter_matches(&filter, &block.block_hash(), &index));
+    assert!(scan_block(&block, 1, &index).is_empty());
+}
+
+#[test]
+fn test_filter_matches_block_paying_to_remote() {
+    let index = channel_index();
+    let to_remote = index
+        .script_pubkeys()
+        .find(|script| index.classify(script).unwrap().kind == OwnedScriptKind::ToRemote)
+        .unwrap()
+        .clone();
+    let tx = paying_to(&[foreign_script(1), to_remote]);
+    let (block, filter) = block_with(vec![paying_to(&[foreign_script(2)]), tx.clone()]);
+
+    assert!(filter_matches(&filter, &block.block_hash(), &index));
+
+    let matches = scan_block(&block, 7, &index);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].height, 7);
+    assert_eq!(matches[0].block_hash, block.block_hash());
+    assert_eq!(matches[0].txid, tx.compute_txid());
+    assert_eq!(matches[0].outputs.len(), 1);
+    assert_eq!(matches[0].outputs[0].0, 1);
+    assert_eq!(matches[0].outputs[0].1.kind, OwnedScriptKind::ToRemote);
+}
+
+#[test]
+fn test_empty_index_never_matches() {
+    let (block, filter) = block_with(vec![paying_to(&[foreign_script(1)])]);
+    assert!(!filter_matches(&filter, &block.block_hash(), &ScriptIndex::new()));
+}
@@ -0,0 +1,16 @@
+// Deterministic keys shared by the test modules: `byte` is repeated 32
+// times to form the secret key, so distinct bytes give distinct keys
+use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use bitcoin::PublicKey as BitcoinPublicKey;
+
+pub fn test_secret(byte: u8) -> SecretKey {
+    SecretKey::from_slice(&[byte; 32]).unwrap()
+}
+
+pub fn test_pubkey(byte: u8) -> PublicKey {
+    PublicKey::from_secret_key(&Secp256k1::new(), &test_secret(byte))
+}
+
+pub fn test_bitcoin_pubkey(byte: u8) -> BitcoinPublicKey {
+    BitcoinPublicKey::new(test_pubkey(byte))
+}
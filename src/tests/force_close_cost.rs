@@ -0,0 +1,41 @@
+use crate::transactions::fees::{calculate_commitment_tx_fee, estimate_force_close_cost, ForceCloseCost};
+use crate::transactions::weights::ANCHOR_INPUT_SATISFACTION_WEIGHT;
+use crate::types::ChannelType;
+
+#[test]
+fn test_force_close_cost_without_htlcs() {
+    let cost = estimate_force_close_cost(ChannelType::StaticRemoteKey, 0, 0, 1000);
+
+    assert_eq!(
+        cost,
+        ForceCloseCost {
+            commitment_fee_sat: 724,
+            htlc_fees_sat: 0,
+            anchor_cpfp_fee_sat: 0,
+            sweep_fees_sat: 484,
+        }
+    );
+    assert_eq!(cost.total_sat(), 1_208);
+}
+
+#[test]
+fn test_force_close_cost_with_htlcs() {
+    // One offered (timeout) and one received (success) HTLC at 2000 sat/kw
+    let cost = estimate_force_close_cost(ChannelType::StaticRemoteKey, 1, 1, 2000);
+
+    assert_eq!(cost.commitment_fee_sat, calculate_commitment_tx_fee(2000, 2));
+    assert_eq!(cost.htlc_fees_sat, 2 * 663 + 2 * 703);
+    assert_eq!(cost.sweep_fees_sat, 3 * 2 * 484);
+}
+
+#[test]
+fn test_anchors_force_close_includes_anchor_cpfp() {
+    let legacy = estimate_force_close_cost(ChannelType::StaticRemoteKey, 1, 0, 1000);
+    let anchors = estimate_force_close_cost(ChannelType::AnchorsZeroFeeHtlcTx, 1, 0, 1000);
+
+    // The child's fee, not the 330 sats our anchor pays back to us
+    assert_eq!(anchors.anchor_cpfp_fee_sat, 328 + ANCHOR_INPUT_SATISFACTION_WEIGHT);
+    assert_eq!(anchors.commitment_fee_sat, 1124 + 172);
+    assert_eq!(anchors.htlc_fees_sat, 666);
+    assert!(anchors.total_sat() > legacy.total_sat());
+}
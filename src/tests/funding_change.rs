@@ -0,0 +1,101 @@
+use crate::transactions::funding::{
+    create_funding_transaction_with_change, FundingChangeError, P2PKH_INPUT_SATISFACTION_WEIGHT,
+    P2WPKH_INPUT_SATISFACTION_WEIGHT,
+};
+use bitcoin::consensus::encode::{deserialize, serialize_hex};
+use bitcoin::script::ScriptBuf;
+use bitcoin::{OutPoint, PublicKey, Transaction, Txid};
+use std::str::FromStr;
+
+// BOLT 3 Appendix B: a block 1 coinbase output funds the channel
+const INPUT_AMOUNT: u64 = 5_000_000_000;
+const FUNDING_AMOUNT: u64 = 10_000_000;
+const FEERATE_PER_KW: u64 = 15_000;
+
+fn input() -> OutPoint {
+    OutPoint::new(
+        Txid::from_str("fd2105607605d2302994ffea703b09f66b6351816ee737a93e42a841ea20bbad").unwrap(),
+        0,
+    )
+}
+
+fn funding_pubkeys() -> (PublicKey, PublicKey) {
+    (
+        PublicKey::from_str("023da092f6980e58d2c037173180e9a465476026ee50f96695963e8efe436f54eb").unwrap(),
+        PublicKey::from_str("030e9f7b623d2ccc7c9bd44d66d5ce21ce504c0acf6385a132cec6d3c39fa711c1").unwrap(),
+    )
+}
+
+fn change_script() -> ScriptBuf {
+    ScriptBuf::from_hex("00143ca33c2e4446f4a305f23c80df8ad1afdcf652f9").unwrap()
+}
+
+fn build(input_amount: u64, satisfaction_weight: u64) -> Result<Transaction, FundingChangeError> {
+    let (local, remote) = funding_pubkeys();
+    create_funding_transaction_with_change(
+        input(),
+        input_amount,
+        satisfaction_weight,
+        FUNDING_AMOUNT,
+        &local,
+        &remote,
+        &change_script(),
+        FEERATE_PER_KW,
+    )
+}
+
+#[test]
+fn test_funding_with_change_matches_appendix_b() {
+    let tx = build(INPUT_AMOUNT, P2PKH_INPUT_SATISFACTION_WEIGHT).unwrap();
+
+    // The unsigned Appendix B funding transaction: 13_920 sats of fee at 15_000 sat/kw
+    assert_eq!(
+        serialize_hex(&tx),
+        "0200000001adbb20ea41a8423ea937e76e8151636bf6093b70eaff942930d20576600521fd0000000000ffffffff02\
+         8096980000000000220020c015c4a6be010e21657068fc2e6a9d02b27ebe4d490a25846f7237f104d1a3cd\
+         20256d29010000001600143ca33c2e4446f4a305f23c80df8ad1afdcf652f900000000"
+    );
+    assert_eq!(tx.output[1].value.to_sat(), INPUT_AMOUNT - FUNDING_AMOUNT - 13_920);
+
+    // Once signed, it is the funding transaction every Appendix C vector spends
+    let signed: Transaction = deserialize(&hex::decode(
+        "0200000001adbb20ea41a8423ea937e76e8151636bf6093b70eaff942930d20576600521fd000000006b483045\
+         02210090587b6201e166ad6af0227d3036a9454223d49a1f11839c1a362184340ef0240220577f7cd5cca787\
+         19405cbf1de7414ac027f0239ef6e214c90fcaab0454d84b3b012103535b32d5eb0a6ed0982a0479bbadc98\
+         68d9836f6ba94dd5a63be16d875069184ffffffff028096980000000000220020c015c4a6be010e21657068\
+         fc2e6a9d02b27ebe4d490a25846f7237f104d1a3cd20256d29010000001600143ca33c2e4446f4a305f23c80\
+         df8ad1afdcf652f900000000",
+    )
+    .unwrap())
+    .unwrap();
+    assert_eq!(signed.output, tx.output);
+    assert_eq!(
+        signed.compute_txid().to_string(),
+        "8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be"
+    );
+}
+
+#[test]
+fn test_segwit_input_pays_less_fee() {
+    let legacy = build(INPUT_AMOUNT, P2PKH_INPUT_SATISFACTION_WEIGHT).unwrap();
+    let segwit = build(INPUT_AMOUNT, P2WPKH_INPUT_SATISFACTION_WEIGHT).unwrap();
+
+    assert!(segwit.output[1].value > legacy.output[1].value);
+}
+
+#[test]
+fn test_dust_change_goes_to_fee() {
+    // Just enough to pay the fee without a change output, plus a little
+    let tx = build(FUNDING_AMOUNT + 14_000, P2PKH_INPUT_SATISFACTION_WEIGHT).unwrap();
+
+    assert_eq!(tx.output.len(), 1);
+    assert_eq!(tx.output[0].value.to_sat(), FUNDING_AMOUNT);
+}
+
+#[test]
+fn test_insufficient_funds() {
+    assert!(matches!(
+        build(FUNDING_AMOUNT, P2PKH_INPUT_SATISFACTION_WEIGHT),
+        Err(FundingChangeError::InsufficientFunds { input_amount_sat: FUNDING_AMOUNT, .. })
+    ));
+}
@@ -0,0 +1,34 @@
+use crate::transactions::funding::{
+    check_funding_amount, FundingAmountError, MAX_FUNDING_SATOSHIS_NO_WUMBO,
+};
+use bitcoin::Amount;
+
+#[test]
+fn test_funding_below_2_pow_24_without_wumbo() {
+    assert_eq!(MAX_FUNDING_SATOSHIS_NO_WUMBO, 16_777_215);
+    assert_eq!(check_funding_amount(5_000_000, false), Ok(()));
+    assert_eq!(check_funding_amount(MAX_FUNDING_SATOSHIS_NO_WUMBO, false), Ok(()));
+    assert_eq!(
+        check_funding_amount(1 << 24, false),
+        Err(FundingAmountError::TooLarge {
+            funding_satoshis: 1 << 24,
+            max_funding_satoshis: MAX_FUNDING_SATOSHIS_NO_WUMBO,
+        })
+    );
+}
+
+#[test]
+fn test_wumbo_allows_large_channels() {
+    assert_eq!(check_funding_amount(1 << 24, true), Ok(()));
+    assert_eq!(check_funding_amount(Amount::MAX_MONEY.to_sat(), true), Ok(()));
+    assert!(matches!(
+        check_funding_amount(Amount::MAX_MONEY.to_sat() + 1, true),
+        Err(FundingAmountError::TooLarge { .. })
+    ));
+}
+
+#[test]
+fn test_zero_funding_rejected() {
+    assert_eq!(check_funding_amount(0, false), Err(FundingAmountError::Zero));
+    assert_eq!(check_funding_amount(0, true), Err(FundingAmountError::Zero));
+}
@@ -0,0 +1,133 @@
+use crate::interactive::funding::{
+    add_funding_output, export_funding_psbt, import_signed_funding_psbt, non_segwit_inputs,
+    FundingPsbtError,
+};
+use crate::transactions::funding::create_funding_transaction;
+use crate::scripts::funding::create_funding_script;
+use crate::tests::fixtures::test_bitcoin_pubkey;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::psbt::Psbt;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::hashes::Hash;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness, WPubkeyHash};
+
+// An external wallet's PSBT: one 6_000_000 sat input and a change output
+fn wallet_psbt() -> Psbt {
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut { value: Amount::from_sat(990_000), script_pubkey: ScriptBuf::new() }],
+    };
+    let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+    psbt.inputs[0].witness_utxo = Some(TxOut {
+        value: Amount::from_sat(6_000_000),
+        script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([0x11; 20])),
+    });
+    psbt
+}
+
+#[test]
+fn test_funding_output_appended_to_psbt() {
+    let mut psbt = wallet_psbt();
+    let index = add_funding_output(&mut psbt, 5_000_000, &test_bitcoin_pubkey(1), &test_bitcoin_pubkey(2));
+
+    let funding_script = create_funding_script(&test_bitcoin_pubkey(1), &test_bitcoin_pubkey(2));
+    assert_eq!(index, 1);
+    assert_eq!(psbt.unsigned_tx.output[index].value, Amount::from_sat(5_000_000));
+    assert_eq!(psbt.unsigned_tx.output[index].script_pubkey, funding_script.to_p2wsh());
+    assert_eq!(psbt.outputs.len(), psbt.unsigned_tx.output.len());
+    assert_eq!(psbt.outputs[index].witness_script, Some(funding_script));
+
+    // The wallet's input pays for both outputs
+    assert_eq!(psbt.fee().unwrap(), Amount::from_sat(10_000));
+}
+
+#[test]
+fn test_outpoint_needs_segwit_inputs() {
+    let mut psbt = wallet_psbt();
+    assert!(non_segwit_inputs(&psbt).is_empty());
+
+    // A legacy input's scriptSig is part of the txid
+    psbt.inputs[0].witness_utxo.as_mut().unwrap().script_pubkey =
+        ScriptBuf::new_p2pkh(&bitcoin::PubkeyHash::from_byte_array([0x11; 20]));
+    assert_eq!(non_segwit_inputs(&psbt), vec![0]);
+
+    // So is an input the wallet gave no witness_utxo for
+    psbt.inputs[0].witness_utxo = None;
+    assert_eq!(non_segwit_inputs(&psbt), vec![0]);
+}
+
+#[test]
+fn test_funding_psbt_round_trips() {
+    let mut psbt = wallet_psbt();
+    add_funding_output(&mut psbt, 5_000_000, &test_bitcoin_pubkey(1), &test_bitcoin_pubkey(2));
+
+    let decoded = Psbt::deserialize(&psbt.serialize()).unwrap();
+    assert_eq!(decoded, psbt);
+}
+
+fn wallet_utxo() -> TxOut {
+    TxOut {
+        value: Amount::from_sat(5_001_000),
+        script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([0x33; 20])),
+    }
+}
+
+fn exported_psbt() -> (Psbt, ScriptBuf) {
+    let funding_script = create_funding_script(&test_bitcoin_pubkey(1), &test_bitcoin_pubkey(2));
+    let tx = create_funding_transaction(Txid::from_byte_array([0x44; 32]), 1, 5_000_000, &test_bitcoin_pubkey(1), &test_bitcoin_pubkey(2));
+    let psbt = export_funding_psbt(tx, &[wallet_utxo()], &funding_script).unwrap();
+    (psbt, funding_script)
+}
+
+#[test]
+fn test_exported_funding_psbt_carries_signing_metadata() {
+    let (psbt, funding_script) = exported_psbt();
+
+    assert_eq!(psbt.inputs[0].witness_utxo, Some(wallet_utxo()));
+    assert_eq!(psbt.outputs[0].witness_script, Some(funding_script));
+    assert_eq!(psbt.fee().unwrap(), Amount::from_sat(1_000));
+    assert_eq!(Psbt::deserialize(&psbt.serialize()).unwrap(), psbt);
+}
+
+#[test]
+fn test_unsigned_funding_psbt_is_not_imported() {
+    let (psbt, funding_script) = exported_psbt();
+    assert_eq!(
+        import_signed_funding_psbt(psbt, &funding_script),
+        Err(FundingPsbtError::InputNotFinalized { input_index: 0 })
+    );
+}
+
+#[test]
+fn test_signed_funding_psbt_imports_with_same_txid() {
+    let (mut psbt, funding_script) = exported_psbt();
+    let unsigned_txid = psbt.unsigned_tx.compute_txid();
+
+    // What the external wallet hands back after signing and finalizing
+    let witness = Witness::from_slice(&[vec![0x30; 72], test_bitcoin_pubkey(3).to_bytes()]);
+    psbt.inputs[0].final_script_witness = Some(witness.clone());
+
+    let tx = import_signed_funding_psbt(psbt, &funding_script).unwrap();
+    assert_eq!(tx.input[0].witness, witness);
+    assert_eq!(tx.compute_txid(), unsigned_txid);
+}
+
+#[test]
+fn test_signed_psbt_without_funding_output_rejected() {
+    let (mut psbt, _) = exported_psbt();
+    psbt.inputs[0].final_script_witness = Some(Witness::from_slice(&[vec![0x30; 72]]));
+
+    let other_script = create_funding_script(&test_bitcoin_pubkey(1), &test_bitcoin_pubkey(3));
+    assert_eq!(
+        import_signed_funding_psbt(psbt, &other_script),
+        Err(FundingPsbtError::MissingFundingOutput)
+    );
+}
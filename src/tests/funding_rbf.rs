@@ -0,0 +1,142 @@
+use crate::interactive::funding::{
+    build_rbf_funding_transactions, FundingRbfError, RBF_FEE_AND_CHANGE_RESERVE_SAT,
+};
+use crate::transactions::funding::{
+    bump_funding_transaction_fee, create_funding_transaction_with_change, FundingBumpError,
+    P2WPKH_INPUT_SATISFACTION_WEIGHT,
+};
+use crate::tests::fixtures::test_bitcoin_pubkey;
+use bitcoin::hashes::Hash;
+use bitcoin::script::ScriptBuf;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, Txid, Witness, WPubkeyHash};
+
+const INPUT_AMOUNT: u64 = 1_000_000;
+const FUNDING_AMOUNT: u64 = 900_000;
+
+fn change_script() -> ScriptBuf {
+    ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([0x33; 20]))
+}
+
+fn fee(tx: &Transaction) -> u64 {
+    INPUT_AMOUNT - tx.output.iter().map(|output| output.value.to_sat()).sum::<u64>()
+}
+
+fn original(feerate_per_kw: u64) -> Transaction {
+    let mut tx = create_funding_transaction_with_change(
+        OutPoint::new(Txid::from_byte_array([0x44; 32]), 0),
+        INPUT_AMOUNT,
+        P2WPKH_INPUT_SATISFACTION_WEIGHT,
+        FUNDING_AMOUNT,
+        &test_bitcoin_pubkey(1),
+        &test_bitcoin_pubkey(2),
+        &change_script(),
+        feerate_per_kw,
+    )
+    .unwrap();
+    tx.input[0].witness = Witness::from_slice(&[vec![0x30; 72], test_bitcoin_pubkey(3).to_bytes()]);
+    tx
+}
+
+fn bump(tx: &Transaction, feerate_per_kw: u64) -> Result<Transaction, FundingBumpError> {
+    bump_funding_transaction_fee(tx, INPUT_AMOUNT, P2WPKH_INPUT_SATISFACTION_WEIGHT, feerate_per_kw)
+}
+
+#[test]
+fn test_bump_takes_fee_from_change() {
+    let tx = original(253);
+    let bumped = bump(&tx, 2_500).unwrap();
+
+    assert_eq!(bumped.output[0], tx.output[0]);
+    assert_eq!(bumped.output[1].script_pubkey, change_script());
+    assert!(fee(&bumped) > fee(&tx));
+    assert_eq!(
+        fee(&bumped),
+        2_500 * (bumped.weight().to_wu() + P2WPKH_INPUT_SATISFACTION_WEIGHT) / 1000
+    );
+    assert_ne!(bumped.compute_txid(), tx.compute_txid());
+}
+
+#[test]
+fn test_bump_signals_rbf_and_clears_signatures() {
+    let bumped = bump(&original(253), 2_500).unwrap();
+
+    assert_eq!(bumped.input[0].sequence, Sequence::ENABLE_RBF_NO_LOCKTIME);
+    assert!(bumped.input[0].sequence.is_rbf());
+    assert!(bumped.input[0].witness.is_empty());
+}
+
+#[test]
+fn test_bump_drops_dust_change() {
+    // Leave just enough change that a large bump eats it
+    let tx = original(253);
+    let mut unsigned = tx.clone();
+    unsigned.input[0].witness = Witness::new();
+    let change_sat = tx.output[1].value.to_sat();
+    let feerate_per_kw = (fee(&tx) + change_sat - 100) * 1000
+        / (unsigned.weight().to_wu() + P2WPKH_INPUT_SATISFACTION_WEIGHT);
+
+    let bumped = bump(&tx, feerate_per_kw).unwrap();
+    assert_eq!(bumped.output.len(), 1);
+    assert_eq!(fee(&bumped), INPUT_AMOUNT - FUNDING_AMOUNT);
+}
+
+#[test]
+fn test_bump_must_beat_old_fee_by_relay_fee() {
+    let tx = original(2_500);
+    assert!(matches!(
+        bump(&tx, 2_500),
+        Err(FundingBumpError::FeeBumpTooSmall { old_fee_sat, new_fee_sat, .. })
+            if old_fee_sat == fee(&tx) && new_fee_sat == fee(&tx)
+    ));
+    assert!(bump(&tx, 2_500 + 250).is_ok());
+}
+
+#[test]
+fn test_bump_beyond_input_rejected() {
+    assert!(matches!(
+        bump(&original(253), 1_000_000),
+        Err(FundingBumpError::InsufficientFunds { input_amount_sat: INPUT_AMOUNT, .. })
+    ));
+}
+
+#[test]
+fn test_bumped_funding_amount_unchanged() {
+    let bumped = bump(&original(253), 10_000).unwrap();
+    assert_eq!(bumped.output[0].value, Amount::from_sat(FUNDING_AMOUNT));
+}
+
+#[test]
+fn test_rbf_demo_funds_a_workshop_utxo() {
+    // start.sh hands out 0.05 BTC UTXOs, the ones `funding --rbf-feerate` picks
+    let input_amount_sat = 5_000_000;
+    let (original, bumped) = build_rbf_funding_transactions(
+        OutPoint::new(Txid::from_byte_array([0x44; 32]), 0),
+        input_amount_sat,
+        &change_script(),
+        2_500,
+    )
+    .unwrap();
+
+    let funding_amount = Amount::from_sat(input_amount_sat - RBF_FEE_AND_CHANGE_RESERVE_SAT);
+    assert_eq!(original.output[0].value, funding_amount);
+    assert_eq!(bumped.output[0].value, funding_amount);
+    assert!(original.input[0].sequence.is_rbf());
+    // Both keep their change, which pays for the bump
+    assert_eq!(original.output[1].script_pubkey, change_script());
+    assert_eq!(bumped.output[1].script_pubkey, change_script());
+    assert!(bumped.output[1].value < original.output[1].value);
+}
+
+#[test]
+fn test_rbf_demo_rejects_input_below_reserve() {
+    let input_amount_sat = RBF_FEE_AND_CHANGE_RESERVE_SAT - 1;
+    assert_eq!(
+        build_rbf_funding_transactions(
+            OutPoint::new(Txid::from_byte_array([0x44; 32]), 0),
+            input_amount_sat,
+            &change_script(),
+            2_500,
+        ),
+        Err(FundingRbfError::InputTooSmall { input_amount_sat })
+    );
+}
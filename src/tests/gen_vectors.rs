@@ -0,0 +1,87 @@
+use crate::interactive::gen_vectors::{generate_vectors, parse_htlc_spec, HtlcSpec, VectorParams};
+use bitcoin::consensus::encode;
+use bitcoin::{Transaction, Txid};
+use std::str::FromStr;
+
+fn params() -> VectorParams {
+    VectorParams {
+        local_seed: [0x01; 32],
+        remote_seed: [0x02; 32],
+        funding_txid: Txid::from_str(
+            "8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be",
+        )
+        .unwrap(),
+        funding_output_index: 0,
+        funding_amount_sat: 10_000_000,
+        commitment_number: 42,
+        to_self_delay: 144,
+        dust_limit_satoshis: 546,
+        feerate_per_kw: 0,
+        to_local_msat: 6_988_000_000,
+        to_remote_msat: 3_000_000_000,
+        offered_htlcs: vec![HtlcSpec { amount_sat: 2_000, cltv_expiry: 502 }],
+        received_htlcs: vec![
+            HtlcSpec { amount_sat: 1_000, cltv_expiry: 500 },
+            // Below the dust limit: trimmed, no HTLC-success tx
+            HtlcSpec { amount_sat: 100, cltv_expiry: 501 },
+        ],
+    }
+}
+
+fn decode_tx(value: &serde_json::Value) -> Transaction {
+    encode::deserialize(&hex::decode(value.as_str().unwrap()).unwrap()).unwrap()
+}
+
+#[test]
+fn test_parse_htlc_spec() {
+    assert_eq!(parse_htlc_spec("2000:502"), Ok(HtlcSpec { amount_sat: 2_000, cltv_expiry: 502 }));
+    assert!(parse_htlc_spec("2000").is_err());
+    assert!(parse_htlc_spec("two:502").is_err());
+}
+
+#[test]
+fn test_generated_htlc_txs_spend_the_commitment() {
+    let vectors = generate_vectors(&params()).unwrap();
+    let commitment_tx = decode_tx(&vectors["output_commit_tx"]);
+    let htlc_txs = vectors["htlc_txs"].as_array().unwrap();
+
+    // to_local, to_remote and the two untrimmed HTLCs
+    assert_eq!(commitment_tx.output.len(), 4);
+    assert_eq!(htlc_txs.len(), 2);
+
+    let mut last_index = None;
+    for vector in htlc_txs {
+        let output_index = vector["commitment_output_index"].as_u64().unwrap() as u32;
+        let tx = decode_tx(&vector["expected_tx_hex"]);
+
+        assert_eq!(tx.input[0].previous_output.txid, commitment_tx.compute_txid());
+        assert_eq!(tx.input[0].previous_output.vout, output_index);
+        assert_eq!(
+            commitment_tx.output[output_index as usize].value.to_sat(),
+            vector["amount_sat"].as_u64().unwrap()
+        );
+        // Listed in commitment output order, like the BOLT 3 appendix
+        assert!(last_index < Some(output_index));
+        last_index = Some(output_index);
+    }
+}
+
+#[test]
+fn test_generated_vectors_are_deterministic() {
+    assert_eq!(generate_vectors(&params()).unwrap(), generate_vectors(&params()).unwrap());
+
+    let other_seed = VectorParams { remote_seed: [0x03; 32], ..params() };
+    assert_ne!(
+        generate_vectors(&params()).unwrap()["output_commit_tx"],
+        generate_vectors(&other_seed).unwrap()["output_commit_tx"]
+    );
+}
+
+#[test]
+fn test_balances_above_funding_amount_are_refused() {
+    // 9_991_000 sats of outputs from a 5_000_000 sat funding output
+    let overdrawn = VectorParams { funding_amount_sat: 5_000_000, ..params() };
+    let error = generate_vectors(&overdrawn).unwrap_err();
+
+    assert!(error.contains("OutputsExceedFunding"), "{}", error);
+}
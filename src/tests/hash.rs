@@ -0,0 +1,40 @@
+use crate::interactive::hash::{decode_input, hash_bytes, payment_hashes, HashAlgo, InputEncoding};
+use crate::internal::hex_utils::ParseError;
+
+#[test]
+fn test_decode_input_by_encoding() {
+    assert_eq!(decode_input("00ff", InputEncoding::Hex), Ok(vec![0x00, 0xff]));
+    // The same text hashed as a string is its UTF-8 bytes
+    assert_eq!(decode_input("00ff", InputEncoding::Utf8), Ok(b"00ff".to_vec()));
+    assert_eq!(
+        decode_input("0g", InputEncoding::Hex),
+        Err(ParseError::InvalidHexCharacter { c: 'g', index: 1 })
+    );
+    assert_eq!(decode_input("abc", InputEncoding::Hex), Err(ParseError::OddLength(3)));
+}
+
+#[test]
+fn test_hash_bytes_of_empty_input() {
+    assert_eq!(
+        hex::encode(hash_bytes(&[], HashAlgo::Sha256)),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex::encode(hash_bytes(&[], HashAlgo::Hash160)),
+        "b472a266d0bd89c13706a4132ccfb16f7c3b9fcb"
+    );
+}
+
+#[test]
+fn test_payment_hashes_bolt3_preimage() {
+    // BOLT 3 Appendix C, HTLC #0: preimage 00...00
+    let (payment_hash, payment_hash160) = payment_hashes(&[0x00; 32]);
+
+    assert_eq!(
+        hex::encode(payment_hash.as_bytes()),
+        "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925"
+    );
+    assert_eq!(hex::encode(payment_hash160), "b8bcb07f6344b42ab04250c86a6e8b75d3fdbbc6");
+    // RIPEMD160(payment_hash) is the same as HASH160 of the preimage
+    assert_eq!(payment_hash160.to_vec(), hash_bytes(&[0x00; 32], HashAlgo::Hash160));
+}
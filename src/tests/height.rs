@@ -0,0 +1,37 @@
+use crate::internal::height::{
+    blocks_until_csv_mature, blocks_until_cltv_expiry, HeightProvider, MockHeightProvider,
+};
+
+#[test]
+fn test_mock_height_provider_advances() {
+    let chain = MockHeightProvider::new(100);
+    chain.advance(44);
+    assert_eq!(chain.current_height(), 144);
+
+    chain.set_height(10);
+    assert_eq!(chain.current_height(), 10);
+}
+
+#[test]
+fn test_htlc_timeout_waits_for_cltv_expiry() {
+    let chain = MockHeightProvider::new(150);
+    assert_eq!(blocks_until_cltv_expiry(&chain, 200), 50);
+
+    // nLockTime 200 is final once the tip is at 200: the next block is 201
+    chain.advance(50);
+    assert_eq!(blocks_until_cltv_expiry(&chain, 200), 0);
+    chain.advance(1_000);
+    assert_eq!(blocks_until_cltv_expiry(&chain, 200), 0);
+}
+
+#[test]
+fn test_to_local_sweep_waits_for_csv() {
+    // Confirmed in block 500 with to_self_delay 144: sweepable in block 644
+    let chain = MockHeightProvider::new(500);
+    assert_eq!(blocks_until_csv_mature(&chain, 500, 144), 143);
+
+    chain.advance(142);
+    assert_eq!(blocks_until_csv_mature(&chain, 500, 144), 1);
+    chain.advance(1);
+    assert_eq!(blocks_until_csv_mature(&chain, 500, 144), 0);
+}
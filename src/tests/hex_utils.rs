@@ -0,0 +1,66 @@
+use crate::internal::hex_utils::{
+    parse_block_hash, parse_hex, parse_preimage, parse_pubkey, parse_txid, ParseError,
+};
+use bitcoin::hashes::Hash;
+
+const FUNDING_TXID: &str = "8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be";
+const PUBKEY: &str = "023da092f6980e58d2c037173180e9a465476026ee50f96695963e8efe436f54eb";
+
+#[test]
+fn test_parse_txid_keeps_display_order() {
+    let txid = parse_txid(FUNDING_TXID).unwrap();
+
+    assert_eq!(txid.to_string(), FUNDING_TXID);
+    // Internal byte order is the reverse of what bitcoind prints
+    assert_eq!(txid.to_byte_array()[0], 0xbe);
+}
+
+#[test]
+fn test_parse_txid_reports_bad_input() {
+    let mut bad = FUNDING_TXID.to_string();
+    bad.replace_range(10..11, "x");
+
+    assert_eq!(parse_txid(&bad), Err(ParseError::InvalidHexCharacter { c: 'x', index: 10 }));
+    assert_eq!(
+        parse_txid(&FUNDING_TXID[..62]),
+        Err(ParseError::WrongLength { expected: 32, actual: 31 })
+    );
+    assert_eq!(parse_txid(&FUNDING_TXID[..63]), Err(ParseError::OddLength(63)));
+}
+
+#[test]
+fn test_parse_block_hash_keeps_display_order() {
+    // Regtest genesis block
+    let hash = "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206";
+    let block_hash = parse_block_hash(hash).unwrap();
+
+    assert_eq!(block_hash.to_string(), hash);
+    assert_eq!(block_hash.to_byte_array()[0], 0x06);
+    assert_eq!(
+        parse_block_hash(&hash[..62]),
+        Err(ParseError::WrongLength { expected: 32, actual: 31 })
+    );
+}
+
+#[test]
+fn test_parse_pubkey() {
+    assert_eq!(parse_pubkey(PUBKEY).unwrap().to_string(), PUBKEY);
+
+    // Right length, but 0x05 is not a valid compressed key prefix
+    let bad_prefix = format!("05{}", &PUBKEY[2..]);
+    assert_eq!(parse_pubkey(&bad_prefix), Err(ParseError::InvalidPubkey));
+    assert_eq!(
+        parse_pubkey(&PUBKEY[..64]),
+        Err(ParseError::WrongLength { expected: 33, actual: 32 })
+    );
+}
+
+#[test]
+fn test_parse_preimage() {
+    assert_eq!(parse_preimage(&"42".repeat(32)), Ok([0x42; 32]));
+    assert_eq!(
+        parse_preimage(&"42".repeat(33)),
+        Err(ParseError::WrongLength { expected: 32, actual: 33 })
+    );
+    assert_eq!(parse_hex(""), Ok(vec![]));
+}
@@ -0,0 +1,96 @@
+use crate::types::{CommitmentKeys, HTLCOutput};
+use crate::*;
+use crate::tests::fixtures::test_pubkey;
+use bitcoin::hashes::sha256::Hash as Sha256;
+use bitcoin::hashes::Hash;
+use bitcoin::{OutPoint, Transaction, Txid};
+use std::str::FromStr;
+
+// Each HTLC gets a distinct payment hash so every output script is unique
+fn build_htlcs(count: usize, first_id: u32) -> Vec<HTLCOutput> {
+    (0..count as u32)
+        .map(|i| HTLCOutput {
+            amount_sat: 10_000,
+            payment_hash: PaymentHash::from_hash_bytes(Sha256::hash(&(first_id + i).to_be_bytes()).to_byte_array()),
+            cltv_expiry: 500 + i,
+        })
+        .collect()
+}
+
+fn build_commitment(offered_htlcs: &[HTLCOutput], received_htlcs: &[HTLCOutput]) -> Transaction {
+    let commitment_keys = CommitmentKeys::from_keys(
+        test_pubkey(0x01),
+        test_pubkey(0x02),
+        test_pubkey(0x03),
+        test_pubkey(0x04),
+        test_pubkey(0x05),
+    );
+
+    let funding_outpoint = OutPoint {
+        txid: Txid::from_str("8984484a580b825b9972d7adb15050b3ab624ccd731946b3eeddb92f4e7ef6be")
+            .unwrap(),
+        vout: 0,
+    };
+
+    create_commitment_transaction(
+        funding_outpoint,
+        10_000_000,
+        10_000_000,
+        &commitment_keys,
+        &test_pubkey(0x06),
+        &test_pubkey(0x07),
+        42,
+        144,
+        546,
+        0,
+        offered_htlcs,
+        received_htlcs,
+    )
+}
+
+#[test]
+fn test_commitment_at_max_htlcs_per_direction() {
+    let offered_htlcs = build_htlcs(MAX_ACCEPTED_HTLCS, 0);
+    let received_htlcs = build_htlcs(MAX_ACCEPTED_HTLCS, MAX_ACCEPTED_HTLCS as u32);
+
+    let tx = build_commitment(&offered_htlcs, &received_htlcs);
+
+    // to_local + to_remote + one output per HTLC
+    assert_eq!(tx.output.len(), 2 + 2 * MAX_ACCEPTED_HTLCS);
+
+    // Outputs must still be in BOLT 3 order after sorting ~1000 entries
+    assert!(tx
+        .output
+        .windows(2)
+        .all(|pair| pair[0].value <= pair[1].value));
+}
+
+#[test]
+fn test_check_htlc_count_rejects_each_direction() {
+    let too_many = build_htlcs(MAX_ACCEPTED_HTLCS + 1, 0);
+    let at_limit = build_htlcs(MAX_ACCEPTED_HTLCS, 0);
+
+    assert_eq!(check_htlc_count(&at_limit, &at_limit), Ok(()));
+    assert_eq!(
+        check_htlc_count(&too_many, &[]),
+        Err(HtlcCountError::TooManyOffered { count: 484, max: 483 })
+    );
+    assert_eq!(
+        check_htlc_count(&[], &too_many),
+        Err(HtlcCountError::TooManyReceived { count: 484, max: 483 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "TooManyOffered")]
+fn test_commitment_rejects_too_many_offered_htlcs() {
+    let offered_htlcs = build_htlcs(MAX_ACCEPTED_HTLCS + 1, 0);
+    build_commitment(&offered_htlcs, &[]);
+}
+
+#[test]
+#[should_panic(expected = "TooManyReceived")]
+fn test_commitment_rejects_too_many_received_htlcs() {
+    let received_htlcs = build_htlcs(MAX_ACCEPTED_HTLCS + 1, 0);
+    build_commitment(&[], &received_htlcs);
+}
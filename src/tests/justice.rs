@@ -0,0 +1,264 @@
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::transactions::claim::ClaimError;
+use crate::transactions::justice::{
+    create_htlc_justice_transaction, create_justice_transaction,
+    create_second_stage_justice_transaction, RevokedHtlcOutput,
+};
+use crate::transactions::weights::{
+    JUSTICE_TO_LOCAL_WEIGHT, OFFERED_HTLC_PENALTY_INPUT_WEIGHT, RECEIVED_HTLC_PENALTY_INPUT_WEIGHT,
+    SWEEP_TX_BASE_WEIGHT,
+};
+use crate::types::{ChannelKeyManager, CommitmentKeys};
+use crate::*;
+use bitcoin::hashes::Hash;
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::ecdsa::Signature;
+use bitcoin::secp256k1::Message;
+use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+use bitcoin::{Amount, OutPoint, Transaction, Txid};
+
+const TO_LOCAL_VALUE: u64 = 3_000_000;
+const REVOKED_COMMITMENT: u64 = 7;
+const FEERATE_PER_KW: u64 = 2_000;
+
+fn local_keys() -> ChannelKeyManager {
+    new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0)
+}
+
+// The to_local script of the peer's commitment `commitment_number`, which
+// our revocation basepoint lets us punish
+fn revoked_to_local_script(keys: &ChannelKeyManager, commitment_number: u64) -> (ScriptBuf, PublicKey) {
+    let peer = SimulatedPeer::new();
+    let per_commitment_point = peer.per_commitment_point(commitment_number);
+    let revocation_key = derive_revocation_public_key(
+        &keys.to_public_keys().revocation_basepoint,
+        &per_commitment_point,
+        &keys.secp_ctx,
+    );
+    let delayed_key = derive_public_key(
+        &peer.public_keys().delayed_payment_basepoint,
+        &per_commitment_point,
+        &keys.secp_ctx,
+    );
+    (create_to_local_script(&revocation_key, &delayed_key, 144), revocation_key)
+}
+
+fn justice_tx(keys: &ChannelKeyManager, revealed_commitment: u64) -> Transaction {
+    let (script, _) = revoked_to_local_script(keys, REVOKED_COMMITMENT);
+    let secret = SecretKey::from_slice(&SimulatedPeer::new().revoke(revealed_commitment)).unwrap();
+    create_justice_transaction(
+        OutPoint::new(Txid::all_zeros(), 1),
+        TO_LOCAL_VALUE,
+        &script,
+        &secret,
+        keys,
+        &create_to_remote_script(&keys.to_public_keys().payment_basepoint),
+        FEERATE_PER_KW,
+    )
+    .unwrap()
+}
+
+fn signed_by(tx: &Transaction, script: &ScriptBuf, pubkey: &PublicKey) -> bool {
+    input_signed_by(tx, 0, script, TO_LOCAL_VALUE, pubkey)
+}
+
+fn input_signed_by(
+    tx: &Transaction,
+    input_index: usize,
+    script: &ScriptBuf,
+    amount: u64,
+    pubkey: &PublicKey,
+) -> bool {
+    let sighash = SighashCache::new(tx)
+        .p2wsh_signature_hash(input_index, script, Amount::from_sat(amount), EcdsaSighashType::All)
+        .unwrap();
+    let msg = Message::from_digest(sighash.to_byte_array());
+    let signature = tx.input[input_index].witness.nth(0).unwrap();
+    let signature = Signature::from_der(&signature[..signature.len() - 1]).unwrap();
+    Secp256k1::new().verify_ecdsa(&msg, &signature, pubkey).is_ok()
+}
+
+#[test]
+fn test_justice_tx_spends_revocation_branch() {
+    let keys = local_keys();
+    let (script, revocation_key) = revoked_to_local_script(&keys, REVOKED_COMMITMENT);
+    let tx = justice_tx(&keys, REVOKED_COMMITMENT);
+
+    let witness = &tx.input[0].witness;
+    assert_eq!(witness.len(), 3);
+    assert_eq!(witness.nth(1), Some(&[0x01][..]));
+    assert_eq!(witness.nth(2), Some(script.as_bytes()));
+    assert!(signed_by(&tx, &script, &revocation_key));
+
+    // No CSV wait on the revocation branch
+    assert_eq!(tx.input[0].sequence, bitcoin::Sequence::MAX);
+}
+
+#[test]
+fn test_justice_tx_pays_fee_for_its_weight() {
+    let tx = justice_tx(&local_keys(), REVOKED_COMMITMENT);
+    let fee = FEERATE_PER_KW * JUSTICE_TO_LOCAL_WEIGHT / 1000;
+
+    assert_eq!(tx.output[0].value.to_sat(), TO_LOCAL_VALUE - fee);
+    // The expected weight assumes a 73-byte signature, so it is an upper bound
+    assert!(tx.weight().to_wu() <= JUSTICE_TO_LOCAL_WEIGHT);
+}
+
+#[test]
+fn test_justice_tx_needs_the_matching_secret() {
+    let keys = local_keys();
+    let (script, revocation_key) = revoked_to_local_script(&keys, REVOKED_COMMITMENT);
+
+    let tx = justice_tx(&keys, REVOKED_COMMITMENT + 1);
+    assert!(!signed_by(&tx, &script, &revocation_key));
+}
+
+#[test]
+fn test_justice_tx_refuses_output_below_fee() {
+    let keys = local_keys();
+    let (script, _) = revoked_to_local_script(&keys, REVOKED_COMMITMENT);
+    let secret = SecretKey::from_slice(&SimulatedPeer::new().revoke(REVOKED_COMMITMENT)).unwrap();
+    let destination = create_to_remote_script(&keys.to_public_keys().payment_basepoint);
+    let fee = FEERATE_PER_KW * JUSTICE_TO_LOCAL_WEIGHT / 1000;
+
+    let result = create_justice_transaction(
+        OutPoint::new(Txid::all_zeros(), 1),
+        fee,
+        &script,
+        &secret,
+        &keys,
+        &destination,
+        FEERATE_PER_KW,
+    );
+    assert_eq!(
+        result,
+        Err(ClaimError::InsufficientFunds {
+            input_sat: fee,
+            required_sat: fee + destination.minimal_non_dust().to_sat(),
+        })
+    );
+}
+
+// One offered and one received HTLC on the peer's revoked commitment
+fn revoked_htlcs(keys: &ChannelKeyManager) -> (Vec<RevokedHtlcOutput>, PublicKey) {
+    let peer = SimulatedPeer::new();
+    let per_commitment_point = peer.per_commitment_point(REVOKED_COMMITMENT);
+    let revocation_key = derive_revocation_public_key(
+        &keys.to_public_keys().revocation_basepoint,
+        &per_commitment_point,
+        &keys.secp_ctx,
+    );
+    let peer_htlc_key =
+        derive_public_key(&peer.public_keys().htlc_basepoint, &per_commitment_point, &keys.secp_ctx);
+    let our_htlc_key =
+        derive_public_key(&keys.to_public_keys().htlc_basepoint, &per_commitment_point, &keys.secp_ctx);
+
+    let offered = RevokedHtlcOutput {
+        outpoint: OutPoint::new(Txid::all_zeros(), 2),
+        amount_sat: 20_000,
+        htlc_script: create_offered_htlc_script(&revocation_key, &peer_htlc_key, &our_htlc_key, &PaymentHash::from_hash_bytes([0x01; 32])),
+        offered: true,
+    };
+    let received = RevokedHtlcOutput {
+        outpoint: OutPoint::new(Txid::all_zeros(), 3),
+        amount_sat: 30_000,
+        htlc_script: create_received_htlc_script(
+            &revocation_key,
+            &peer_htlc_key,
+            &our_htlc_key,
+            &PaymentHash::from_hash_bytes([0x02; 32]),
+            500,
+        ),
+        offered: false,
+    };
+    (vec![offered, received], revocation_key)
+}
+
+#[test]
+fn test_htlc_justice_tx_sweeps_every_htlc() {
+    let keys = local_keys();
+    let (htlcs, revocation_key) = revoked_htlcs(&keys);
+    let secret = SecretKey::from_slice(&SimulatedPeer::new().revoke(REVOKED_COMMITMENT)).unwrap();
+    let tx = create_htlc_justice_transaction(
+        &htlcs,
+        &secret,
+        &keys,
+        &create_to_remote_script(&keys.to_public_keys().payment_basepoint),
+        FEERATE_PER_KW,
+    )
+    .unwrap();
+
+    assert_eq!(tx.input.len(), 2);
+    for (i, htlc) in htlcs.iter().enumerate() {
+        let witness = &tx.input[i].witness;
+        assert_eq!(tx.input[i].previous_output, htlc.outpoint);
+        assert_eq!(witness.len(), 3);
+        assert_eq!(witness.nth(1), Some(&revocation_key.serialize()[..]));
+        assert_eq!(witness.nth(2), Some(htlc.htlc_script.as_bytes()));
+        assert!(input_signed_by(&tx, i, &htlc.htlc_script, htlc.amount_sat, &revocation_key));
+    }
+
+    let weight =
+        SWEEP_TX_BASE_WEIGHT + OFFERED_HTLC_PENALTY_INPUT_WEIGHT + RECEIVED_HTLC_PENALTY_INPUT_WEIGHT;
+    assert_eq!(tx.output.len(), 1);
+    assert_eq!(tx.output[0].value.to_sat(), 50_000 - FEERATE_PER_KW * weight / 1000);
+    assert!(tx.weight().to_wu() <= weight);
+}
+
+#[test]
+fn test_second_stage_justice_tx_spends_revoked_htlc_tx_output() {
+    let keys = local_keys();
+    let peer = SimulatedPeer::new();
+    let peer_keys = peer.public_keys();
+
+    // The peer's revoked commitment, and an HTLC-success they built on it
+    let commitment_keys = CommitmentKeys::from_basepoints(
+        &peer.per_commitment_point(REVOKED_COMMITMENT),
+        &peer_keys.delayed_payment_basepoint,
+        &peer_keys.htlc_basepoint,
+        &keys.to_public_keys().revocation_basepoint,
+        &keys.to_public_keys().htlc_basepoint,
+        &keys.secp_ctx,
+    );
+    let htlc_tx = create_htlc_success_transaction(
+        OutPoint::new(Txid::all_zeros(), 2),
+        50_000,
+        &commitment_keys,
+        144,
+        FEERATE_PER_KW,
+    );
+
+    let secret = SecretKey::from_slice(&peer.revoke(REVOKED_COMMITMENT)).unwrap();
+    let tx = create_second_stage_justice_transaction(
+        &htlc_tx,
+        &commitment_keys,
+        144,
+        &secret,
+        &keys,
+        &create_to_remote_script(&keys.to_public_keys().payment_basepoint),
+        FEERATE_PER_KW,
+    )
+    .unwrap();
+
+    assert_eq!(tx.input[0].previous_output, OutPoint::new(htlc_tx.compute_txid(), 0));
+    assert_eq!(tx.input[0].witness.nth(1), Some(&[0x01][..]));
+    let script = ScriptBuf::from_bytes(tx.input[0].witness.nth(2).unwrap().to_vec());
+    assert_eq!(script.to_p2wsh(), htlc_tx.output[0].script_pubkey);
+    let htlc_value = htlc_tx.output[0].value.to_sat();
+    assert!(input_signed_by(&tx, 0, &script, htlc_value, &commitment_keys.revocation_key));
+}
+
+#[test]
+fn test_htlc_justice_tx_needs_htlcs_and_funds() {
+    let keys = local_keys();
+    let secret = SecretKey::from_slice(&SimulatedPeer::new().revoke(REVOKED_COMMITMENT)).unwrap();
+    let destination = create_to_remote_script(&keys.to_public_keys().payment_basepoint);
+
+    let result = create_htlc_justice_transaction(&[], &secret, &keys, &destination, FEERATE_PER_KW);
+    assert_eq!(result, Err(ClaimError::NoOutputs));
+
+    // At this feerate the fee is more than both HTLCs are worth
+    let (htlcs, _) = revoked_htlcs(&keys);
+    let result = create_htlc_justice_transaction(&htlcs, &secret, &keys, &destination, 100_000);
+    assert!(matches!(result, Err(ClaimError::InsufficientFunds { input_sat: 50_000, .. })));
+}
@@ -0,0 +1,75 @@
+use crate::internal::legacy_demo::*;
+use crate::transactions::sanity::{check_spends_parent_txid, OutpointError};
+use crate::tests::fixtures::{test_bitcoin_pubkey, test_secret};
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::ecdsa::Signature;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+
+const AMOUNT: u64 = 100_000;
+const FEE: u64 = 1_000;
+
+fn spend(outpoint: OutPoint, value: u64, script_pubkey: ScriptBuf) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut { value: Amount::from_sat(value), script_pubkey }],
+    }
+}
+
+// Sign the 2-of-2 P2SH input of `tx` with both channel keys
+fn sign_both(tx: &Transaction, redeem_script: &ScriptBuf) -> (Transaction, [Vec<u8>; 2]) {
+    // create_funding_script sorts the keys, so sign in the same order
+    let (first, second) =
+        if test_bitcoin_pubkey(1).inner.serialize() < test_bitcoin_pubkey(2).inner.serialize() {
+            (1, 2)
+        } else {
+            (2, 1)
+        };
+    let signatures = [
+        sign_p2sh_input(tx, 0, redeem_script, &test_secret(first)),
+        sign_p2sh_input(tx, 0, redeem_script, &test_secret(second)),
+    ];
+    let mut signed = tx.clone();
+    signed.input[0].script_sig = p2sh_multisig_script_sig([&signatures[0], &signatures[1]], redeem_script);
+    (signed, signatures)
+}
+
+#[test]
+fn test_malleated_signature_is_the_same_signature() {
+    let (redeem_script, _) = create_p2sh_funding_output(&test_bitcoin_pubkey(1), &test_bitcoin_pubkey(2));
+    let tx = spend(OutPoint::null(), AMOUNT, ScriptBuf::new());
+    let signature = sign_p2sh_input(&tx, 0, &redeem_script, &test_secret(1));
+
+    let malleated = malleate_signature(&signature);
+    assert_ne!(malleated, signature);
+    assert_eq!(malleated.last(), signature.last());
+
+    // Normalizing s undoes the malleation
+    let mut normalized = Signature::from_der(&malleated[..malleated.len() - 1]).unwrap();
+    normalized.normalize_s();
+    assert_eq!(normalized, Signature::from_der(&signature[..signature.len() - 1]).unwrap());
+}
+
+#[test]
+fn test_malleation_orphans_presigned_child() {
+    let (redeem_script, script_pubkey) = create_p2sh_funding_output(&test_bitcoin_pubkey(1), &test_bitcoin_pubkey(2));
+    let commitment_tx = spend(OutPoint::new(Txid::all_zeros(), 0), AMOUNT - FEE, script_pubkey.clone());
+    let (commitment_tx, signatures) = sign_both(&commitment_tx, &redeem_script);
+
+    // A child pre-signed against the commitment as we signed it
+    let child = spend(OutPoint::new(commitment_tx.compute_txid(), 0), AMOUNT - 2 * FEE, script_pubkey);
+    assert_eq!(check_spends_parent_txid(&child, &commitment_tx), Ok(0));
+
+    let malleated = malleate_p2sh_multisig_tx(&commitment_tx, [&signatures[0], &signatures[1]], &redeem_script);
+    assert_ne!(malleated.compute_txid(), commitment_tx.compute_txid());
+    assert_eq!(check_spends_parent_txid(&child, &malleated), Err(OutpointError::DoesNotSpendParent));
+}
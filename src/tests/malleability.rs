@@ -0,0 +1,64 @@
+use crate::transactions::funding::create_funding_transaction;
+use crate::transactions::sanity::{check_spends_parent_txid, OutpointError};
+use crate::tests::fixtures::test_bitcoin_pubkey;
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+
+// A funding transaction whose (wallet) input has been "signed"
+fn signed_funding_tx(signature: &[u8]) -> Transaction {
+    let mut tx = create_funding_transaction(Txid::all_zeros(), 0, 5_000_000, &test_bitcoin_pubkey(1), &test_bitcoin_pubkey(2));
+    tx.input[0].witness = Witness::from_slice(&[signature, &test_bitcoin_pubkey(3).to_bytes()[..]]);
+    tx
+}
+
+fn spend(outpoint: OutPoint) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut { value: Amount::from_sat(4_990_000), script_pubkey: ScriptBuf::new() }],
+    }
+}
+
+#[test]
+fn test_witness_malleation_keeps_child_outpoint_valid() {
+    let funding_tx = signed_funding_tx(&[0x30; 71]);
+    // Pre-signed before the funding transaction is broadcast
+    let commitment_tx = spend(OutPoint::new(funding_tx.compute_txid(), 0));
+
+    // Someone swaps the witness for a different (equally valid) one
+    let malleated = signed_funding_tx(&[0x30; 72]);
+    assert_ne!(malleated.compute_wtxid(), funding_tx.compute_wtxid());
+    assert_eq!(malleated.compute_txid(), funding_tx.compute_txid());
+
+    assert_eq!(check_spends_parent_txid(&commitment_tx, &funding_tx), Ok(0));
+    assert_eq!(check_spends_parent_txid(&commitment_tx, &malleated), Ok(0));
+}
+
+#[test]
+fn test_child_referencing_wtxid_rejected() {
+    let funding_tx = signed_funding_tx(&[0x30; 71]);
+    let wtxid_as_txid = Txid::from_byte_array(funding_tx.compute_wtxid().to_byte_array());
+    let child = spend(OutPoint::new(wtxid_as_txid, 0));
+
+    assert_eq!(
+        check_spends_parent_txid(&child, &funding_tx),
+        Err(OutpointError::ReferencesWtxid { input_index: 0 })
+    );
+}
+
+#[test]
+fn test_unrelated_child_rejected() {
+    let funding_tx = signed_funding_tx(&[0x30; 71]);
+    let child = spend(OutPoint::new(Txid::all_zeros(), 0));
+
+    assert_eq!(check_spends_parent_txid(&child, &funding_tx), Err(OutpointError::DoesNotSpendParent));
+}
@@ -1,3 +1,51 @@
+mod anchors;
+mod channel_open;
+mod closing;
+mod cltv_encoding;
+mod commitment_funder;
+mod commitment_number;
+mod commitment_parser;
+mod cpfp;
+mod derivation_scheme;
+mod dust;
 mod exercises;
+mod explorer;
+mod filter_scan;
+mod fixtures;
+mod force_close_cost;
+mod funding_change;
+mod funding_limits;
+mod funding_psbt;
+mod funding_rbf;
+mod gen_vectors;
+mod hash;
+mod height;
+mod hex_utils;
+mod htlc_limits;
+mod justice;
+mod legacy_demo;
+mod malleability;
+mod msat_rounding;
+mod payment_hash;
+mod regtest;
+mod rejection_hint;
+mod relative_locktime;
+mod remote_commitment;
+mod sanity;
+mod script_index;
+mod script_utils;
+// derive_commitment_secret is the Exercise 10 algorithm, so it only exists
+// in the solutions tree
+#[cfg(feature = "use-solutions")]
+mod shachain;
+mod shutdown;
+mod simulated_peer;
+mod spv;
+mod sweep;
+mod to_self_delay;
+mod tx_info;
+mod txid_endianness;
+mod update_fee;
 mod vectors_bolt3;
-pub mod workflows;
\ No newline at end of file
+mod weights;
+pub mod workflows;
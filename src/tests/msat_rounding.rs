@@ -0,0 +1,69 @@
+use crate::transactions::fees::{calculate_commitment_tx_fee, commitment_fee_breakdown, CommitmentFeeBreakdown};
+
+fn total_msat(breakdown: &CommitmentFeeBreakdown) -> u64 {
+    (breakdown.to_local_sat + breakdown.to_remote_sat + breakdown.htlcs_sat) * 1000
+        + breakdown.total_fees_msat()
+}
+
+#[test]
+fn test_whole_satoshi_balances_round_nothing() {
+    // BOLT 3 Appendix C, "simple commitment tx with no HTLCs"
+    let breakdown = commitment_fee_breakdown(7_000_000_000, 3_000_000_000, &[], 546, 15_000, true);
+
+    assert_eq!(
+        breakdown,
+        CommitmentFeeBreakdown {
+            to_local_sat: 7_000_000 - 10_860,
+            to_remote_sat: 3_000_000,
+            htlcs_sat: 0,
+            commitment_fee_sat: 10_860,
+            trimmed_sat: 0,
+            rounded_to_fees_msat: 0,
+        }
+    );
+}
+
+#[test]
+fn test_sub_satoshi_remainders_go_to_fees() {
+    let htlcs = [1_000_999, 2_000_500];
+    let breakdown = commitment_fee_breakdown(6_988_000_999, 3_000_000_001, &htlcs, 546, 647, true);
+
+    assert_eq!(breakdown.htlcs_sat, 1_000 + 2_000);
+    assert_eq!(breakdown.rounded_to_fees_msat, 999 + 1 + 999 + 500);
+    assert_eq!(breakdown.commitment_fee_sat, calculate_commitment_tx_fee(647, 2));
+    assert_eq!(
+        total_msat(&breakdown),
+        6_988_000_999 + 3_000_000_001 + htlcs.iter().sum::<u64>()
+    );
+}
+
+#[test]
+fn test_fee_comes_from_remote_funder() {
+    let breakdown = commitment_fee_breakdown(3_000_000_500, 7_000_000_500, &[], 546, 15_000, false);
+
+    assert_eq!(breakdown.to_local_sat, 3_000_000);
+    assert_eq!(breakdown.to_remote_sat, 7_000_000 - 10_860);
+    assert_eq!(total_msat(&breakdown), 10_000_001_000);
+}
+
+#[test]
+fn test_funder_cannot_pay_more_than_its_balance() {
+    let breakdown = commitment_fee_breakdown(5_000_999, 3_000_000_000, &[], 546, 15_000, true);
+
+    assert_eq!(breakdown.to_local_sat, 0);
+    assert_eq!(breakdown.commitment_fee_sat, 5_000);
+    assert_eq!(total_msat(&breakdown), 3_005_000_999);
+}
+
+#[test]
+fn test_trimmed_to_remote_goes_to_fees() {
+    // 500 sats to the fundee is below a 546 sat dust limit
+    let breakdown = commitment_fee_breakdown(7_000_000_000, 500_999, &[], 546, 15_000, true);
+
+    assert_eq!(breakdown.to_local_sat, 7_000_000 - 10_860);
+    assert_eq!(breakdown.to_remote_sat, 0);
+    assert_eq!(breakdown.trimmed_sat, 500);
+    assert_eq!(breakdown.rounded_to_fees_msat, 999);
+    assert_eq!(breakdown.total_fees_msat(), 10_860_000 + 500_999);
+    assert_eq!(total_msat(&breakdown), 7_000_500_999);
+}
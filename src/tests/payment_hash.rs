@@ -0,0 +1,39 @@
+use crate::types::{payment_hash160, PaymentHash};
+use crate::*;
+use crate::tests::fixtures::test_pubkey;
+
+#[test]
+fn test_payment_hash_from_preimage() {
+    // BOLT 3 Appendix C, HTLC #0: preimage 00...00
+    let payment_hash = PaymentHash::from_preimage(&[0x00; 32]);
+
+    assert_eq!(
+        hex::encode(payment_hash.as_bytes()),
+        "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925"
+    );
+    assert_eq!(
+        hex::encode(payment_hash160(&payment_hash)),
+        "b8bcb07f6344b42ab04250c86a6e8b75d3fdbbc6"
+    );
+}
+
+#[test]
+fn test_htlc_script_commits_to_hash_not_preimage() {
+    let preimage = [0x00; 32];
+    let payment_hash = PaymentHash::from_preimage(&preimage);
+
+    let script = create_received_htlc_script_for_channel_type(
+        &test_pubkey(1),
+        &test_pubkey(2),
+        &test_pubkey(3),
+        &payment_hash,
+        500,
+        ChannelType::StaticRemoteKey,
+    );
+
+    let script_hex = hex::encode(script.as_bytes());
+    assert!(script_hex.contains(&hex::encode(payment_hash160(&payment_hash))));
+    // The mistake the type guards against: the preimage used as the hash
+    let preimage_as_hash = PaymentHash::from_hash_bytes(preimage);
+    assert!(!script_hex.contains(&hex::encode(payment_hash160(&preimage_as_hash))));
+}
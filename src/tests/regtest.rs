@@ -0,0 +1,835 @@
+// Integration tests that fund each channel script on regtest and spend it
+// through every branch, asserting bitcoind accepts the spending transaction.
+// The negative tests check that invalid spends are refused by
+// `testmempoolaccept` before anything is broadcast.
+//
+// These need the workshop's bitcoind (see start.sh) to be running, so they
+// are ignored by default:
+//
+//   cargo test --features use-solutions -- --ignored
+//
+use crate::internal::bitcoind_client::{get_bitcoind_client, BitcoindClient};
+use crate::internal::legacy_demo::*;
+use crate::types::ChannelKeyManager;
+use crate::*;
+use bitcoin::hashes::sha256::Hash as Sha256;
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use bitcoin::transaction::Version;
+use bitcoin::{Address, Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
+use serial_test::serial;
+
+const OUTPUT_AMOUNT: u64 = 100_000;
+const SPEND_FEE: u64 = 1_000;
+const TO_SELF_DELAY: u16 = 10;
+
+/// Fixed keys for every role in the scripts under test
+pub struct ScriptKeys {
+    pub signer: ChannelKeyManager,
+    pub revocation: SecretKey,
+    pub local_htlc: SecretKey,
+    pub remote_htlc: SecretKey,
+    pub local_delayed: SecretKey,
+    pub preimage: [u8; 32],
+}
+
+impl ScriptKeys {
+    pub fn new() -> Self {
+        ScriptKeys {
+            signer: new_keys_manager([0x01; 32], Network::Regtest).derive_channel_keys(0),
+            revocation: SecretKey::from_slice(&[0x11; 32]).unwrap(),
+            local_htlc: SecretKey::from_slice(&[0x12; 32]).unwrap(),
+            remote_htlc: SecretKey::from_slice(&[0x13; 32]).unwrap(),
+            local_delayed: SecretKey::from_slice(&[0x14; 32]).unwrap(),
+            preimage: [0x42; 32],
+        }
+    }
+
+    pub fn pubkey(&self, secret: &SecretKey) -> PublicKey {
+        PublicKey::from_secret_key(&Secp256k1::new(), secret)
+    }
+
+    pub fn payment_hash(&self) -> PaymentHash {
+        PaymentHash::from_preimage(&self.preimage)
+    }
+
+    pub fn offered_htlc_script(&self) -> ScriptBuf {
+        create_offered_htlc_script(
+            &self.pubkey(&self.revocation),
+            &self.pubkey(&self.local_htlc),
+            &self.pubkey(&self.remote_htlc),
+            &self.payment_hash(),
+        )
+    }
+
+    pub fn received_htlc_script(&self, cltv_expiry: u32) -> ScriptBuf {
+        create_received_htlc_script(
+            &self.pubkey(&self.revocation),
+            &self.pubkey(&self.local_htlc),
+            &self.pubkey(&self.remote_htlc),
+            &self.payment_hash(),
+            cltv_expiry,
+        )
+    }
+
+    pub fn to_local_script(&self) -> ScriptBuf {
+        create_to_local_script(
+            &self.pubkey(&self.revocation),
+            &self.pubkey(&self.local_delayed),
+            TO_SELF_DELAY,
+        )
+    }
+
+    pub fn sign(&self, tx: &Transaction, script: &ScriptBuf, secret: &SecretKey) -> Vec<u8> {
+        self.signer
+            .sign_transaction_input_sighash_all(tx, 0, script, OUTPUT_AMOUNT, secret)
+    }
+}
+
+/// Pay OUTPUT_AMOUNT from the wallet to the P2WSH of `witness_script` and confirm it
+pub fn fund_witness_script(bitcoind: &BitcoindClient, witness_script: &ScriptBuf) -> OutPoint {
+    let script_pubkey = witness_script.to_p2wsh();
+    let address = Address::from_script(&script_pubkey, Network::Regtest).unwrap();
+    let txid = bitcoind.send_to_address(&address, OUTPUT_AMOUNT);
+
+    let funding_tx = bitcoind.get_raw_transaction(&txid).unwrap();
+    let vout = funding_tx
+        .output
+        .iter()
+        .position(|out| out.script_pubkey == script_pubkey)
+        .expect("Funding output not found");
+
+    mine_blocks(bitcoind, 1);
+    OutPoint::new(txid, vout as u32)
+}
+
+pub fn mine_blocks(bitcoind: &BitcoindClient, num_blocks: u64) {
+    let address = bitcoind.get_new_address().unwrap();
+    bitcoind.generate_to_address(num_blocks, &address).unwrap();
+}
+
+/// Unsigned one-input, one-output transaction paying back to the wallet
+pub fn build_spend(
+    bitcoind: &BitcoindClient,
+    outpoint: OutPoint,
+    lock_time: LockTime,
+    sequence: Sequence,
+) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time,
+        input: vec![TxIn {
+            previous_output: outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(OUTPUT_AMOUNT - SPEND_FEE),
+            script_pubkey: bitcoind.get_new_address().unwrap().script_pubkey(),
+        }],
+    }
+}
+
+fn with_witness(mut tx: Transaction, items: &[&[u8]], script: &ScriptBuf) -> Transaction {
+    let mut witness = Witness::new();
+    for item in items {
+        witness.push(item);
+    }
+    witness.push(script.as_bytes());
+    tx.input[0].witness = witness;
+    tx
+}
+
+fn assert_broadcast(bitcoind: &BitcoindClient, tx: &Transaction) {
+    if let Err(e) = bitcoind.send_raw_transaction(tx) {
+        panic!("bitcoind rejected spend {}: {}", tx.compute_txid(), e);
+    }
+    mine_blocks(bitcoind, 1);
+}
+
+/// Run `testmempoolaccept` on a spend expected to be invalid and return the reason
+fn assert_rejected(bitcoind: &BitcoindClient, tx: &Transaction) -> String {
+    let result = bitcoind
+        .test_mempool_accept(std::slice::from_ref(tx))
+        .unwrap()
+        .remove(0);
+    assert!(!result.allowed, "bitcoind accepted invalid spend {}", result.txid);
+    result.reject_reason.unwrap_or_default()
+}
+
+// OFFERED HTLC
+// ============================================================================
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_offered_htlc_revocation_claim() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.offered_htlc_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    let tx = build_spend(&bitcoind, outpoint, LockTime::ZERO, Sequence::MAX);
+    let sig = keys.sign(&tx, &script, &keys.revocation);
+    let revocation_pubkey = keys.pubkey(&keys.revocation).serialize();
+    let tx = with_witness(tx, &[&sig, &revocation_pubkey], &script);
+
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_offered_htlc_preimage_claim() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.offered_htlc_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    let tx = build_spend(&bitcoind, outpoint, LockTime::ZERO, Sequence::MAX);
+    let remote_sig = keys.sign(&tx, &script, &keys.remote_htlc);
+    let tx = with_witness(tx, &[&remote_sig, &keys.preimage], &script);
+
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_offered_htlc_timeout_claim() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.offered_htlc_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    // HTLC-timeout path: 2-of-2 with an empty element selecting OP_NOTIF
+    let cltv_expiry = bitcoind.get_block_count().unwrap() as u32;
+    let tx = build_spend(
+        &bitcoind,
+        outpoint,
+        LockTime::from_consensus(cltv_expiry),
+        Sequence::ZERO,
+    );
+    let remote_sig = keys.sign(&tx, &script, &keys.remote_htlc);
+    let local_sig = keys.sign(&tx, &script, &keys.local_htlc);
+    let tx = with_witness(tx, &[&[], &remote_sig, &local_sig, &[]], &script);
+
+    assert_broadcast(&bitcoind, &tx);
+}
+
+// RECEIVED HTLC
+// ============================================================================
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_received_htlc_revocation_claim() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.received_htlc_script(bitcoind.get_block_count().unwrap() as u32);
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    let tx = build_spend(&bitcoind, outpoint, LockTime::ZERO, Sequence::MAX);
+    let sig = keys.sign(&tx, &script, &keys.revocation);
+    let revocation_pubkey = keys.pubkey(&keys.revocation).serialize();
+    let tx = with_witness(tx, &[&sig, &revocation_pubkey], &script);
+
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_received_htlc_preimage_claim() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.received_htlc_script(bitcoind.get_block_count().unwrap() as u32 + 100);
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    // HTLC-success path: 2-of-2 plus the preimage
+    let tx = build_spend(&bitcoind, outpoint, LockTime::ZERO, Sequence::ZERO);
+    let remote_sig = keys.sign(&tx, &script, &keys.remote_htlc);
+    let local_sig = keys.sign(&tx, &script, &keys.local_htlc);
+    let tx = with_witness(tx, &[&[], &remote_sig, &local_sig, &keys.preimage], &script);
+
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_received_htlc_timeout_claim() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let cltv_expiry = bitcoind.get_block_count().unwrap() as u32;
+    let script = keys.received_htlc_script(cltv_expiry);
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    // Remote's timeout path: OP_CLTV needs a matching locktime and a non-final sequence
+    let tx = build_spend(
+        &bitcoind,
+        outpoint,
+        LockTime::from_consensus(cltv_expiry),
+        Sequence::ENABLE_LOCKTIME_NO_RBF,
+    );
+    let remote_sig = keys.sign(&tx, &script, &keys.remote_htlc);
+    let tx = with_witness(tx, &[&remote_sig, &[]], &script);
+
+    assert_broadcast(&bitcoind, &tx);
+}
+
+// TO_LOCAL
+// ============================================================================
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_to_local_revocation_claim() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.to_local_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    let tx = build_spend(&bitcoind, outpoint, LockTime::ZERO, Sequence::MAX);
+    let sig = keys.sign(&tx, &script, &keys.revocation);
+    let tx = with_witness(tx, &[&sig, &[0x01]], &script);
+
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_to_local_delayed_claim() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.to_local_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    // The funding block counts as the first confirmation
+    mine_blocks(&bitcoind, TO_SELF_DELAY as u64 - 1);
+
+    let tx = build_spend(
+        &bitcoind,
+        outpoint,
+        LockTime::ZERO,
+        relative_locktime_sequence(TO_SELF_DELAY as u32).unwrap(),
+    );
+    let sig = keys.sign(&tx, &script, &keys.local_delayed);
+    let tx = with_witness(tx, &[&sig, &[]], &script);
+
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_justice_transaction() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+
+    // A to_local whose revocation key comes from our revocation basepoint
+    let per_commitment_secret = SecretKey::from_slice(&[0x15; 32]).unwrap();
+    let revocation_key = derive_revocation_public_key(
+        &keys.signer.to_public_keys().revocation_basepoint,
+        &keys.pubkey(&per_commitment_secret),
+        &keys.signer.secp_ctx,
+    );
+    let script =
+        create_to_local_script(&revocation_key, &keys.pubkey(&keys.local_delayed), TO_SELF_DELAY);
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    let tx = crate::transactions::justice::create_justice_transaction(
+        outpoint,
+        OUTPUT_AMOUNT,
+        &script,
+        &per_commitment_secret,
+        &keys.signer,
+        &bitcoind.get_new_address().unwrap().script_pubkey(),
+        253,
+    )
+    .unwrap();
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_htlc_justice_transaction() {
+    use crate::transactions::justice::{create_htlc_justice_transaction, RevokedHtlcOutput};
+
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+
+    // HTLC outputs whose revocation key comes from our revocation basepoint
+    let per_commitment_secret = SecretKey::from_slice(&[0x15; 32]).unwrap();
+    let revocation_key = derive_revocation_public_key(
+        &keys.signer.to_public_keys().revocation_basepoint,
+        &keys.pubkey(&per_commitment_secret),
+        &keys.signer.secp_ctx,
+    );
+    let local_htlc = keys.pubkey(&keys.local_htlc);
+    let remote_htlc = keys.pubkey(&keys.remote_htlc);
+    let offered_script =
+        create_offered_htlc_script(&revocation_key, &local_htlc, &remote_htlc, &keys.payment_hash());
+    let received_script = create_received_htlc_script(
+        &revocation_key,
+        &local_htlc,
+        &remote_htlc,
+        &keys.payment_hash(),
+        bitcoind.get_block_count().unwrap() as u32 + 100,
+    );
+    let htlcs = [(offered_script, true), (received_script, false)].map(|(htlc_script, offered)| {
+        RevokedHtlcOutput {
+            outpoint: fund_witness_script(&bitcoind, &htlc_script),
+            amount_sat: OUTPUT_AMOUNT,
+            htlc_script,
+            offered,
+        }
+    });
+
+    let tx = create_htlc_justice_transaction(
+        &htlcs,
+        &per_commitment_secret,
+        &keys.signer,
+        &bitcoind.get_new_address().unwrap().script_pubkey(),
+        253,
+    )
+    .unwrap();
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_to_local_claim_transaction() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+
+    // A to_local whose delayed key comes from our delayed payment basepoint
+    let per_commitment_point = keys.signer.derive_per_commitment_point(0);
+    let delayed_key = derive_public_key(
+        &keys.signer.to_public_keys().delayed_payment_basepoint,
+        &per_commitment_point,
+        &keys.signer.secp_ctx,
+    );
+    let script = create_to_local_script(&keys.pubkey(&keys.revocation), &delayed_key, TO_SELF_DELAY);
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    let tx = crate::transactions::sweep::create_to_local_claim_transaction(
+        outpoint,
+        OUTPUT_AMOUNT,
+        &script,
+        &per_commitment_point,
+        TO_SELF_DELAY,
+        &keys.signer,
+        &bitcoind.get_new_address().unwrap().script_pubkey(),
+        253,
+    )
+    .unwrap();
+
+    // Valid, but not until the delay has passed
+    let reason = assert_rejected(&bitcoind, &tx);
+    assert!(reason.contains("non-BIP68-final"), "{}", reason);
+    mine_blocks(&bitcoind, TO_SELF_DELAY as u64 - 1);
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_to_remote_claim_transaction() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+
+    // Their commitment pays our to_remote straight to our payment basepoint
+    let script_pubkey = create_to_remote_script(&keys.signer.to_public_keys().payment_basepoint);
+    let address = Address::from_script(&script_pubkey, Network::Regtest).unwrap();
+    let txid = bitcoind.send_to_address(&address, OUTPUT_AMOUNT);
+    let vout = bitcoind
+        .get_raw_transaction(&txid)
+        .unwrap()
+        .output
+        .iter()
+        .position(|out| out.script_pubkey == script_pubkey)
+        .expect("to_remote output not found");
+    mine_blocks(&bitcoind, 1);
+
+    let tx = crate::transactions::sweep::create_to_remote_claim_transaction(
+        OutPoint::new(txid, vout as u32),
+        OUTPUT_AMOUNT,
+        &keys.signer,
+        &bitcoind.get_new_address().unwrap().script_pubkey(),
+        253,
+    )
+    .unwrap();
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_batched_sweep_transaction() {
+    use crate::transactions::sweep::{create_batched_sweep_transaction, ClaimableOutput};
+
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+
+    let per_commitment_point = keys.signer.derive_per_commitment_point(0);
+    let delayed_key = derive_public_key(
+        &keys.signer.to_public_keys().delayed_payment_basepoint,
+        &per_commitment_point,
+        &keys.signer.secp_ctx,
+    );
+    let to_local_script =
+        create_to_local_script(&keys.pubkey(&keys.revocation), &delayed_key, TO_SELF_DELAY);
+    let to_local_outpoint = fund_witness_script(&bitcoind, &to_local_script);
+
+    let to_remote_script = create_to_remote_script(&keys.signer.to_public_keys().payment_basepoint);
+    let address = Address::from_script(&to_remote_script, Network::Regtest).unwrap();
+    let txid = bitcoind.send_to_address(&address, OUTPUT_AMOUNT);
+    let vout = bitcoind
+        .get_raw_transaction(&txid)
+        .unwrap()
+        .output
+        .iter()
+        .position(|out| out.script_pubkey == to_remote_script)
+        .expect("to_remote output not found");
+    mine_blocks(&bitcoind, 1);
+
+    let outputs = [
+        ClaimableOutput::Delayed {
+            outpoint: to_local_outpoint,
+            value_sat: OUTPUT_AMOUNT,
+            to_local_script,
+            per_commitment_point,
+            to_self_delay: TO_SELF_DELAY,
+        },
+        ClaimableOutput::ToRemote {
+            outpoint: OutPoint::new(txid, vout as u32),
+            value_sat: OUTPUT_AMOUNT,
+        },
+    ];
+    let tx = create_batched_sweep_transaction(
+        &outputs,
+        &keys.signer,
+        &bitcoind.get_new_address().unwrap().script_pubkey(),
+        253,
+    )
+    .unwrap();
+
+    // The whole batch waits for the to_local delay
+    let reason = assert_rejected(&bitcoind, &tx);
+    assert!(reason.contains("non-BIP68-final"), "{}", reason);
+    mine_blocks(&bitcoind, TO_SELF_DELAY as u64 - 2);
+    assert_broadcast(&bitcoind, &tx);
+}
+
+// NEGATIVE TESTS
+// ============================================================================
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_rejects_wrong_preimage() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.offered_htlc_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    let tx = build_spend(&bitcoind, outpoint, LockTime::ZERO, Sequence::MAX);
+    let remote_sig = keys.sign(&tx, &script, &keys.remote_htlc);
+    let wrong_preimage = [0x43; 32];
+    let tx = with_witness(tx, &[&remote_sig, &wrong_preimage], &script);
+
+    let reason = assert_rejected(&bitcoind, &tx);
+    assert!(reason.contains("script-verify-flag-failed"), "unexpected reason: {}", reason);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_rejects_premature_csv_spend() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.to_local_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    // Only one confirmation so far, TO_SELF_DELAY are required
+    let tx = build_spend(
+        &bitcoind,
+        outpoint,
+        LockTime::ZERO,
+        relative_locktime_sequence(TO_SELF_DELAY as u32).unwrap(),
+    );
+    let sig = keys.sign(&tx, &script, &keys.local_delayed);
+    let tx = with_witness(tx, &[&sig, &[]], &script);
+
+    let reason = assert_rejected(&bitcoind, &tx);
+    assert_eq!(reason, "non-BIP68-final");
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_rejects_signature_from_wrong_key() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.to_local_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    // Revocation branch, but signed with the delayed payment key
+    let tx = build_spend(&bitcoind, outpoint, LockTime::ZERO, Sequence::MAX);
+    let sig = keys.sign(&tx, &script, &keys.local_delayed);
+    let tx = with_witness(tx, &[&sig, &[0x01]], &script);
+
+    let reason = assert_rejected(&bitcoind, &tx);
+    assert!(reason.contains("script-verify-flag-failed"), "unexpected reason: {}", reason);
+}
+
+// REORGS
+// ============================================================================
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_invalidate_and_reconsider_block() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let script = keys.to_local_script();
+    let outpoint = fund_witness_script(&bitcoind, &script);
+
+    let tip_height = bitcoind.get_block_count().unwrap();
+    let funding_block = bitcoind.get_block_hash(tip_height).unwrap();
+
+    // Disconnecting the funding block drops the tip back by one
+    bitcoind.invalidate_block(&funding_block).unwrap();
+    assert_eq!(bitcoind.get_block_count().unwrap(), tip_height - 1);
+
+    bitcoind.reconsider_block(&funding_block).unwrap();
+    assert_eq!(bitcoind.get_block_count().unwrap(), tip_height);
+    assert_eq!(bitcoind.get_block_hash(tip_height).unwrap(), funding_block);
+
+    // The funding output is spendable again once its block is back
+    let tx = build_spend(&bitcoind, outpoint, LockTime::ZERO, Sequence::MAX);
+    let sig = keys.sign(&tx, &script, &keys.revocation);
+    let tx = with_witness(tx, &[&sig, &[0x01]], &script);
+    assert_broadcast(&bitcoind, &tx);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_funding_inclusion_proof() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let outpoint = fund_witness_script(&bitcoind, &keys.to_local_script());
+    let funding_block = bitcoind.get_block_hash(bitcoind.get_block_count().unwrap()).unwrap();
+
+    let verified = crate::internal::spv::verify_funding_inclusion(
+        &bitcoind,
+        &outpoint.txid,
+        &funding_block,
+    );
+    assert!(verified.is_ok(), "{:?}", verified);
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_filter_scan_finds_to_remote_payment() {
+    use crate::internal::filter_scan::scan_with_filters;
+    use crate::internal::script_index::{OwnedScriptKind, ScriptIndex};
+
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let index = ScriptIndex::for_channel(
+        &keys.signer,
+        &crate::internal::simulated_peer::SimulatedPeer::new().public_keys(),
+        TO_SELF_DELAY,
+        0..=0,
+    );
+    let to_remote = index
+        .script_pubkeys()
+        .find(|script| index.classify(script).unwrap().kind == OwnedScriptKind::ToRemote)
+        .unwrap()
+        .clone();
+
+    let start_height = bitcoind.get_block_count().unwrap() + 1;
+    mine_blocks(&bitcoind, 3);
+    let address = Address::from_script(&to_remote, Network::Regtest).unwrap();
+    let txid = bitcoind.send_to_address(&address, OUTPUT_AMOUNT);
+    mine_blocks(&bitcoind, 1);
+    let tip_height = bitcoind.get_block_count().unwrap();
+
+    let (matches, blocks_fetched) =
+        scan_with_filters(&bitcoind, &index, start_height..=tip_height).unwrap();
+    assert!(blocks_fetched < (tip_height - start_height + 1) as usize);
+    assert!(matches.iter().any(|m| m.txid == txid && m.height == tip_height));
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_funding_replaced_by_fee() {
+    use crate::internal::helper::sign_raw_transaction;
+    use crate::transactions::funding::{
+        bump_funding_transaction_fee, create_funding_transaction_with_change,
+        P2WPKH_INPUT_SATISFACTION_WEIGHT,
+    };
+
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let utxo = bitcoind.list_unspent().0[0].clone();
+    let change_script = bitcoind.get_new_address().unwrap().script_pubkey();
+
+    let mut tx = create_funding_transaction_with_change(
+        OutPoint::new(utxo.txid, utxo.vout),
+        utxo.amount,
+        P2WPKH_INPUT_SATISFACTION_WEIGHT,
+        OUTPUT_AMOUNT,
+        &bitcoin::PublicKey::new(keys.pubkey(&keys.revocation)),
+        &bitcoin::PublicKey::new(keys.pubkey(&keys.local_htlc)),
+        &change_script,
+        253,
+    )
+    .unwrap();
+    tx.input[0].sequence = Sequence::ENABLE_RBF_NO_LOCKTIME;
+    let original = sign_raw_transaction(bitcoind.clone(), tx);
+    bitcoind.send_raw_transaction(&original).unwrap();
+
+    let bumped = bump_funding_transaction_fee(
+        &original,
+        utxo.amount,
+        P2WPKH_INPUT_SATISFACTION_WEIGHT,
+        2_500,
+    )
+    .unwrap();
+    let bumped = sign_raw_transaction(bitcoind.clone(), bumped);
+    assert_broadcast(&bitcoind, &bumped);
+
+    // The replacement confirmed and the original is gone
+    assert!(bitcoind.get_raw_transaction(&bumped.compute_txid()).is_ok());
+    assert!(bitcoind.get_raw_transaction(&original.compute_txid()).is_err());
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_cli_funding_rbf_on_workshop_utxo() {
+    use crate::interactive::funding::build_rbf_funding_transactions;
+    use crate::internal::helper::{get_unspent_output, sign_raw_transaction};
+
+    // The UTXO and transactions `funding --rbf-feerate` would use
+    let bitcoind = get_bitcoind_client();
+    let prevout = get_unspent_output(bitcoind.clone()).previous_output;
+    let input_amount_sat = bitcoind.get_raw_transaction(&prevout.txid).unwrap().output
+        [prevout.vout as usize]
+        .value
+        .to_sat();
+    let change_script = bitcoind.get_new_address().unwrap().script_pubkey();
+    let (original, bumped) =
+        build_rbf_funding_transactions(prevout, input_amount_sat, &change_script, 2_500).unwrap();
+
+    let original = sign_raw_transaction(bitcoind.clone(), original);
+    bitcoind.send_raw_transaction(&original).unwrap();
+    let bumped = sign_raw_transaction(bitcoind.clone(), bumped);
+    assert_broadcast(&bitcoind, &bumped);
+
+    assert!(bitcoind.get_raw_transaction(&bumped.compute_txid()).is_ok());
+    assert!(bitcoind.get_raw_transaction(&original.compute_txid()).is_err());
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_lock_and_unlock_unspent() {
+    let bitcoind = get_bitcoind_client();
+    let utxo = bitcoind.list_unspent().0[0].clone();
+    let outpoint = OutPoint { txid: utxo.txid, vout: utxo.vout };
+
+    // A locked output is hidden from `listunspent`
+    bitcoind.lock_unspent(&[outpoint]).unwrap();
+    assert!(bitcoind.list_lock_unspent().unwrap().contains(&outpoint));
+    assert!(!bitcoind
+        .list_unspent()
+        .0
+        .iter()
+        .any(|utxo| utxo.txid == outpoint.txid && utxo.vout == outpoint.vout));
+
+    bitcoind.unlock_unspent(&[outpoint]).unwrap();
+    assert!(!bitcoind.list_lock_unspent().unwrap().contains(&outpoint));
+}
+
+// MALLEABILITY
+// ============================================================================
+
+/// Sign input 0 of a spend from the legacy P2SH 2-of-2 with both keys
+fn sign_p2sh_multisig(
+    tx: Transaction,
+    redeem_script: &ScriptBuf,
+    secrets: &[SecretKey; 2],
+) -> (Transaction, [Vec<u8>; 2]) {
+    // The redeem script orders its keys, so the signatures must follow
+    let mut secrets = *secrets;
+    secrets.sort_by_key(|secret| PublicKey::from_secret_key(&Secp256k1::new(), secret).serialize());
+    let signatures = secrets.map(|secret| sign_p2sh_input(&tx, 0, redeem_script, &secret));
+
+    let mut tx = tx;
+    tx.input[0].script_sig = p2sh_multisig_script_sig([&signatures[0], &signatures[1]], redeem_script);
+    (tx, signatures)
+}
+
+#[test]
+#[ignore = "requires a running regtest bitcoind"]
+#[serial]
+fn test_regtest_malleated_legacy_commitment_orphans_child() {
+    let bitcoind = get_bitcoind_client();
+    let keys = ScriptKeys::new();
+    let secrets = [keys.local_htlc, keys.remote_htlc];
+    let (redeem_script, script_pubkey) = create_p2sh_funding_output(
+        &bitcoin::PublicKey::new(keys.pubkey(&secrets[0])),
+        &bitcoin::PublicKey::new(keys.pubkey(&secrets[1])),
+    );
+
+    // Fund the pre-segwit channel output
+    let address = Address::from_script(&script_pubkey, Network::Regtest).unwrap();
+    let txid = bitcoind.send_to_address(&address, OUTPUT_AMOUNT);
+    let funding_tx = bitcoind.get_raw_transaction(&txid).unwrap();
+    let vout = funding_tx
+        .output
+        .iter()
+        .position(|out| out.script_pubkey == script_pubkey)
+        .expect("Funding output not found");
+    mine_blocks(&bitcoind, 1);
+
+    // Sign a "commitment" paying back to the 2-of-2, and a child spending it
+    let mut commitment_tx =
+        build_spend(&bitcoind, OutPoint::new(txid, vout as u32), LockTime::ZERO, Sequence::MAX);
+    commitment_tx.output[0].script_pubkey = script_pubkey;
+    let (commitment_tx, signatures) = sign_p2sh_multisig(commitment_tx, &redeem_script, &secrets);
+
+    let mut child = build_spend(
+        &bitcoind,
+        OutPoint::new(commitment_tx.compute_txid(), 0),
+        LockTime::ZERO,
+        Sequence::MAX,
+    );
+    child.output[0].value = Amount::from_sat(OUTPUT_AMOUNT - 2 * SPEND_FEE);
+    let (child, _) = sign_p2sh_multisig(child, &redeem_script, &secrets);
+
+    // A miner confirms a high-S copy of the commitment instead
+    let malleated =
+        malleate_p2sh_multisig_tx(&commitment_tx, [&signatures[0], &signatures[1]], &redeem_script);
+    assert_ne!(malleated.compute_txid(), commitment_tx.compute_txid());
+    bitcoind
+        .generate_block(&bitcoind.get_new_address().unwrap(), &[malleated])
+        .unwrap();
+
+    // The pre-signed child names an output that never confirmed
+    let reason = assert_rejected(&bitcoind, &child);
+    assert!(reason.contains("missing-inputs"), "{}", reason);
+}
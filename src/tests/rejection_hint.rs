@@ -0,0 +1,50 @@
+use crate::internal::helper::rejection_hint;
+
+// Reject reasons below are as bitcoind's testmempoolaccept reports them
+
+#[test]
+fn test_hint_for_missing_inputs() {
+    assert!(rejection_hint("missing-inputs").contains("unknown or already spent"));
+    assert!(rejection_hint("bad-txns-inputs-missingorspent").contains("unknown or already spent"));
+}
+
+#[test]
+fn test_hint_for_absolute_locktime_needs_exact_reason() {
+    assert!(rejection_hint("non-final").contains("nLockTime"));
+    // Only the bare reason is the nLockTime one
+    assert!(!rejection_hint("non-final-tx").contains("nLockTime"));
+}
+
+#[test]
+fn test_hint_for_relative_locktime() {
+    assert!(rejection_hint("non-BIP68-final").contains("relative timelock"));
+}
+
+#[test]
+fn test_hint_for_bad_witness() {
+    let reason = "mandatory-script-verify-flag-failed (Signature must be zero for failed CHECK(MULTI)SIG operation)";
+    assert!(rejection_hint(reason).contains("witness does not satisfy"));
+    assert!(rejection_hint("bad-witness-nonstandard").contains("witness does not satisfy"));
+}
+
+#[test]
+fn test_hint_for_dust() {
+    assert!(rejection_hint("dust").contains("dust limit"));
+}
+
+#[test]
+fn test_hint_for_low_fee() {
+    assert!(rejection_hint("min relay fee not met, 0 < 110").contains("fee is too low"));
+}
+
+#[test]
+fn test_hint_for_conflicts() {
+    assert!(rejection_hint("txn-already-in-mempool").contains("already broadcast"));
+    assert!(rejection_hint("txn-mempool-conflict").contains("already broadcast"));
+}
+
+#[test]
+fn test_hint_falls_back_to_decoding() {
+    assert!(rejection_hint("tx-size").contains("decoderawtransaction"));
+    assert!(rejection_hint("").contains("decoderawtransaction"));
+}
@@ -0,0 +1,49 @@
+use crate::transactions::commitment::{
+    relative_locktime_sequence, relative_locktime_sequence_from_seconds, RelativeLocktimeError,
+};
+use bitcoin::relative;
+use bitcoin::Sequence;
+
+#[test]
+fn test_block_based_sequence() {
+    let sequence = relative_locktime_sequence(144).unwrap();
+
+    assert_eq!(sequence.to_consensus_u32(), 144);
+    assert!(sequence.is_relative_lock_time());
+    assert!(sequence.is_height_locked());
+    assert_eq!(sequence, Sequence::from_height(144));
+}
+
+#[test]
+fn test_block_based_sequence_limits() {
+    assert_eq!(relative_locktime_sequence(0).unwrap(), Sequence::ZERO);
+    assert_eq!(relative_locktime_sequence(65535).unwrap().to_consensus_u32(), 0xffff);
+    assert_eq!(
+        relative_locktime_sequence(65536),
+        Err(RelativeLocktimeError::TooManyBlocks(65536))
+    );
+}
+
+#[test]
+fn test_time_based_sequence() {
+    // One day is 168.75 units of 512 seconds, rounded up to 169
+    let sequence = relative_locktime_sequence_from_seconds(86_400).unwrap();
+
+    assert_eq!(sequence.to_consensus_u32(), (1 << 22) | 169);
+    assert!(sequence.is_time_locked());
+    assert_eq!(
+        sequence.to_relative_lock_time(),
+        Some(relative::LockTime::from_512_second_intervals(169))
+    );
+}
+
+#[test]
+fn test_time_based_sequence_limits() {
+    let max_seconds = 65535 * 512;
+
+    assert!(relative_locktime_sequence_from_seconds(max_seconds).is_ok());
+    assert_eq!(
+        relative_locktime_sequence_from_seconds(max_seconds + 1),
+        Err(RelativeLocktimeError::TooManySeconds(max_seconds + 1))
+    );
+}
@@ -0,0 +1,233 @@
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::types::{ChannelKeyManager, CommitmentKeys, HTLCOutput};
+use crate::*;
+use bitcoin::hashes::Hash;
+use bitcoin::PublicKey as BitcoinPublicKey;
+use bitcoin::{OutPoint, Transaction, Txid};
+
+const OUR_BALANCE: u64 = 6_000_000;
+const THEIR_BALANCE: u64 = 4_000_000;
+const COMMITMENT_NUMBER: u64 = 5;
+// The delay we require of the peer, and each side's dust limit
+const THEIR_TO_SELF_DELAY: u16 = 144;
+const OUR_DUST_LIMIT: u64 = 1_500;
+const THEIR_DUST_LIMIT: u64 = 546;
+const FEERATE_PER_KW: u64 = 2_000;
+
+fn our_keys() -> ChannelKeyManager {
+    new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0)
+}
+
+// The keys `SimulatedPeer::new()` signs with, for building its own view
+fn their_keys() -> ChannelKeyManager {
+    new_keys_manager(SimulatedPeer::SEED, Network::Bitcoin).derive_channel_keys(0)
+}
+
+fn our_offered() -> Vec<HTLCOutput> {
+    vec![HTLCOutput {
+        amount_sat: 20_000,
+        payment_hash: PaymentHash::from_hash_bytes([0x01; 32]),
+        cltv_expiry: 500,
+    }]
+}
+
+fn our_received() -> Vec<HTLCOutput> {
+    vec![HTLCOutput {
+        amount_sat: 30_000,
+        payment_hash: PaymentHash::from_hash_bytes([0x02; 32]),
+        cltv_expiry: 510,
+    }]
+}
+
+fn our_view(we_are_funder: bool) -> Transaction {
+    our_view_with_balances(we_are_funder, OUR_BALANCE, THEIR_BALANCE)
+}
+
+fn our_view_with_balances(we_are_funder: bool, our_balance: u64, their_balance: u64) -> Transaction {
+    our_view_with_htlcs(we_are_funder, our_balance, their_balance, &our_offered(), &our_received())
+}
+
+fn our_view_with_htlcs(
+    we_are_funder: bool,
+    our_balance: u64,
+    their_balance: u64,
+    offered: &[HTLCOutput],
+    received: &[HTLCOutput],
+) -> Transaction {
+    let peer = SimulatedPeer::new();
+    build_remote_commitment_transaction(
+        OutPoint::new(Txid::all_zeros(), 0),
+        our_balance,
+        their_balance,
+        &our_keys(),
+        &peer.public_keys(),
+        &peer.per_commitment_point(COMMITMENT_NUMBER),
+        COMMITMENT_NUMBER,
+        THEIR_TO_SELF_DELAY,
+        OUR_DUST_LIMIT,
+        THEIR_DUST_LIMIT,
+        FEERATE_PER_KW,
+        offered,
+        received,
+        we_are_funder,
+    )
+}
+
+// The same commitment, built by the peer as its holder commitment
+fn their_view(we_are_funder: bool) -> Transaction {
+    let keys = their_keys();
+    let local = keys.to_public_keys();
+    let remote = our_keys().to_public_keys();
+    let commitment_keys = CommitmentKeys::from_basepoints(
+        &keys.derive_per_commitment_point(COMMITMENT_NUMBER),
+        &local.delayed_payment_basepoint,
+        &local.htlc_basepoint,
+        &remote.revocation_basepoint,
+        &remote.htlc_basepoint,
+        &keys.secp_ctx,
+    );
+    create_commitment_transaction_with_funder(
+        OutPoint::new(Txid::all_zeros(), 0),
+        THEIR_BALANCE,
+        OUR_BALANCE,
+        &commitment_keys,
+        &local.payment_basepoint,
+        &remote.payment_basepoint,
+        COMMITMENT_NUMBER,
+        THEIR_TO_SELF_DELAY,
+        THEIR_DUST_LIMIT,
+        FEERATE_PER_KW,
+        &our_received(),
+        &our_offered(),
+        !we_are_funder,
+    )
+}
+
+#[test]
+fn test_remote_commitment_matches_peer_holder_view() {
+    for we_are_funder in [true, false] {
+        assert_eq!(
+            our_view(we_are_funder),
+            their_view(we_are_funder),
+            "we_are_funder = {}",
+            we_are_funder
+        );
+    }
+}
+
+#[test]
+fn test_remote_commitment_is_revocable_by_us() {
+    let keys = our_keys();
+    let peer = SimulatedPeer::new();
+    let per_commitment_point = peer.per_commitment_point(COMMITMENT_NUMBER);
+    let revocation_key = derive_revocation_public_key(
+        &keys.to_public_keys().revocation_basepoint,
+        &per_commitment_point,
+        &keys.secp_ctx,
+    );
+    let their_delayed_key = derive_public_key(
+        &peer.public_keys().delayed_payment_basepoint,
+        &per_commitment_point,
+        &keys.secp_ctx,
+    );
+    let their_to_local =
+        create_to_local_script(&revocation_key, &their_delayed_key, THEIR_TO_SELF_DELAY);
+    let our_to_remote = create_to_remote_script(&keys.to_public_keys().payment_basepoint);
+
+    let tx = our_view(true);
+    let fee = calculate_commitment_tx_fee(FEERATE_PER_KW, 2);
+    assert!(tx
+        .output
+        .iter()
+        .any(|output| output.script_pubkey == their_to_local.to_p2wsh()
+            && output.value.to_sat() == THEIR_BALANCE));
+    assert!(tx
+        .output
+        .iter()
+        .any(|output| output.script_pubkey == our_to_remote
+            && output.value.to_sat() == OUR_BALANCE - fee));
+}
+
+#[test]
+fn test_remote_commitment_trims_sub_dust_non_funder_balance() {
+    // We fund, so their to_local pays no fee and is checked against their
+    // dust limit as it is
+    let their_balance = THEIR_DUST_LIMIT - 1;
+    let tx = our_view_with_balances(true, OUR_BALANCE, their_balance);
+    let fee = calculate_commitment_tx_fee(FEERATE_PER_KW, 2);
+
+    let values: Vec<u64> = tx.output.iter().map(|output| output.value.to_sat()).collect();
+    assert_eq!(values, vec![20_000, 30_000, OUR_BALANCE - fee]);
+}
+
+#[test]
+fn test_remote_commitment_trims_htlcs_with_their_dust_limit() {
+    // Offered by us, so received on their commitment: dust on ours, not theirs
+    let offered = HTLCOutput {
+        amount_sat: 2_500,
+        payment_hash: PaymentHash::from_hash_bytes([0x03; 32]),
+        cltv_expiry: 520,
+    };
+    // Received by us, so offered on their commitment: dust on both
+    let received = HTLCOutput {
+        amount_sat: 1_500,
+        payment_hash: PaymentHash::from_hash_bytes([0x04; 32]),
+        cltv_expiry: 530,
+    };
+    let trimming = |htlc: &HTLCOutput, offered_by_local: bool| {
+        htlc_trimming(
+            htlc.amount_sat,
+            OUR_DUST_LIMIT,
+            THEIR_DUST_LIMIT,
+            FEERATE_PER_KW,
+            offered_by_local,
+            ChannelType::StaticRemoteKey,
+        )
+    };
+    assert_eq!(trimming(&offered, true).to_string(), "trimmed on local, present on remote");
+    assert_eq!(trimming(&received, false).to_string(), "trimmed on local, trimmed on remote");
+
+    let tx = our_view_with_htlcs(
+        true,
+        OUR_BALANCE,
+        THEIR_BALANCE,
+        &[our_offered(), vec![offered]].concat(),
+        &[our_received(), vec![received]].concat(),
+    );
+    // Only the untrimmed HTLCs count towards the fee
+    let fee = calculate_commitment_tx_fee(FEERATE_PER_KW, 3);
+
+    let values: Vec<u64> = tx.output.iter().map(|output| output.value.to_sat()).collect();
+    assert_eq!(values, vec![2_500, 20_000, 30_000, THEIR_BALANCE, OUR_BALANCE - fee]);
+}
+
+#[test]
+fn test_peer_accepts_our_signature_on_remote_commitment() {
+    let keys = our_keys();
+    let peer = SimulatedPeer::new();
+    let our_funding_pubkey = keys.to_public_keys().funding_pubkey;
+    let funding_script = create_funding_script(
+        &BitcoinPublicKey::new(our_funding_pubkey),
+        &BitcoinPublicKey::new(peer.public_keys().funding_pubkey),
+    );
+
+    let tx = our_view(true);
+    let signature = keys.sign_transaction_input_sighash_all(
+        &tx,
+        0,
+        &funding_script,
+        10_000_000,
+        &keys.funding_key,
+    );
+    assert_eq!(
+        peer.validate_commitment_signature(
+            &tx,
+            0,
+            &funding_script,
+            10_000_000,
+            &signature,
+            &our_funding_pubkey
+        ),
+        Ok(())
+    );
+}
@@ -0,0 +1,87 @@
+use crate::transactions::sanity::{
+    check_commitment_tx_encoding, check_htlc_success_tx_encoding, check_htlc_timeout_tx_encoding,
+    TxEncodingError,
+};
+use crate::types::ChannelType;
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+
+fn single_input_tx(lock_time: u32, sequence: u32) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::from_consensus(lock_time),
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::from_consensus(sequence),
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut { value: Amount::from_sat(1000), script_pubkey: ScriptBuf::new() }],
+    }
+}
+
+#[test]
+fn test_commitment_encoding() {
+    // BOLT 3 vector: commitment number 42 obscured
+    assert_eq!(check_commitment_tx_encoding(&single_input_tx(0x2052193e, 0x802bb038)), Ok(()));
+
+    assert_eq!(
+        check_commitment_tx_encoding(&single_input_tx(0x0052193e, 0x802bb038)),
+        Err(TxEncodingError::CommitmentLocktime(0x0052193e))
+    );
+    assert_eq!(
+        check_commitment_tx_encoding(&single_input_tx(0x2052193e, 0xffffffff)),
+        Err(TxEncodingError::CommitmentSequence(0xffffffff))
+    );
+
+    let mut v1 = single_input_tx(0x2052193e, 0x802bb038);
+    v1.version = Version::ONE;
+    assert_eq!(check_commitment_tx_encoding(&v1), Err(TxEncodingError::WrongVersion(Version::ONE)));
+}
+
+#[test]
+fn test_htlc_timeout_encoding() {
+    let legacy = ChannelType::StaticRemoteKey;
+    let anchors = ChannelType::AnchorsZeroFeeHtlcTx;
+
+    assert_eq!(check_htlc_timeout_tx_encoding(&single_input_tx(502, 0), legacy), Ok(()));
+    assert_eq!(check_htlc_timeout_tx_encoding(&single_input_tx(502, 1), anchors), Ok(()));
+
+    assert!(matches!(
+        check_htlc_timeout_tx_encoding(&single_input_tx(0, 0), legacy),
+        Err(TxEncodingError::HtlcTimeoutLocktime(_))
+    ));
+    // A timestamp is never a valid cltv_expiry
+    assert!(matches!(
+        check_htlc_timeout_tx_encoding(&single_input_tx(1_700_000_000, 0), legacy),
+        Err(TxEncodingError::HtlcTimeoutLocktime(_))
+    ));
+    assert_eq!(
+        check_htlc_timeout_tx_encoding(&single_input_tx(502, 0), anchors),
+        Err(TxEncodingError::HtlcSequence {
+            expected: Sequence::from_consensus(1),
+            actual: Sequence::ZERO,
+        })
+    );
+}
+
+#[test]
+fn test_htlc_success_encoding() {
+    let legacy = ChannelType::StaticRemoteKey;
+
+    assert_eq!(check_htlc_success_tx_encoding(&single_input_tx(0, 0), legacy), Ok(()));
+    assert!(matches!(
+        check_htlc_success_tx_encoding(&single_input_tx(500, 0), legacy),
+        Err(TxEncodingError::HtlcSuccessLocktime(_))
+    ));
+
+    let mut two_inputs = single_input_tx(0, 0);
+    two_inputs.input.push(two_inputs.input[0].clone());
+    assert_eq!(
+        check_htlc_success_tx_encoding(&two_inputs, legacy),
+        Err(TxEncodingError::WrongInputCount(2))
+    );
+}
@@ -0,0 +1,99 @@
+use crate::internal::script_index::{OwnedScript, OwnedScriptKind, ScriptIndex};
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::types::{ChannelKeyManager, CommitmentKeys, HTLCOutput};
+use crate::*;
+use bitcoin::hashes::Hash;
+use bitcoin::{OutPoint, Transaction, Txid};
+
+const TO_SELF_DELAY: u16 = 144;
+
+fn local_keys() -> ChannelKeyManager {
+    new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0)
+}
+
+fn holder_commitment_keys(keys: &ChannelKeyManager, commitment_number: u64) -> CommitmentKeys {
+    let local = keys.to_public_keys();
+    let remote = SimulatedPeer::new().public_keys();
+    CommitmentKeys::from_basepoints(
+        &keys.derive_per_commitment_point(commitment_number),
+        &local.delayed_payment_basepoint,
+        &local.htlc_basepoint,
+        &remote.revocation_basepoint,
+        &remote.htlc_basepoint,
+        &keys.secp_ctx,
+    )
+}
+
+fn holder_commitment(
+    keys: &ChannelKeyManager,
+    commitment_number: u64,
+    offered_htlcs: &[HTLCOutput],
+) -> Transaction {
+    create_commitment_transaction(
+        OutPoint::new(Txid::all_zeros(), 0),
+        7_000_000,
+        3_000_000,
+        &holder_commitment_keys(keys, commitment_number),
+        &keys.to_public_keys().payment_basepoint,
+        &SimulatedPeer::new().public_keys().payment_basepoint,
+        commitment_number,
+        TO_SELF_DELAY,
+        546,
+        253,
+        offered_htlcs,
+        &[],
+    )
+}
+
+fn channel_index(keys: &ChannelKeyManager) -> ScriptIndex {
+    ScriptIndex::for_channel(keys, &SimulatedPeer::new().public_keys(), TO_SELF_DELAY, 0..=9)
+}
+
+#[test]
+fn test_index_holds_to_remote_and_each_to_local() {
+    assert_eq!(channel_index(&local_keys()).len(), 1 + 10);
+}
+
+#[test]
+fn test_classifies_to_local_by_commitment_number() {
+    let keys = local_keys();
+    let index = channel_index(&keys);
+    let tx = holder_commitment(&keys, 5, &[]);
+
+    // Their to_remote on our commitment is not ours
+    assert_eq!(
+        index.owned_outputs(&tx),
+        vec![(
+            1,
+            OwnedScript { kind: OwnedScriptKind::ToLocal, commitment_number: Some(5) }
+        )]
+    );
+
+    // Older and newer commitments than the indexed window are not recognized
+    assert!(index.owned_outputs(&holder_commitment(&keys, 10, &[])).is_empty());
+}
+
+#[test]
+fn test_classifies_our_to_remote() {
+    let keys = local_keys();
+    let script_pubkey = create_to_remote_script(&keys.to_public_keys().payment_basepoint);
+
+    assert_eq!(
+        channel_index(&keys).classify(&script_pubkey),
+        Some(OwnedScript { kind: OwnedScriptKind::ToRemote, commitment_number: None })
+    );
+}
+
+#[test]
+fn test_classifies_added_htlcs() {
+    let keys = local_keys();
+    let mut index = channel_index(&keys);
+    let htlc = HTLCOutput { amount_sat: 50_000, payment_hash: PaymentHash::from_hash_bytes([0x42; 32]), cltv_expiry: 500 };
+    let tx = holder_commitment(&keys, 3, std::slice::from_ref(&htlc));
+    assert_eq!(index.owned_outputs(&tx).len(), 1);
+
+    index.add_htlcs(3, &holder_commitment_keys(&keys, 3), &[htlc], &[]);
+    let kinds: Vec<OwnedScriptKind> =
+        index.owned_outputs(&tx).into_iter().map(|(_, owned)| owned.kind).collect();
+    assert_eq!(kinds, vec![OwnedScriptKind::OfferedHtlc, OwnedScriptKind::ToLocal]);
+}
@@ -0,0 +1,119 @@
+use crate::internal::script_utils::*;
+use crate::*;
+use crate::tests::fixtures::test_pubkey;
+use bitcoin::blockdata::opcodes::all as opcodes;
+use bitcoin::hashes::Hash;
+use bitcoin::script::{Builder, ScriptBuf};
+use bitcoin::secp256k1::PublicKey;
+use bitcoin::{Network, WScriptHash, Witness};
+
+fn p2wsh_script() -> ScriptBuf {
+    ScriptBuf::new_p2wsh(&WScriptHash::from_byte_array([0x11; 32]))
+}
+
+#[test]
+fn test_address_for_p2wsh_uses_network_prefix() {
+    let script = p2wsh_script();
+
+    let regtest = address_for_script(&script, Network::Regtest).unwrap();
+    let mainnet = address_for_script(&script, Network::Bitcoin).unwrap();
+
+    assert!(regtest.to_string().starts_with("bcrt1q"));
+    assert!(mainnet.to_string().starts_with("bc1q"));
+    assert_eq!(regtest.script_pubkey(), script);
+}
+
+#[test]
+fn test_op_return_has_no_address() {
+    let script = Builder::new()
+        .push_opcode(opcodes::OP_RETURN)
+        .push_slice([0xab; 4])
+        .into_script();
+
+    assert!(address_for_script(&script, Network::Regtest).is_none());
+    assert_eq!(
+        display_script(&script, Network::Regtest),
+        "script 6a04abababab"
+    );
+}
+
+fn sample_keys() -> (PublicKey, PublicKey, PublicKey) {
+    (test_pubkey(1), test_pubkey(2), test_pubkey(3))
+}
+
+#[test]
+fn test_received_htlc_script_standard_at_cltv_boundaries() {
+    let (revocation, local, remote) = sample_keys();
+    let payment_hash = PaymentHash::from_hash_bytes([0x42; 32]);
+
+    // Each boundary changes how many bytes the expiry push takes (0..=4 data bytes)
+    for cltv_expiry in [
+        1,
+        16,
+        17,
+        127,
+        128,
+        32_767,
+        32_768,
+        8_388_607,
+        8_388_608,
+        499_999_999,
+    ] {
+        let script =
+            create_received_htlc_script(&revocation, &local, &remote, &payment_hash, cltv_expiry);
+
+        assert!(
+            check_witness_script_standard(&script).is_ok(),
+            "cltv_expiry {}",
+            cltv_expiry
+        );
+
+        // Worst-case HTLC-success witness: two 73-byte signatures plus the preimage
+        let witness = Witness::from_slice(&[
+            &[][..],
+            &[0x30; 73],
+            &[0x30; 73],
+            &[0x42; 32],
+            script.as_bytes(),
+        ]);
+        assert!(
+            check_witness_standard(&witness).is_ok(),
+            "cltv_expiry {}",
+            cltv_expiry
+        );
+    }
+}
+
+#[test]
+fn test_witness_standardness_limits() {
+    let script = ScriptBuf::from_bytes(vec![0x51; MAX_STANDARD_P2WSH_SCRIPT_SIZE]);
+    assert!(check_witness_script_standard(&script).is_ok());
+
+    let too_large = ScriptBuf::from_bytes(vec![0x51; MAX_STANDARD_P2WSH_SCRIPT_SIZE + 1]);
+    assert_eq!(
+        check_witness_script_standard(&too_large),
+        Err(StandardnessError::ScriptTooLarge {
+            size: MAX_STANDARD_P2WSH_SCRIPT_SIZE + 1
+        })
+    );
+
+    let max_item = [0u8; MAX_STANDARD_P2WSH_STACK_ITEM_SIZE];
+    let big_item = [0u8; MAX_STANDARD_P2WSH_STACK_ITEM_SIZE + 1];
+    assert!(check_witness_standard(&Witness::from_slice(&[&max_item[..], &[0x51]])).is_ok());
+    assert_eq!(
+        check_witness_standard(&Witness::from_slice(&[&[][..], &big_item[..], &[0x51]])),
+        Err(StandardnessError::StackItemTooLarge {
+            index: 1,
+            size: MAX_STANDARD_P2WSH_STACK_ITEM_SIZE + 1
+        })
+    );
+
+    let mut items = vec![vec![]; MAX_STANDARD_P2WSH_STACK_ITEMS + 1];
+    items.push(vec![0x51]);
+    assert_eq!(
+        check_witness_standard(&Witness::from_slice(&items)),
+        Err(StandardnessError::TooManyStackItems {
+            count: MAX_STANDARD_P2WSH_STACK_ITEMS + 1
+        })
+    );
+}
@@ -0,0 +1,51 @@
+use crate::keys::channel_key_manager::derive_commitment_secret;
+use crate::types::ChannelKeyManager;
+use bitcoin::secp256k1::{Secp256k1, SecretKey};
+use proptest::prelude::*;
+
+// Commitment numbers are 48 bits
+const MAX_INDEX: u64 = (1 << 48) - 1;
+
+fn key_manager(commitment_seed: [u8; 32]) -> ChannelKeyManager {
+    let key = SecretKey::from_slice(&[0x01; 32]).unwrap();
+    ChannelKeyManager {
+        funding_key: key,
+        revocation_basepoint_secret: key,
+        payment_basepoint_secret: key,
+        delayed_payment_basepoint_secret: key,
+        htlc_basepoint_secret: key,
+        commitment_seed,
+        secp_ctx: Secp256k1::new(),
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_seed_derives_every_secret(seed in any::<[u8; 32]>(), index in 0..=MAX_INDEX) {
+        // The seed is the secret of the "index" with all 48 bits unknown
+        let keys = key_manager(seed);
+        prop_assert_eq!(derive_commitment_secret(&seed, 48, index), keys.build_commitment_secret(index));
+    }
+
+    #[test]
+    fn test_revealed_secret_derives_its_range(
+        seed in any::<[u8; 32]>(),
+        index in 0..=MAX_INDEX,
+        bits in 0u32..=48,
+        low_bits in any::<u64>(),
+    ) {
+        let keys = key_manager(seed);
+
+        // A revealed secret whose index ends in `bits` zeros...
+        let mask = (1u64 << bits) - 1;
+        let base_index = index & !mask;
+        let base_secret = keys.build_commitment_secret(base_index);
+
+        // ...derives the secret of any index that only differs in those bits
+        let target_index = base_index | (low_bits & mask);
+        prop_assert_eq!(
+            derive_commitment_secret(&base_secret, bits, target_index),
+            keys.build_commitment_secret(target_index)
+        );
+    }
+}
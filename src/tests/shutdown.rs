@@ -0,0 +1,69 @@
+use crate::scripts::shutdown::{validate_shutdown_script, ShutdownScriptError};
+use bitcoin::hashes::Hash;
+use bitcoin::key::{TweakedPublicKey, XOnlyPublicKey};
+use bitcoin::opcodes::all::OP_PUSHNUM_16;
+use bitcoin::script::{Builder, ScriptBuf};
+use bitcoin::{PubkeyHash, ScriptHash, WPubkeyHash, WScriptHash};
+
+fn p2tr() -> ScriptBuf {
+    let xonly = XOnlyPublicKey::from_slice(&[
+        0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b,
+        0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8,
+        0x17, 0x98,
+    ])
+    .unwrap();
+    ScriptBuf::new_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(xonly))
+}
+
+#[test]
+fn test_legacy_and_v0_templates_always_allowed() {
+    let scripts = [
+        ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()),
+        ScriptBuf::new_p2sh(&ScriptHash::all_zeros()),
+        ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+        ScriptBuf::new_p2wsh(&WScriptHash::all_zeros()),
+    ];
+
+    for script in scripts {
+        assert_eq!(validate_shutdown_script(&script, false), Ok(()), "{}", script);
+    }
+}
+
+#[test]
+fn test_p2tr_only_with_anysegwit() {
+    assert_eq!(
+        validate_shutdown_script(&p2tr(), false),
+        Err(ShutdownScriptError::AnySegwitNotNegotiated)
+    );
+    assert_eq!(validate_shutdown_script(&p2tr(), true), Ok(()));
+}
+
+#[test]
+fn test_future_witness_version_with_anysegwit() {
+    // OP_16 <2 bytes>: the shortest witness program of the highest version
+    let script = Builder::new()
+        .push_opcode(OP_PUSHNUM_16)
+        .push_slice([0xab, 0xcd])
+        .into_script();
+
+    assert_eq!(validate_shutdown_script(&script, true), Ok(()));
+}
+
+#[test]
+fn test_other_scripts_rejected() {
+    // A v0 program that is neither 20 nor 32 bytes
+    let bad_v0 = Builder::new().push_int(0).push_slice([0u8; 24]).into_script();
+    // Bare multisig / arbitrary scripts
+    let op_return = ScriptBuf::new_op_return([0u8; 4]);
+
+    for script in [bad_v0, op_return, ScriptBuf::new()] {
+        for anysegwit in [false, true] {
+            assert_eq!(
+                validate_shutdown_script(&script, anysegwit),
+                Err(ShutdownScriptError::NotAllowed),
+                "{}",
+                script
+            );
+        }
+    }
+}
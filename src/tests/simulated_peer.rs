@@ -0,0 +1,62 @@
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::keys::derivation::new_keys_manager;
+use crate::scripts::funding::create_funding_script;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, Network, OutPoint, Sequence, Transaction, TxIn, TxOut, Witness};
+use bitcoin::PublicKey as BitcoinPublicKey;
+
+const FUNDING_AMOUNT: u64 = 5_000_000;
+
+fn spend_funding() -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut { value: Amount::from_sat(4_990_000), script_pubkey: ScriptBuf::new() }],
+    }
+}
+
+#[test]
+fn test_simulated_peer_uses_workshop_seed() {
+    let expected = new_keys_manager([0x02; 32], Network::Bitcoin).derive_channel_keys(0);
+
+    assert_eq!(SimulatedPeer::new().public_keys().funding_pubkey, expected.to_public_keys().funding_pubkey);
+    assert_eq!(SimulatedPeer::new().revoke(7), expected.build_commitment_secret(7));
+}
+
+#[test]
+fn test_simulated_peer_validates_our_commitment_signature() {
+    let peer = SimulatedPeer::new();
+    let us = new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0);
+    let our_funding_pubkey = us.to_public_keys().funding_pubkey;
+    let funding_script = create_funding_script(
+        &BitcoinPublicKey::new(our_funding_pubkey),
+        &BitcoinPublicKey::new(peer.public_keys().funding_pubkey),
+    );
+    let tx = spend_funding();
+
+    let our_signature =
+        us.sign_transaction_input_sighash_all(&tx, 0, &funding_script, FUNDING_AMOUNT, &us.funding_key);
+    assert_eq!(
+        peer.validate_commitment_signature(&tx, 0, &funding_script, FUNDING_AMOUNT, &our_signature, &our_funding_pubkey),
+        Ok(())
+    );
+
+    // The peer's own signature is not ours
+    let peer_signature = peer.sign_holder_commitment(&tx, 0, &funding_script, FUNDING_AMOUNT);
+    assert!(peer
+        .validate_commitment_signature(&tx, 0, &funding_script, FUNDING_AMOUNT, &peer_signature, &our_funding_pubkey)
+        .is_err());
+
+    // Nor is a signature over a different amount
+    assert!(peer
+        .validate_commitment_signature(&tx, 0, &funding_script, FUNDING_AMOUNT + 1, &our_signature, &our_funding_pubkey)
+        .is_err());
+}
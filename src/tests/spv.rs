@@ -0,0 +1,76 @@
+use crate::internal::spv::{verify_merkle_proof, InclusionError};
+use bitcoin::block::{Header, Version};
+use bitcoin::hashes::Hash;
+use bitcoin::merkle_tree::{self, MerkleBlock};
+use bitcoin::{BlockHash, CompactTarget, TxMerkleNode, Txid};
+
+fn test_txids() -> Vec<Txid> {
+    (0u8..5).map(|i| Txid::from_byte_array([i + 1; 32])).collect()
+}
+
+// A header whose merkle root commits to `txids`; PoW is not checked by the verifier
+fn header_for(txids: &[Txid]) -> Header {
+    let root = merkle_tree::calculate_root(txids.iter().map(|txid| txid.to_raw_hash()))
+        .map(TxMerkleNode::from_raw_hash)
+        .unwrap();
+    Header {
+        version: Version::TWO,
+        prev_blockhash: BlockHash::all_zeros(),
+        merkle_root: root,
+        time: 1_700_000_000,
+        bits: CompactTarget::from_consensus(0x207fffff),
+        nonce: 0,
+    }
+}
+
+fn proof_for(txids: &[Txid], target: Txid) -> MerkleBlock {
+    MerkleBlock::from_header_txids_with_predicate(&header_for(txids), txids, |txid| {
+        *txid == target
+    })
+}
+
+#[test]
+fn test_included_tx_verifies() {
+    let txids = test_txids();
+    let proof = proof_for(&txids, txids[3]);
+    let block_hash = proof.header.block_hash();
+
+    assert_eq!(verify_merkle_proof(&txids[3], &proof, &block_hash), Ok(3));
+}
+
+#[test]
+fn test_tx_not_in_proof() {
+    let txids = test_txids();
+    let proof = proof_for(&txids, txids[3]);
+    let block_hash = proof.header.block_hash();
+
+    assert_eq!(
+        verify_merkle_proof(&txids[1], &proof, &block_hash),
+        Err(InclusionError::TxNotInProof(txids[1]))
+    );
+}
+
+#[test]
+fn test_proof_for_wrong_block() {
+    let txids = test_txids();
+    let proof = proof_for(&txids, txids[0]);
+    let expected = BlockHash::from_byte_array([0xaa; 32]);
+
+    assert!(matches!(
+        verify_merkle_proof(&txids[0], &proof, &expected),
+        Err(InclusionError::WrongBlock { .. })
+    ));
+}
+
+#[test]
+fn test_tampered_merkle_root() {
+    let txids = test_txids();
+    let mut proof = proof_for(&txids, txids[2]);
+    proof.header.merkle_root = TxMerkleNode::from_byte_array([0xbb; 32]);
+    let block_hash = proof.header.block_hash();
+
+    assert!(matches!(
+        verify_merkle_proof(&txids[2], &proof, &block_hash),
+        Err(InclusionError::InvalidProof(_))
+    ));
+}
@@ -0,0 +1,346 @@
+use crate::internal::simulated_peer::SimulatedPeer;
+use crate::transactions::claim::ClaimError;
+use crate::transactions::justice::RevokedHtlcOutput;
+use crate::transactions::sweep::{
+    create_batched_sweep_transaction, create_second_stage_claim_transaction,
+    create_to_local_claim_transaction, create_to_remote_claim_transaction, ClaimableOutput,
+};
+use crate::transactions::weights::{
+    DELAYED_OUTPUT_INPUT_WEIGHT, DELAYED_OUTPUT_SWEEP_WEIGHT, OFFERED_HTLC_PENALTY_INPUT_WEIGHT,
+    REVOKED_TO_LOCAL_INPUT_WEIGHT, SWEEP_TX_BASE_WEIGHT, TO_REMOTE_INPUT_WEIGHT,
+    TO_REMOTE_SWEEP_WEIGHT,
+};
+use crate::types::{ChannelKeyManager, CommitmentKeys};
+use crate::*;
+use bitcoin::hashes::Hash;
+use bitcoin::script::ScriptBuf;
+use bitcoin::secp256k1::ecdsa::Signature;
+use bitcoin::secp256k1::Message;
+use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, Txid};
+
+const TO_LOCAL_VALUE: u64 = 3_000_000;
+const TO_SELF_DELAY: u16 = 144;
+const FEERATE_PER_KW: u64 = 2_000;
+const COMMITMENT_NUMBER: u64 = 3;
+
+fn local_keys() -> ChannelKeyManager {
+    new_keys_manager([0x01; 32], Network::Bitcoin).derive_channel_keys(0)
+}
+
+// Keys for our commitment `COMMITMENT_NUMBER` against the simulated peer
+fn holder_commitment_keys(keys: &ChannelKeyManager) -> CommitmentKeys {
+    let local = keys.to_public_keys();
+    let remote = SimulatedPeer::new().public_keys();
+    CommitmentKeys::from_basepoints(
+        &keys.derive_per_commitment_point(COMMITMENT_NUMBER),
+        &local.delayed_payment_basepoint,
+        &local.htlc_basepoint,
+        &remote.revocation_basepoint,
+        &remote.htlc_basepoint,
+        &keys.secp_ctx,
+    )
+}
+
+fn to_local_script(commitment_keys: &CommitmentKeys) -> ScriptBuf {
+    create_to_local_script(
+        &commitment_keys.revocation_key,
+        &commitment_keys.local_delayed_payment_key,
+        TO_SELF_DELAY,
+    )
+}
+
+fn input_signed_by(
+    tx: &Transaction,
+    input_index: usize,
+    script: &ScriptBuf,
+    amount: u64,
+    pubkey: &PublicKey,
+) -> bool {
+    let sighash = SighashCache::new(tx)
+        .p2wsh_signature_hash(input_index, script, Amount::from_sat(amount), EcdsaSighashType::All)
+        .unwrap();
+    let msg = Message::from_digest(sighash.to_byte_array());
+    let signature = tx.input[input_index].witness.nth(0).unwrap();
+    let signature = Signature::from_der(&signature[..signature.len() - 1]).unwrap();
+    Secp256k1::new().verify_ecdsa(&msg, &signature, pubkey).is_ok()
+}
+
+#[test]
+fn test_to_local_claim_waits_for_to_self_delay() {
+    let keys = local_keys();
+    let commitment_keys = holder_commitment_keys(&keys);
+    let script = to_local_script(&commitment_keys);
+    let tx = create_to_local_claim_transaction(
+        OutPoint::new(Txid::all_zeros(), 1),
+        TO_LOCAL_VALUE,
+        &script,
+        &keys.derive_per_commitment_point(COMMITMENT_NUMBER),
+        TO_SELF_DELAY,
+        &keys,
+        &create_to_remote_script(&keys.to_public_keys().payment_basepoint),
+        FEERATE_PER_KW,
+    )
+    .unwrap();
+
+    assert_eq!(tx.input[0].sequence, Sequence::from_height(TO_SELF_DELAY));
+
+    // <local_delayedsig> <> <to_local_script>
+    let witness = &tx.input[0].witness;
+    assert_eq!(witness.len(), 3);
+    assert_eq!(witness.nth(1), Some(&[][..]));
+    assert_eq!(witness.nth(2), Some(script.as_bytes()));
+    assert!(input_signed_by(&tx, 0, &script, TO_LOCAL_VALUE, &commitment_keys.local_delayed_payment_key));
+
+    let fee = FEERATE_PER_KW * DELAYED_OUTPUT_SWEEP_WEIGHT / 1000;
+    assert_eq!(tx.output[0].value.to_sat(), TO_LOCAL_VALUE - fee);
+    assert!(tx.weight().to_wu() <= DELAYED_OUTPUT_SWEEP_WEIGHT);
+}
+
+#[test]
+fn test_to_local_claim_refuses_output_below_fee() {
+    let keys = local_keys();
+    let commitment_keys = holder_commitment_keys(&keys);
+    let destination = create_to_remote_script(&keys.to_public_keys().payment_basepoint);
+    let fee = FEERATE_PER_KW * DELAYED_OUTPUT_SWEEP_WEIGHT / 1000;
+
+    let result = create_to_local_claim_transaction(
+        OutPoint::new(Txid::all_zeros(), 1),
+        fee,
+        &to_local_script(&commitment_keys),
+        &keys.derive_per_commitment_point(COMMITMENT_NUMBER),
+        TO_SELF_DELAY,
+        &keys,
+        &destination,
+        FEERATE_PER_KW,
+    );
+    assert_eq!(
+        result,
+        Err(ClaimError::InsufficientFunds {
+            input_sat: fee,
+            required_sat: fee + destination.minimal_non_dust().to_sat(),
+        })
+    );
+}
+
+#[test]
+fn test_to_remote_claim_spends_p2wpkh() {
+    let keys = local_keys();
+    let payment_basepoint = keys.to_public_keys().payment_basepoint;
+    let to_remote_script = create_to_remote_script(&payment_basepoint);
+    let tx = create_to_remote_claim_transaction(
+        OutPoint::new(Txid::all_zeros(), 0),
+        TO_LOCAL_VALUE,
+        &keys,
+        &to_remote_script,
+        FEERATE_PER_KW,
+    )
+    .unwrap();
+
+    // <sig> <pubkey>, with no delay
+    let witness = &tx.input[0].witness;
+    assert_eq!(witness.len(), 2);
+    assert_eq!(witness.nth(1), Some(&payment_basepoint.serialize()[..]));
+    assert_eq!(tx.input[0].sequence, Sequence::MAX);
+
+    // The signature commits to the output actually being spent
+    let sighash = SighashCache::new(&tx)
+        .p2wpkh_signature_hash(0, &to_remote_script, Amount::from_sat(TO_LOCAL_VALUE), EcdsaSighashType::All)
+        .unwrap();
+    let signature = witness.nth(0).unwrap();
+    let signature = Signature::from_der(&signature[..signature.len() - 1]).unwrap();
+    assert!(Secp256k1::new()
+        .verify_ecdsa(&Message::from_digest(sighash.to_byte_array()), &signature, &payment_basepoint)
+        .is_ok());
+
+    let fee = FEERATE_PER_KW * TO_REMOTE_SWEEP_WEIGHT / 1000;
+    assert_eq!(tx.output[0].value.to_sat(), TO_LOCAL_VALUE - fee);
+    assert!(tx.weight().to_wu() <= TO_REMOTE_SWEEP_WEIGHT);
+}
+
+#[test]
+fn test_to_remote_claim_refuses_output_below_fee() {
+    let keys = local_keys();
+    let to_remote_script = create_to_remote_script(&keys.to_public_keys().payment_basepoint);
+    let fee = FEERATE_PER_KW * TO_REMOTE_SWEEP_WEIGHT / 1000;
+
+    let result = create_to_remote_claim_transaction(
+        OutPoint::new(Txid::all_zeros(), 0),
+        fee + 1,
+        &keys,
+        &to_remote_script,
+        FEERATE_PER_KW,
+    );
+    assert_eq!(
+        result,
+        Err(ClaimError::InsufficientFunds {
+            input_sat: fee + 1,
+            required_sat: fee + to_remote_script.minimal_non_dust().to_sat(),
+        })
+    );
+}
+
+#[test]
+fn test_second_stage_claim_spends_htlc_tx_output() {
+    let keys = local_keys();
+    let commitment_keys = holder_commitment_keys(&keys);
+    let htlc_tx = create_htlc_timeout_transaction(
+        OutPoint::new(Txid::all_zeros(), 2),
+        50_000,
+        500,
+        &commitment_keys,
+        TO_SELF_DELAY,
+        FEERATE_PER_KW,
+    );
+    let tx = create_second_stage_claim_transaction(
+        &htlc_tx,
+        &commitment_keys,
+        TO_SELF_DELAY,
+        &keys,
+        &create_to_remote_script(&keys.to_public_keys().payment_basepoint),
+        FEERATE_PER_KW,
+    )
+    .unwrap();
+
+    assert_eq!(tx.input[0].previous_output, OutPoint::new(htlc_tx.compute_txid(), 0));
+    assert_eq!(tx.input[0].sequence, Sequence::from_height(TO_SELF_DELAY));
+
+    // The witness script is the one the HTLC transaction pays to
+    let script = ScriptBuf::from_bytes(tx.input[0].witness.nth(2).unwrap().to_vec());
+    assert_eq!(script.to_p2wsh(), htlc_tx.output[0].script_pubkey);
+    let htlc_value = htlc_tx.output[0].value.to_sat();
+    assert!(input_signed_by(&tx, 0, &script, htlc_value, &commitment_keys.local_delayed_payment_key));
+}
+
+// One claimable output of each kind: our delayed to_local and to_remote,
+// and the to_local and an offered HTLC of the peer's revoked commitment 7
+fn claimable_outputs(keys: &ChannelKeyManager) -> (Vec<ClaimableOutput>, PublicKey) {
+    let peer = SimulatedPeer::new();
+    let revoked_point = peer.per_commitment_point(7);
+    let per_commitment_secret = SecretKey::from_slice(&peer.revoke(7)).unwrap();
+    let revocation_key = derive_revocation_public_key(
+        &keys.to_public_keys().revocation_basepoint,
+        &revoked_point,
+        &keys.secp_ctx,
+    );
+    let peer_delayed_key =
+        derive_public_key(&peer.public_keys().delayed_payment_basepoint, &revoked_point, &keys.secp_ctx);
+    let peer_htlc_key = derive_public_key(&peer.public_keys().htlc_basepoint, &revoked_point, &keys.secp_ctx);
+    let our_htlc_key =
+        derive_public_key(&keys.to_public_keys().htlc_basepoint, &revoked_point, &keys.secp_ctx);
+
+    let outputs = vec![
+        ClaimableOutput::Delayed {
+            outpoint: OutPoint::new(Txid::all_zeros(), 0),
+            value_sat: TO_LOCAL_VALUE,
+            to_local_script: to_local_script(&holder_commitment_keys(keys)),
+            per_commitment_point: keys.derive_per_commitment_point(COMMITMENT_NUMBER),
+            to_self_delay: TO_SELF_DELAY,
+        },
+        ClaimableOutput::ToRemote {
+            outpoint: OutPoint::new(Txid::all_zeros(), 1),
+            value_sat: 1_000_000,
+        },
+        ClaimableOutput::RevokedToLocal {
+            outpoint: OutPoint::new(Txid::all_zeros(), 2),
+            value_sat: 2_000_000,
+            to_local_script: create_to_local_script(&revocation_key, &peer_delayed_key, TO_SELF_DELAY),
+            per_commitment_secret,
+        },
+        ClaimableOutput::RevokedHtlc {
+            htlc: RevokedHtlcOutput {
+                outpoint: OutPoint::new(Txid::all_zeros(), 3),
+                amount_sat: 20_000,
+                htlc_script: create_offered_htlc_script(
+                    &revocation_key,
+                    &peer_htlc_key,
+                    &our_htlc_key,
+                    &PaymentHash::from_hash_bytes([0x01; 32]),
+                ),
+                offered: true,
+            },
+            per_commitment_secret,
+        },
+    ];
+    (outputs, revocation_key)
+}
+
+#[test]
+fn test_batched_sweep_signs_each_input_for_its_output() {
+    let keys = local_keys();
+    let (outputs, revocation_key) = claimable_outputs(&keys);
+    let tx = create_batched_sweep_transaction(
+        &outputs,
+        &keys,
+        &create_to_remote_script(&keys.to_public_keys().payment_basepoint),
+        FEERATE_PER_KW,
+    )
+    .unwrap();
+
+    assert_eq!(tx.input.len(), 4);
+    assert_eq!(tx.output.len(), 1);
+    for (input, output) in tx.input.iter().zip(&outputs) {
+        assert_eq!(input.previous_output, output.outpoint());
+    }
+
+    // Only the delayed output waits for to_self_delay
+    assert_eq!(tx.input[0].sequence, Sequence::from_height(TO_SELF_DELAY));
+    assert!(tx.input[1..].iter().all(|input| input.sequence == Sequence::MAX));
+
+    let commitment_keys = holder_commitment_keys(&keys);
+    let ClaimableOutput::Delayed { to_local_script, .. } = &outputs[0] else { unreachable!() };
+    assert_eq!(tx.input[0].witness.nth(1), Some(&[][..]));
+    assert!(input_signed_by(&tx, 0, to_local_script, TO_LOCAL_VALUE, &commitment_keys.local_delayed_payment_key));
+
+    let payment_basepoint = keys.to_public_keys().payment_basepoint;
+    assert_eq!(tx.input[1].witness.len(), 2);
+    assert_eq!(tx.input[1].witness.nth(1), Some(&payment_basepoint.serialize()[..]));
+
+    let ClaimableOutput::RevokedToLocal { to_local_script, .. } = &outputs[2] else { unreachable!() };
+    assert_eq!(tx.input[2].witness.nth(1), Some(&[0x01][..]));
+    assert!(input_signed_by(&tx, 2, to_local_script, 2_000_000, &revocation_key));
+
+    let ClaimableOutput::RevokedHtlc { htlc, .. } = &outputs[3] else { unreachable!() };
+    assert_eq!(tx.input[3].witness.nth(1), Some(&revocation_key.serialize()[..]));
+    assert!(input_signed_by(&tx, 3, &htlc.htlc_script, htlc.amount_sat, &revocation_key));
+}
+
+#[test]
+fn test_batched_sweep_pays_fee_for_every_input() {
+    let keys = local_keys();
+    let (outputs, _) = claimable_outputs(&keys);
+    let tx = create_batched_sweep_transaction(
+        &outputs,
+        &keys,
+        &create_to_remote_script(&keys.to_public_keys().payment_basepoint),
+        FEERATE_PER_KW,
+    )
+    .unwrap();
+
+    let weight = SWEEP_TX_BASE_WEIGHT
+        + DELAYED_OUTPUT_INPUT_WEIGHT
+        + TO_REMOTE_INPUT_WEIGHT
+        + REVOKED_TO_LOCAL_INPUT_WEIGHT
+        + OFFERED_HTLC_PENALTY_INPUT_WEIGHT;
+    let total: u64 = outputs.iter().map(ClaimableOutput::value_sat).sum();
+    assert_eq!(tx.output[0].value.to_sat(), total - FEERATE_PER_KW * weight / 1000);
+    assert!(tx.weight().to_wu() <= weight);
+
+    // A batch of one costs the same as the single-output claim
+    assert_eq!(SWEEP_TX_BASE_WEIGHT + DELAYED_OUTPUT_INPUT_WEIGHT, DELAYED_OUTPUT_SWEEP_WEIGHT);
+    assert_eq!(SWEEP_TX_BASE_WEIGHT + TO_REMOTE_INPUT_WEIGHT, TO_REMOTE_SWEEP_WEIGHT);
+}
+
+#[test]
+fn test_batched_sweep_needs_outputs_and_funds() {
+    let keys = local_keys();
+    let destination = create_to_remote_script(&keys.to_public_keys().payment_basepoint);
+
+    let result = create_batched_sweep_transaction(&[], &keys, &destination, FEERATE_PER_KW);
+    assert_eq!(result, Err(ClaimError::NoOutputs));
+
+    // Alone, the offered HTLC is worth less than its fee at this feerate
+    let (outputs, _) = claimable_outputs(&keys);
+    let result = create_batched_sweep_transaction(&outputs[3..], &keys, &destination, 50_000);
+    assert!(matches!(result, Err(ClaimError::InsufficientFunds { input_sat: 20_000, .. })));
+}
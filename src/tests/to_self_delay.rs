@@ -0,0 +1,36 @@
+use crate::transactions::commitment::{validate_to_self_delay, ToSelfDelayError, ToSelfDelays};
+
+const MAX_TO_SELF_DELAY: u16 = 2016;
+
+#[test]
+fn test_delays_swap_sides() {
+    // We ask them to wait 144 blocks, they ask us to wait 720
+    assert_eq!(
+        validate_to_self_delay(144, 720, MAX_TO_SELF_DELAY),
+        Ok(ToSelfDelays { local: 720, remote: 144 })
+    );
+}
+
+#[test]
+fn test_their_delay_at_limit_accepted() {
+    assert_eq!(
+        validate_to_self_delay(144, MAX_TO_SELF_DELAY, MAX_TO_SELF_DELAY),
+        Ok(ToSelfDelays { local: MAX_TO_SELF_DELAY, remote: 144 })
+    );
+}
+
+#[test]
+fn test_their_delay_over_limit_rejected() {
+    assert_eq!(
+        validate_to_self_delay(144, MAX_TO_SELF_DELAY + 1, MAX_TO_SELF_DELAY),
+        Err(ToSelfDelayError::TooLarge {
+            to_self_delay: MAX_TO_SELF_DELAY + 1,
+            max_to_self_delay: MAX_TO_SELF_DELAY,
+        })
+    );
+}
+
+#[test]
+fn test_zero_delay_for_counterparty_rejected() {
+    assert_eq!(validate_to_self_delay(0, 144, MAX_TO_SELF_DELAY), Err(ToSelfDelayError::Zero));
+}
@@ -0,0 +1,90 @@
+use crate::interactive::tx_info::{
+    csv_delays, decode_commitment_number, describe_witness, transaction_fee,
+};
+use crate::*;
+use crate::tests::fixtures::test_pubkey;
+use bitcoin::consensus::encode;
+use bitcoin::hashes::Hash;
+use bitcoin::locktime::absolute::LockTime;
+use bitcoin::script::ScriptBuf;
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+
+fn unsigned_tx() -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut { value: Amount::from_sat(1000), script_pubkey: ScriptBuf::new() }],
+    }
+}
+
+fn bolt3_htlc_tx(index: usize) -> Transaction {
+    let vectors: serde_json::Value =
+        serde_json::from_str(include_str!("data/bolt3_htlc_txs_min_feerate.json")).unwrap();
+    let tx_hex = vectors["htlc_txs"][index]["expected_tx_hex"].as_str().unwrap();
+    encode::deserialize(&hex::decode(tx_hex).unwrap()).unwrap()
+}
+
+#[test]
+fn test_decode_commitment_number_round_trip() {
+    let (initiator, receiver) = (test_pubkey(1), test_pubkey(2));
+    let mut tx = unsigned_tx();
+    set_obscured_commitment_number(&mut tx, 42, &initiator, &receiver);
+
+    assert_eq!(decode_commitment_number(&tx, &initiator, &receiver), Some(42));
+}
+
+#[test]
+fn test_decode_commitment_number_requires_prefixes() {
+    let tx = unsigned_tx();
+
+    assert_eq!(decode_commitment_number(&tx, &test_pubkey(1), &test_pubkey(2)), None);
+}
+
+#[test]
+fn test_describe_htlc_witnesses() {
+    // The vectors file lists htlc-success #0 first and htlc-timeout #2 second
+    assert_eq!(
+        describe_witness(&bolt3_htlc_tx(0).input[0].witness),
+        "HTLC-success: received HTLC claimed with the preimage"
+    );
+    assert_eq!(
+        describe_witness(&bolt3_htlc_tx(1).input[0].witness),
+        "HTLC-timeout: offered HTLC reclaimed after cltv_expiry"
+    );
+}
+
+#[test]
+fn test_describe_to_local_witnesses() {
+    let script = create_to_local_script(&test_pubkey(1), &test_pubkey(2), 144);
+    let sig = [0x30; 72];
+
+    let revocation = Witness::from_slice(&[&sig[..], &[0x01], script.as_bytes()]);
+    let delayed = Witness::from_slice(&[&sig[..], &[], script.as_bytes()]);
+
+    assert_eq!(describe_witness(&revocation), "to_local revocation (penalty)");
+    assert_eq!(describe_witness(&delayed), "to_local after to_self_delay");
+    assert_eq!(describe_witness(&Witness::new()), "no witness (legacy input)");
+}
+
+#[test]
+fn test_csv_delays() {
+    let to_local = create_to_local_script(&test_pubkey(1), &test_pubkey(2), 144);
+    let htlc = create_offered_htlc_script(&test_pubkey(1), &test_pubkey(2), &test_pubkey(3), &PaymentHash::from_hash_bytes([0; 32]));
+
+    assert_eq!(csv_delays(&to_local), vec![144]);
+    assert!(csv_delays(&htlc).is_empty());
+}
+
+#[test]
+fn test_transaction_fee_rejects_negative() {
+    assert_eq!(transaction_fee(10_000, 9_000), Some(1_000));
+    assert_eq!(transaction_fee(10_000, 10_000), Some(0));
+    assert_eq!(transaction_fee(9_000, 10_000), None);
+}
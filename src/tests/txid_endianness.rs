@@ -0,0 +1,76 @@
+// Txids are displayed byte-reversed, and hand-rolled reversal is the classic
+// source of "transaction not found" bugs. Everything outside the tests must
+// go through Txid::from_str / parse_txid, which handle the byte order, rather
+// than reversing bytes and feeding them to Txid::from_*.
+use std::fs;
+use std::path::Path;
+
+fn rust_sources(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            rust_sources(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+// How far after a `.reverse()` to look for the bytes being turned into a txid
+const LOOKAHEAD_LINES: usize = 5;
+
+// The variable a `.reverse()` on this line is called on, e.g. `bytes` for
+// `bytes.reverse();`
+fn reversed_variable(line: &str) -> Option<&str> {
+    let receiver = &line[..line.find(".reverse()")?];
+    let start = receiver
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map_or(0, |i| i + 1);
+    Some(&receiver[start..]).filter(|name| !name.is_empty())
+}
+
+// Whether `line` builds a txid from raw bytes in `variable`
+fn builds_txid_from(line: &str, variable: &str) -> bool {
+    (line.contains("Txid::from_") || line.contains("from_byte_array")) && line.contains(variable)
+}
+
+#[test]
+fn test_no_manual_txid_byte_reversal() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut files = Vec::new();
+    for dir in ["internal", "interactive", "exercises", "solutions"] {
+        rust_sources(&src.join(dir), &mut files);
+    }
+    assert!(!files.is_empty());
+
+    let mut offenders = Vec::new();
+    for file in &files {
+        let source = fs::read_to_string(file).unwrap();
+        let lines: Vec<&str> = source.lines().collect();
+        for (line_number, line) in lines.iter().enumerate() {
+            let Some(variable) = reversed_variable(line) else { continue };
+            let window = &lines[line_number..lines.len().min(line_number + 1 + LOOKAHEAD_LINES)];
+            if window.iter().any(|later| builds_txid_from(later, variable)) {
+                offenders.push(format!("{}:{}: {}", file.display(), line_number + 1, line.trim()));
+            }
+        }
+    }
+
+    assert!(
+        offenders.is_empty(),
+        "Parse txids with Txid::from_str (or hex_utils::parse_txid) instead of reversing bytes:\n{}",
+        offenders.join("\n")
+    );
+}
+
+#[test]
+fn test_only_reversal_into_a_txid_is_flagged() {
+    assert_eq!(reversed_variable("    bytes.reverse();"), Some("bytes"));
+    assert_eq!(reversed_variable("    let x = 1;"), None);
+
+    assert!(builds_txid_from("let txid = Txid::from_slice(&bytes).unwrap();", "bytes"));
+    assert!(builds_txid_from("Txid::from_byte_array(bytes)", "bytes"));
+    // Reversing for any other reason is fine
+    assert!(!builds_txid_from("outputs.sort();", "outputs"));
+    assert!(!builds_txid_from("let txid = Txid::from_str(&hex)?;", "bytes"));
+}
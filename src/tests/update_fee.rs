@@ -0,0 +1,127 @@
+use crate::transactions::fees::{validate_update_fee, UpdateFeeError, FEERATE_FLOOR_PER_KW};
+
+const MIN_FEERATE: u64 = 1_000;
+const MAX_FEERATE: u64 = 10_000;
+const FUNDER_BALANCE: u64 = 100_000;
+const RESERVE: u64 = 10_000;
+
+#[test]
+fn test_update_fee_accepts_window_bounds() {
+    // 10_000 sat/kw * 724 / 1000 = 7_240 sat, well within the funder's headroom
+    assert_eq!(
+        validate_update_fee(
+            MIN_FEERATE,
+            MIN_FEERATE,
+            MAX_FEERATE,
+            FUNDER_BALANCE,
+            RESERVE,
+            0
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        validate_update_fee(
+            MAX_FEERATE,
+            MIN_FEERATE,
+            MAX_FEERATE,
+            FUNDER_BALANCE,
+            RESERVE,
+            0
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_update_fee_rejects_outside_window() {
+    assert_eq!(
+        validate_update_fee(
+            MIN_FEERATE - 1,
+            MIN_FEERATE,
+            MAX_FEERATE,
+            FUNDER_BALANCE,
+            RESERVE,
+            0
+        ),
+        Err(UpdateFeeError::TooLow {
+            feerate_per_kw: MIN_FEERATE - 1,
+            min_feerate_per_kw: MIN_FEERATE
+        })
+    );
+    assert_eq!(
+        validate_update_fee(
+            MAX_FEERATE + 1,
+            MIN_FEERATE,
+            MAX_FEERATE,
+            FUNDER_BALANCE,
+            RESERVE,
+            0
+        ),
+        Err(UpdateFeeError::TooHigh {
+            feerate_per_kw: MAX_FEERATE + 1,
+            max_feerate_per_kw: MAX_FEERATE
+        })
+    );
+}
+
+#[test]
+fn test_update_fee_enforces_feerate_floor() {
+    // An estimator allowing 0 sat/kw still cannot go under the BOLT 2 floor
+    assert!(matches!(
+        validate_update_fee(
+            FEERATE_FLOOR_PER_KW - 1,
+            0,
+            MAX_FEERATE,
+            FUNDER_BALANCE,
+            RESERVE,
+            0
+        ),
+        Err(UpdateFeeError::TooLow {
+            min_feerate_per_kw: FEERATE_FLOOR_PER_KW,
+            ..
+        })
+    ));
+    assert_eq!(
+        validate_update_fee(
+            FEERATE_FLOOR_PER_KW,
+            0,
+            MAX_FEERATE,
+            FUNDER_BALANCE,
+            RESERVE,
+            0
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_update_fee_rejects_fee_eating_reserve() {
+    // With 20 HTLCs the weight is 724 + 172 * 20 = 4_164, so 10_000 sat/kw costs 41_640 sat
+    let funder_balance = 41_640 + RESERVE;
+    assert_eq!(
+        validate_update_fee(
+            MAX_FEERATE,
+            MIN_FEERATE,
+            MAX_FEERATE,
+            funder_balance,
+            RESERVE,
+            20
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        validate_update_fee(
+            MAX_FEERATE,
+            MIN_FEERATE,
+            MAX_FEERATE,
+            funder_balance - 1,
+            RESERVE,
+            20
+        ),
+        Err(UpdateFeeError::CannotAffordFee {
+            fee_sat: 41_640,
+            funder_balance_sat: funder_balance - 1,
+            channel_reserve_sat: RESERVE,
+        })
+    );
+}
@@ -0,0 +1,29 @@
+use crate::transactions::fees::{
+    calculate_commitment_tx_fee, calculate_htlc_success_tx_fee, calculate_htlc_timeout_tx_fee,
+};
+use crate::transactions::weights::*;
+use crate::types::ChannelType;
+
+#[test]
+fn test_bolt3_weights() {
+    let legacy = ChannelType::StaticRemoteKey;
+    let anchors = ChannelType::AnchorsZeroFeeHtlcTx;
+
+    assert_eq!(commitment_base_weight(legacy), 724);
+    assert_eq!(commitment_base_weight(anchors), 1124);
+    assert_eq!(htlc_output_weight(), 172);
+    assert_eq!(htlc_timeout_weight(legacy), 663);
+    assert_eq!(htlc_timeout_weight(anchors), 666);
+    assert_eq!(htlc_success_weight(legacy), 703);
+    assert_eq!(htlc_success_weight(anchors), 706);
+    assert_eq!(commitment_tx_weight(legacy, 5), 724 + 5 * 172);
+    assert_eq!(commitment_tx_weight(anchors, 0), 1124);
+}
+
+#[test]
+fn test_fees_use_static_remotekey_weights() {
+    // BOLT 3 Appendix C: 5 untrimmed HTLCs at 647 sat/kw
+    assert_eq!(calculate_commitment_tx_fee(647, 5), 1024);
+    assert_eq!(calculate_htlc_timeout_tx_fee(1000), 663);
+    assert_eq!(calculate_htlc_success_tx_fee(1000), 703);
+}
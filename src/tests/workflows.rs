@@ -3,16 +3,18 @@ use bitcoin::{OutPoint, Transaction};
 use hex;
 
 use crate::transactions::commitment::{
-    create_commitment_transaction, set_obscured_commitment_number,
+    create_commitment_transaction_with_funder, set_obscured_commitment_number,
 };
-use crate::transactions::fees::is_htlc_dust;
+use crate::transactions::fees::is_htlc_dust_for_channel_type;
 use crate::types::{
-    Bolt3Htlc, Bolt3TestVector, ChannelKeyManager, CommitmentKeys, HTLCOutput, HtlcDirection,
+    Bolt3Htlc, Bolt3TestVector, ChannelKeyManager, ChannelType, CommitmentKeys, HTLCOutput,
+    HtlcDirection,
 };
 use bitcoin::hashes::sha256::Hash as Sha256;
 use bitcoin::hashes::{sha256, Hash};
 
 
+#[allow(clippy::too_many_arguments)]
 pub fn build_complete_commitment_transaction(
     funding_outpoint: OutPoint,
     commitment_keys: &CommitmentKeys, 
@@ -26,26 +28,38 @@ pub fn build_complete_commitment_transaction(
     to_self_delay: u16,
     dust_limit_satoshis: u64,
     feerate_per_kw: u64,
+    local_is_funder: bool,
 ) -> Transaction {
     // Convert msat to sat
     let to_local_value = to_local_value_msat / 1000;
     let to_remote_value = to_remote_value_msat / 1000;
 
-    // Trim dust HTLCs
+    // Trim dust HTLCs, by the rules of the static_remotekey format the
+    // exercises build
+    let is_dust = |htlc: &HTLCOutput, outbound_htlc: bool| {
+        is_htlc_dust_for_channel_type(
+            htlc.amount_sat,
+            dust_limit_satoshis,
+            feerate_per_kw,
+            outbound_htlc,
+            ChannelType::StaticRemoteKey,
+        )
+    };
     let offered_trimmed: Vec<_> = offered_htlcs
         .iter()
-        .filter(|htlc| !is_htlc_dust(htlc.amount_sat, dust_limit_satoshis, feerate_per_kw, true))
+        .filter(|htlc| !is_dust(htlc, true))
         .cloned()
         .collect();
 
     let received_trimmed: Vec<_> = received_htlcs
         .iter()
-        .filter(|htlc| !is_htlc_dust(htlc.amount_sat, dust_limit_satoshis, feerate_per_kw, false))
+        .filter(|htlc| !is_dust(htlc, false))
         .cloned()
         .collect();
 
-    // create commitment transaction using exercise students completed
-    let tx = create_commitment_transaction(
+    // create commitment transaction using exercise students completed,
+    // taking the fee from whichever side opened the channel
+    let tx = create_commitment_transaction_with_funder(
         funding_outpoint,
         to_local_value,
         to_remote_value,
@@ -58,6 +72,7 @@ pub fn build_complete_commitment_transaction(
         feerate_per_kw,
         &offered_trimmed,
         &received_trimmed,
+        local_is_funder,
     );
 
     tx
@@ -125,6 +140,7 @@ pub fn build_bolt3_simple_commitment(test_vector: &Bolt3TestVector) -> Transacti
         test_vector.local_delay,
         test_vector.local_dust_limit_satoshi,
         test_vector.feerate_per_kw,
+        true, // The BOLT 3 vectors are from the funder's side
     )
 }
 
@@ -194,5 +210,6 @@ pub fn build_bolt3_commitment_with_htlcs(
         test_vector.local_delay,
         test_vector.local_dust_limit_satoshi,
         test_vector.feerate_per_kw,
+        true, // The BOLT 3 vectors are from the funder's side
     )
 }
\ No newline at end of file